@@ -19,8 +19,10 @@ fn main() {
         println!("cargo:rustc-check-cfg=cfg(no_core_net)");
         println!("cargo:rustc-check-cfg=cfg(no_core_num_saturating)");
         println!("cargo:rustc-check-cfg=cfg(no_core_try_from)");
+        println!("cargo:rustc-check-cfg=cfg(no_const_generics)");
         println!("cargo:rustc-check-cfg=cfg(no_diagnostic_namespace)");
         println!("cargo:rustc-check-cfg=cfg(no_float_copysign)");
+        println!("cargo:rustc-check-cfg=cfg(no_maybe_uninit)");
         println!("cargo:rustc-check-cfg=cfg(no_num_nonzero_signed)");
         println!("cargo:rustc-check-cfg=cfg(no_relaxed_trait_bounds)");
         println!("cargo:rustc-check-cfg=cfg(no_serde_derive)");
@@ -50,6 +52,18 @@ fn main() {
         println!("cargo:rustc-cfg=no_float_copysign");
     }
 
+    // MaybeUninit<T> stabilized in Rust 1.36.
+    // https://blog.rust-lang.org/2019/07/04/Rust-1.36.0.html#maybeuninitt-instead-of-memuninitialized
+    if minor < 36 {
+        println!("cargo:rustc-cfg=no_maybe_uninit");
+    }
+
+    // Const generics stabilized in Rust 1.51.
+    // https://blog.rust-lang.org/2021/03/25/Rust-1.51.0.html#const-generics-mvp
+    if minor < 51 {
+        println!("cargo:rustc-cfg=no_const_generics");
+    }
+
     // Support for #[cfg(target_has_atomic = "...")] stabilized in Rust 1.60.
     if minor < 60 {
         println!("cargo:rustc-cfg=no_target_has_atomic");
@@ -0,0 +1,47 @@
+//! A format-agnostic interface for whole-value byte serialization.
+//!
+//! Data format crates can implement [`BytesFormat`] for a marker type to
+//! expose their usual `to_vec`/`from_slice` entry points behind a common
+//! trait. The `#[serde(convenience_api)]` derive attribute then generates
+//! inherent `to_bytes::<F>()` and `from_bytes::<F>()` methods on a type,
+//! parameterized by any such format, so application code does not need to
+//! thread a serializer choice through every call site.
+//!
+//! ```edition2021
+//! use serde_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! #[serde(convenience_api)]
+//! struct Config {
+//!     threads: u32,
+//! }
+//!
+//! // Given some `Json: BytesFormat` provided by a format crate:
+//! // let bytes = config.to_bytes::<Json>()?;
+//! // let config = Config::from_bytes::<Json>(&bytes)?;
+//! ```
+
+use crate::lib::*;
+
+use crate::de::Deserialize;
+use crate::ser::Serialize;
+
+/// A data format that can serialize any serde data structure to a byte
+/// vector and deserialize it back from a byte slice.
+pub trait BytesFormat {
+    /// Error returned when serialization fails.
+    type SerializeError;
+
+    /// Error returned when deserialization fails.
+    type DeserializeError;
+
+    /// Serializes `value` to bytes in this format.
+    fn serialize_to_bytes<T>(value: &T) -> Result<Vec<u8>, Self::SerializeError>
+    where
+        T: ?Sized + Serialize;
+
+    /// Deserializes a value of type `T` from bytes in this format.
+    fn deserialize_from_bytes<'de, T>(bytes: &'de [u8]) -> Result<T, Self::DeserializeError>
+    where
+        T: Deserialize<'de>;
+}
@@ -0,0 +1,509 @@
+//! Deserialization with an enforced size budget.
+//!
+//! A malicious or corrupt input can be tiny on the wire yet expand into an
+//! enormous value — a few bytes of header claiming a billion-element
+//! sequence, or a compressed stream that inflates into gigabytes of string
+//! data. Each format can defend against this individually, but the attack is
+//! against the data model, so the defense can live there too:
+//! [`BudgetDeserializer`] wraps any [`Deserializer`] and charges every value
+//! passing through it against a configured budget, failing with a clear
+//! error once the budget is exhausted.
+//!
+//! Every value costs one unit, and strings and byte arrays additionally cost
+//! one unit per byte, so the budget approximates the memory required to hold
+//! the deserialized data regardless of how compactly it was encoded.
+//!
+//! ```edition2021
+//! use serde::de::budget::BudgetDeserializer;
+//! use serde::de::value::{Error, SeqDeserializer};
+//! use serde::Deserialize;
+//!
+//! let huge = SeqDeserializer::<_, Error>::new(0u64..1_000_000_000);
+//! let error = Vec::<u64>::deserialize(BudgetDeserializer::new(huge, 1024)).unwrap_err();
+//! assert_eq!(error.to_string(), "deserialization size budget exceeded");
+//! ```
+
+use crate::lib::*;
+
+use crate::de::{
+    self, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+
+/// A deserializer adapter that fails once the values passing through it
+/// exceed a size budget.
+///
+/// See the [module documentation] for how values are costed.
+///
+/// [module documentation]: self
+pub struct BudgetDeserializer<D> {
+    de: D,
+    budget: Cell<usize>,
+}
+
+impl<D> BudgetDeserializer<D> {
+    /// Wraps a deserializer with a budget of the given number of units.
+    pub fn new(de: D, budget: usize) -> Self {
+        BudgetDeserializer {
+            de,
+            budget: Cell::new(budget),
+        }
+    }
+}
+
+fn charge<E>(budget: &Cell<usize>, cost: usize) -> Result<(), E>
+where
+    E: de::Error,
+{
+    let remaining = budget.get();
+    if cost > remaining {
+        Err(de::Error::custom("deserialization size budget exceeded"))
+    } else {
+        budget.set(remaining - cost);
+        Ok(())
+    }
+}
+
+/// A value being deserialized under a budget shared with the values around
+/// it. Wraps whichever trait the format hands back next: a visitor, an
+/// accessor, or a seed.
+struct Budgeted<'b, T> {
+    inner: T,
+    budget: &'b Cell<usize>,
+}
+
+macro_rules! forward_deserialize {
+    ($($func:ident $(($($arg:ident: $ty:ty),*))*,)*) => {
+        $(
+            fn $func<V>(self $(, $($arg: $ty),*)*, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.de.$func(
+                    $($($arg,)*)*
+                    Budgeted {
+                        inner: visitor,
+                        budget: &self.budget,
+                    },
+                )
+            }
+        )*
+    };
+}
+
+impl<'de, D> Deserializer<'de> for BudgetDeserializer<D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    forward_deserialize! {
+        deserialize_any,
+        deserialize_bool,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_i128,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_u128,
+        deserialize_f32,
+        deserialize_f64,
+        deserialize_char,
+        deserialize_str,
+        deserialize_string,
+        deserialize_bytes,
+        deserialize_byte_buf,
+        deserialize_option,
+        deserialize_unit,
+        deserialize_seq,
+        deserialize_map,
+        deserialize_identifier,
+        deserialize_ignored_any,
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+macro_rules! charge_scalar {
+    ($($func:ident($ty:ty),)*) => {
+        $(
+            fn $func<E>(self, v: $ty) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                tri!(charge(self.budget, 1));
+                self.inner.$func(v)
+            }
+        )*
+    };
+}
+
+impl<'de, 'b, V> Visitor<'de> for Budgeted<'b, V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    charge_scalar! {
+        visit_bool(bool),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+        visit_char(char),
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        tri!(charge(self.budget, 1 + v.len()));
+        self.inner.visit_str(v)
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        tri!(charge(self.budget, 1 + v.len()));
+        self.inner.visit_borrowed_str(v)
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        tri!(charge(self.budget, 1 + v.len()));
+        self.inner.visit_string(v)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        tri!(charge(self.budget, 1 + v.len()));
+        self.inner.visit_bytes(v)
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        tri!(charge(self.budget, 1 + v.len()));
+        self.inner.visit_borrowed_bytes(v)
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        tri!(charge(self.budget, 1 + v.len()));
+        self.inner.visit_byte_buf(v)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        tri!(charge(self.budget, 1));
+        self.inner.visit_none()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        tri!(charge(self.budget, 1));
+        self.inner.visit_some(Budgeted {
+            inner: deserializer,
+            budget: self.budget,
+        })
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        tri!(charge(self.budget, 1));
+        self.inner.visit_unit()
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        tri!(charge(self.budget, 1));
+        self.inner.visit_newtype_struct(Budgeted {
+            inner: deserializer,
+            budget: self.budget,
+        })
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        tri!(charge(self.budget, 1));
+        self.inner.visit_seq(Budgeted {
+            inner: seq,
+            budget: self.budget,
+        })
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        tri!(charge(self.budget, 1));
+        self.inner.visit_map(Budgeted {
+            inner: map,
+            budget: self.budget,
+        })
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        tri!(charge(self.budget, 1));
+        self.inner.visit_enum(Budgeted {
+            inner: data,
+            budget: self.budget,
+        })
+    }
+}
+
+impl<'de, 'b, T> DeserializeSeed<'de> for Budgeted<'b, T>
+where
+    T: DeserializeSeed<'de>,
+{
+    type Value = T::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.inner.deserialize(Budgeted {
+            inner: deserializer,
+            budget: self.budget,
+        })
+    }
+}
+
+impl<'de, 'b, A> SeqAccess<'de> for Budgeted<'b, A>
+where
+    A: SeqAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.inner.next_element_seed(Budgeted {
+            inner: seed,
+            budget: self.budget,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'de, 'b, A> MapAccess<'de> for Budgeted<'b, A>
+where
+    A: MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        self.inner.next_key_seed(Budgeted {
+            inner: seed,
+            budget: self.budget,
+        })
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.inner.next_value_seed(Budgeted {
+            inner: seed,
+            budget: self.budget,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+impl<'de, 'b, A> EnumAccess<'de> for Budgeted<'b, A>
+where
+    A: EnumAccess<'de>,
+{
+    type Error = A::Error;
+    type Variant = Budgeted<'b, A::Variant>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let budget = self.budget;
+        let (value, variant) = tri!(self.inner.variant_seed(Budgeted {
+            inner: seed,
+            budget,
+        }));
+        Ok((
+            value,
+            Budgeted {
+                inner: variant,
+                budget,
+            },
+        ))
+    }
+}
+
+impl<'de, 'b, A> VariantAccess<'de> for Budgeted<'b, A>
+where
+    A: VariantAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.inner.unit_variant()
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.inner.newtype_variant_seed(Budgeted {
+            inner: seed,
+            budget: self.budget,
+        })
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.inner.tuple_variant(
+            len,
+            Budgeted {
+                inner: visitor,
+                budget: self.budget,
+            },
+        )
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.inner.struct_variant(
+            fields,
+            Budgeted {
+                inner: visitor,
+                budget: self.budget,
+            },
+        )
+    }
+}
+
+macro_rules! forward_budgeted_deserialize {
+    ($($func:ident $(($($arg:ident: $ty:ty),*))*,)*) => {
+        $(
+            fn $func<V>(self $(, $($arg: $ty),*)*, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.inner.$func(
+                    $($($arg,)*)*
+                    Budgeted {
+                        inner: visitor,
+                        budget: self.budget,
+                    },
+                )
+            }
+        )*
+    };
+}
+
+impl<'de, 'b, D> Deserializer<'de> for Budgeted<'b, D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    forward_budgeted_deserialize! {
+        deserialize_any,
+        deserialize_bool,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_i128,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_u128,
+        deserialize_f32,
+        deserialize_f64,
+        deserialize_char,
+        deserialize_str,
+        deserialize_string,
+        deserialize_bytes,
+        deserialize_byte_buf,
+        deserialize_option,
+        deserialize_unit,
+        deserialize_seq,
+        deserialize_map,
+        deserialize_identifier,
+        deserialize_ignored_any,
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
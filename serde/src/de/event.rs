@@ -0,0 +1,567 @@
+//! Deserialization from a stream of events produced by a pull parser.
+//!
+//! Formats built around an event or SAX-style pull parser can plug into serde
+//! by translating their parse events into [`Event`] values and handing an
+//! iterator of them to [`from_iter`], instead of implementing the full
+//! [`Deserializer`] trait against their parser directly.
+//!
+//! ```edition2021
+//! use serde::de::event::{from_iter, Event};
+//! use serde::de::value::Error;
+//! use serde::Deserialize;
+//! use serde_derive::Deserialize;
+//!
+//! #[derive(Deserialize, PartialEq, Debug)]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! let events = [
+//!     Event::MapStart(Some(2)),
+//!     Event::Str("x".into()),
+//!     Event::I32(1),
+//!     Event::Str("y".into()),
+//!     Event::I32(2),
+//!     Event::MapEnd,
+//! ];
+//!
+//! let point = Point::deserialize(&mut from_iter::<_, Error>(events)).unwrap();
+//! assert_eq!(point, Point { x: 1, y: 2 });
+//! ```
+//!
+//! [`Deserializer`]: crate::Deserializer
+
+use crate::lib::*;
+
+use crate::de::value::CowStrDeserializer;
+use crate::de::{self, Deserializer, IntoDeserializer, Visitor};
+
+/// One element of the serde data model, as produced by a pull parser.
+///
+/// Strings and byte arrays are carried as [`Cow`] so that parsers which hold
+/// the complete input in memory can pass borrowed data through to the visitor
+/// and keep zero-copy deserialization working.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event<'de> {
+    #[allow(missing_docs)]
+    Bool(bool),
+    #[allow(missing_docs)]
+    I8(i8),
+    #[allow(missing_docs)]
+    I16(i16),
+    #[allow(missing_docs)]
+    I32(i32),
+    #[allow(missing_docs)]
+    I64(i64),
+    #[allow(missing_docs)]
+    U8(u8),
+    #[allow(missing_docs)]
+    U16(u16),
+    #[allow(missing_docs)]
+    U32(u32),
+    #[allow(missing_docs)]
+    U64(u64),
+    #[allow(missing_docs)]
+    F32(f32),
+    #[allow(missing_docs)]
+    F64(f64),
+    #[allow(missing_docs)]
+    Char(char),
+    #[allow(missing_docs)]
+    Str(Cow<'de, str>),
+    #[allow(missing_docs)]
+    Bytes(Cow<'de, [u8]>),
+    /// `None` of an `Option`.
+    None,
+    /// `Some` of an `Option`; followed by the events of the contained value.
+    Some,
+    /// The unit value, or the content of a unit struct or unit variant.
+    Unit,
+    /// Start of a sequence, tuple or tuple struct, with the number of
+    /// elements if the parser knows it up front.
+    SeqStart(Option<usize>),
+    /// End of the innermost open sequence.
+    SeqEnd,
+    /// Start of a map or struct, with the number of entries if the parser
+    /// knows it up front. Keys and values alternate between this event and
+    /// the matching [`MapEnd`](Event::MapEnd).
+    MapStart(Option<usize>),
+    /// End of the innermost open map.
+    MapEnd,
+}
+
+impl<'de> Event<'de> {
+    fn unexpected(&self) -> de::Unexpected<'_> {
+        match self {
+            Event::Bool(v) => de::Unexpected::Bool(*v),
+            Event::I8(v) => de::Unexpected::Signed(i64::from(*v)),
+            Event::I16(v) => de::Unexpected::Signed(i64::from(*v)),
+            Event::I32(v) => de::Unexpected::Signed(i64::from(*v)),
+            Event::I64(v) => de::Unexpected::Signed(*v),
+            Event::U8(v) => de::Unexpected::Unsigned(u64::from(*v)),
+            Event::U16(v) => de::Unexpected::Unsigned(u64::from(*v)),
+            Event::U32(v) => de::Unexpected::Unsigned(u64::from(*v)),
+            Event::U64(v) => de::Unexpected::Unsigned(*v),
+            Event::F32(v) => de::Unexpected::Float(f64::from(*v)),
+            Event::F64(v) => de::Unexpected::Float(*v),
+            Event::Char(v) => de::Unexpected::Char(*v),
+            Event::Str(v) => de::Unexpected::Str(v),
+            Event::Bytes(v) => de::Unexpected::Bytes(v),
+            Event::None | Event::Some => de::Unexpected::Option,
+            Event::Unit => de::Unexpected::Unit,
+            Event::SeqStart(_) => de::Unexpected::Seq,
+            Event::MapStart(_) => de::Unexpected::Map,
+            Event::SeqEnd => de::Unexpected::Other("end of sequence"),
+            Event::MapEnd => de::Unexpected::Other("end of map"),
+        }
+    }
+}
+
+/// Renders a stream of events as approximate, human-oriented JSON.
+///
+/// Intended for assertion failures and debug logging: a mismatch deep in a
+/// nested structure is much easier to locate in `{"outer": {"inner": [1,
+/// 2]}}` than in the flat list of events that produced it. The output is
+/// not valid JSON in general — non-string keys, bytes, and chars are
+/// rendered in the nearest Rust notation — and is not meant to be parsed.
+///
+/// ```edition2021
+/// use serde::de::event::{pseudo_json, Event};
+///
+/// let events = [
+///     Event::MapStart(Some(1)),
+///     Event::Str("point".into()),
+///     Event::SeqStart(Some(2)),
+///     Event::I32(1),
+///     Event::I32(2),
+///     Event::SeqEnd,
+///     Event::MapEnd,
+/// ];
+///
+/// assert_eq!(pseudo_json(&events), r#"{"point": [1, 2]}"#);
+/// ```
+pub fn pseudo_json(events: &[Event]) -> String {
+    enum Frame {
+        Seq,
+        /// Whether the next event in this map is a key.
+        Map {
+            expect_key: bool,
+        },
+    }
+
+    let mut out = String::new();
+    let mut stack = Vec::new();
+    let mut first = true;
+
+    for event in events {
+        match event {
+            // `Some` is transparent: the contained value follows and takes
+            // this event's position in the enclosing container.
+            Event::Some => continue,
+            Event::SeqEnd => {
+                stack.pop();
+                out.push(']');
+                first = false;
+                continue;
+            }
+            Event::MapEnd => {
+                stack.pop();
+                out.push('}');
+                first = false;
+                continue;
+            }
+            _ => {}
+        }
+
+        // Separator and key/value punctuation for the position this event
+        // occupies in the enclosing container, if any.
+        match stack.last_mut() {
+            None | Some(Frame::Seq) => {
+                if !first {
+                    out.push_str(", ");
+                }
+            }
+            Some(Frame::Map { expect_key }) => {
+                if *expect_key {
+                    if !first {
+                        out.push_str(", ");
+                    }
+                } else {
+                    out.push_str(": ");
+                }
+                *expect_key = !*expect_key;
+            }
+        }
+        first = false;
+
+        match event {
+            Event::Bool(v) => {
+                let _ = write!(out, "{}", v);
+            }
+            Event::I8(v) => {
+                let _ = write!(out, "{}", v);
+            }
+            Event::I16(v) => {
+                let _ = write!(out, "{}", v);
+            }
+            Event::I32(v) => {
+                let _ = write!(out, "{}", v);
+            }
+            Event::I64(v) => {
+                let _ = write!(out, "{}", v);
+            }
+            Event::U8(v) => {
+                let _ = write!(out, "{}", v);
+            }
+            Event::U16(v) => {
+                let _ = write!(out, "{}", v);
+            }
+            Event::U32(v) => {
+                let _ = write!(out, "{}", v);
+            }
+            Event::U64(v) => {
+                let _ = write!(out, "{}", v);
+            }
+            Event::F32(v) => {
+                let _ = write!(out, "{}", v);
+            }
+            Event::F64(v) => {
+                let _ = write!(out, "{}", v);
+            }
+            Event::Char(v) => {
+                let _ = write!(out, "{:?}", v);
+            }
+            Event::Str(v) => {
+                let _ = write!(out, "{:?}", v);
+            }
+            Event::Bytes(v) => {
+                let _ = write!(out, "b{:?}", v.as_ref());
+            }
+            Event::None | Event::Unit => out.push_str("null"),
+            Event::SeqStart(_) => {
+                out.push('[');
+                stack.push(Frame::Seq);
+                first = true;
+            }
+            Event::MapStart(_) => {
+                out.push('{');
+                stack.push(Frame::Map { expect_key: true });
+                first = true;
+            }
+            // Handled above.
+            Event::Some | Event::SeqEnd | Event::MapEnd => {}
+        }
+    }
+
+    out
+}
+
+/// Creates a deserializer that reads from an iterator of [`Event`]s.
+///
+/// The returned deserializer is used by mutable reference so that nested
+/// values can be deserialized from the same event stream:
+/// `T::deserialize(&mut from_iter(events))`.
+pub fn from_iter<'de, I, E>(iter: I) -> EventDeserializer<'de, I::IntoIter, E>
+where
+    I: IntoIterator<Item = Event<'de>>,
+    E: de::Error,
+{
+    EventDeserializer {
+        iter: iter.into_iter().peekable(),
+        marker: PhantomData,
+    }
+}
+
+/// A deserializer that pulls [`Event`]s from an iterator.
+pub struct EventDeserializer<'de, I, E>
+where
+    I: Iterator<Item = Event<'de>>,
+{
+    iter: iter::Peekable<I>,
+    marker: PhantomData<E>,
+}
+
+impl<'de, I, E> EventDeserializer<'de, I, E>
+where
+    I: Iterator<Item = Event<'de>>,
+    E: de::Error,
+{
+    /// Checks that every event has been consumed.
+    pub fn end(&mut self) -> Result<(), E> {
+        match self.iter.peek() {
+            Some(event) => Err(de::Error::invalid_value(
+                event.unexpected(),
+                &"no remaining events",
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn next(&mut self) -> Result<Event<'de>, E> {
+        match self.iter.next() {
+            Some(event) => Ok(event),
+            None => Err(de::Error::custom("unexpected end of events")),
+        }
+    }
+
+    fn expect(&mut self, expected: Event<'static>, description: &'static str) -> Result<(), E> {
+        let event = tri!(self.next());
+        if event == expected {
+            Ok(())
+        } else {
+            Err(de::Error::invalid_value(event.unexpected(), &description))
+        }
+    }
+}
+
+impl<'de, 'a, I, E> Deserializer<'de> for &'a mut EventDeserializer<'de, I, E>
+where
+    I: Iterator<Item = Event<'de>>,
+    E: de::Error,
+{
+    type Error = E;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match tri!(self.next()) {
+            Event::Bool(v) => visitor.visit_bool(v),
+            Event::I8(v) => visitor.visit_i8(v),
+            Event::I16(v) => visitor.visit_i16(v),
+            Event::I32(v) => visitor.visit_i32(v),
+            Event::I64(v) => visitor.visit_i64(v),
+            Event::U8(v) => visitor.visit_u8(v),
+            Event::U16(v) => visitor.visit_u16(v),
+            Event::U32(v) => visitor.visit_u32(v),
+            Event::U64(v) => visitor.visit_u64(v),
+            Event::F32(v) => visitor.visit_f32(v),
+            Event::F64(v) => visitor.visit_f64(v),
+            Event::Char(v) => visitor.visit_char(v),
+            Event::Str(Cow::Borrowed(v)) => visitor.visit_borrowed_str(v),
+            Event::Str(Cow::Owned(v)) => visitor.visit_string(v),
+            Event::Bytes(Cow::Borrowed(v)) => visitor.visit_borrowed_bytes(v),
+            Event::Bytes(Cow::Owned(v)) => visitor.visit_byte_buf(v),
+            Event::None => visitor.visit_none(),
+            Event::Some => visitor.visit_some(self),
+            Event::Unit => visitor.visit_unit(),
+            Event::SeqStart(len) => {
+                let value = tri!(visitor.visit_seq(EventSeqAccess {
+                    de: &mut *self,
+                    len
+                }));
+                tri!(self.expect(Event::SeqEnd, "end of sequence"));
+                Ok(value)
+            }
+            Event::MapStart(len) => {
+                let value = tri!(visitor.visit_map(EventMapAccess {
+                    de: &mut *self,
+                    len
+                }));
+                tri!(self.expect(Event::MapEnd, "end of map"));
+                Ok(value)
+            }
+            event => Err(de::Error::invalid_value(event.unexpected(), &"a value")),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.iter.peek() {
+            Some(Event::None) => {
+                self.iter.next();
+                visitor.visit_none()
+            }
+            Some(Event::Some) => {
+                self.iter.next();
+                visitor.visit_some(self)
+            }
+            // Formats that do not distinguish a missing value from a present
+            // one can omit the Some marker.
+            Some(_) => visitor.visit_some(self),
+            None => Err(de::Error::custom("unexpected end of events")),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match tri!(self.next()) {
+            // A bare string is a unit variant.
+            Event::Str(variant) => visitor.visit_enum(variant.into_deserializer()),
+            // A single-entry map is any other kind of variant.
+            Event::MapStart(_) => {
+                let value = tri!(visitor.visit_enum(EventEnumAccess { de: &mut *self }));
+                tri!(self.expect(Event::MapEnd, "end of map"));
+                Ok(value)
+            }
+            event => Err(de::Error::invalid_value(
+                event.unexpected(),
+                &"a string or map representing an enum variant",
+            )),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct EventSeqAccess<'a, 'de, I, E>
+where
+    I: Iterator<Item = Event<'de>>,
+{
+    de: &'a mut EventDeserializer<'de, I, E>,
+    len: Option<usize>,
+}
+
+impl<'a, 'de, I, E> de::SeqAccess<'de> for EventSeqAccess<'a, 'de, I, E>
+where
+    I: Iterator<Item = Event<'de>>,
+    E: de::Error,
+{
+    type Error = E;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.de.iter.peek() {
+            Some(Event::SeqEnd) | None => Ok(None),
+            Some(_) => seed.deserialize(&mut *self.de).map(Some),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.len
+    }
+}
+
+struct EventMapAccess<'a, 'de, I, E>
+where
+    I: Iterator<Item = Event<'de>>,
+{
+    de: &'a mut EventDeserializer<'de, I, E>,
+    len: Option<usize>,
+}
+
+impl<'a, 'de, I, E> de::MapAccess<'de> for EventMapAccess<'a, 'de, I, E>
+where
+    I: Iterator<Item = Event<'de>>,
+    E: de::Error,
+{
+    type Error = E;
+
+    fn next_key_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.de.iter.peek() {
+            Some(Event::MapEnd) | None => Ok(None),
+            Some(_) => seed.deserialize(&mut *self.de).map(Some),
+        }
+    }
+
+    fn next_value_seed<T>(&mut self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut *self.de)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.len
+    }
+}
+
+struct EventEnumAccess<'a, 'de, I, E>
+where
+    I: Iterator<Item = Event<'de>>,
+{
+    de: &'a mut EventDeserializer<'de, I, E>,
+}
+
+impl<'a, 'de, I, E> de::EnumAccess<'de> for EventEnumAccess<'a, 'de, I, E>
+where
+    I: Iterator<Item = Event<'de>>,
+    E: de::Error,
+{
+    type Error = E;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        match tri!(self.de.next()) {
+            Event::Str(variant) => {
+                let deserializer: CowStrDeserializer<E> = variant.into_deserializer();
+                let value = tri!(seed.deserialize(deserializer));
+                Ok((value, self))
+            }
+            event => Err(de::Error::invalid_value(
+                event.unexpected(),
+                &"a string key naming an enum variant",
+            )),
+        }
+    }
+}
+
+impl<'a, 'de, I, E> de::VariantAccess<'de> for EventEnumAccess<'a, 'de, I, E>
+where
+    I: Iterator<Item = Event<'de>>,
+    E: de::Error,
+{
+    type Error = E;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.de.expect(Event::Unit, "unit")
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.de)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.de.deserialize_any(visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.de.deserialize_any(visitor)
+    }
+}
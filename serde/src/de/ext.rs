@@ -0,0 +1,1220 @@
+//! Chainable adapters for composing diagnostics onto a [`Deserializer`].
+//!
+//! Applications that want to annotate errors with context, bound the nesting
+//! depth of untrusted input, or observe which data model methods a format
+//! invokes normally have to write a full wrapper `Deserializer` for each of
+//! these concerns. The [`DeserializerExt`] extension trait packages them as
+//! chainable adapters instead:
+//!
+//! ```edition2021
+//! use serde::de::value::{Error, StrDeserializer};
+//! use serde::de::{DeserializerExt, IntoDeserializer};
+//! use serde::Deserialize;
+//!
+//! let de: StrDeserializer<Error> = "not a number".into_deserializer();
+//! let result = u32::deserialize(de.with_context(|| "parsing Foo"));
+//! assert_eq!(
+//!     result.unwrap_err().to_string(),
+//!     "parsing Foo: invalid type: string \"not a number\", expected u32",
+//! );
+//! ```
+//!
+//! The trait has a blanket impl for every `Deserializer`, so the adapters
+//! compose with each other and with any format's deserializer.
+//!
+//! [`Deserializer`]: crate::Deserializer
+
+use crate::lib::*;
+
+use crate::de::{
+    DeserializeSeed, Deserializer, EnumAccess, Error, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+
+/// Extension methods for [`Deserializer`] that wrap it in diagnostic adapters.
+///
+/// This trait is implemented for every `Deserializer` and is not meant to be
+/// implemented outside of serde.
+///
+/// [`Deserializer`]: crate::Deserializer
+pub trait DeserializerExt<'de>: Deserializer<'de> {
+    /// Prefixes any error produced during deserialization with context.
+    ///
+    /// The closure is called only if an error occurs. The resulting message
+    /// is `{context}: {error}`.
+    ///
+    /// ```edition2021
+    /// use serde::de::value::{BoolDeserializer, Error};
+    /// use serde::de::{DeserializerExt, IntoDeserializer};
+    /// use serde::Deserialize;
+    ///
+    /// let de: BoolDeserializer<Error> = true.into_deserializer();
+    /// let error = u8::deserialize(de.with_context(|| "parsing Config.port")).unwrap_err();
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "parsing Config.port: invalid type: boolean `true`, expected u8",
+    /// );
+    /// ```
+    fn with_context<F, C>(self, context: F) -> WithContext<Self, F>
+    where
+        Self: Sized,
+        F: FnOnce() -> C,
+        C: Display,
+    {
+        WithContext { de: self, context }
+    }
+
+    /// Fails with an error once the input nests deeper than `limit` levels.
+    ///
+    /// Sequences, maps, enum variants, newtype structs, and `Some` each count
+    /// as one level of nesting; scalars do not. A limit of 0 therefore allows
+    /// only a bare scalar. This guards recursive data structures against
+    /// stack overflow from deeply nested untrusted input.
+    ///
+    /// ```edition2021
+    /// use serde::de::event::{from_iter, Event};
+    /// use serde::de::value::Error;
+    /// use serde::de::DeserializerExt;
+    /// use serde::Deserialize;
+    ///
+    /// let events = [
+    ///     Event::SeqStart(None),
+    ///     Event::SeqStart(None),
+    ///     Event::U32(1),
+    ///     Event::SeqEnd,
+    ///     Event::SeqEnd,
+    /// ];
+    ///
+    /// let mut de = from_iter::<_, Error>(events.clone());
+    /// assert!(Vec::<Vec<u32>>::deserialize((&mut de).depth_limit(2)).is_ok());
+    ///
+    /// let mut de = from_iter::<_, Error>(events);
+    /// assert!(Vec::<Vec<u32>>::deserialize((&mut de).depth_limit(1)).is_err());
+    /// ```
+    fn depth_limit(self, limit: usize) -> DepthLimit<Self>
+    where
+        Self: Sized,
+    {
+        DepthLimit {
+            de: self,
+            remaining: limit,
+        }
+    }
+
+    /// Reports every `Deserializer` method invoked on the input to a callback.
+    ///
+    /// The callback receives the method name, such as `"deserialize_u32"`,
+    /// each time a value — including values nested inside sequences, maps,
+    /// options, and enum variants — is deserialized. This is useful for
+    /// tracing what a derived impl asks of a format.
+    ///
+    /// ```edition2021
+    /// use serde::de::value::{Error, U32Deserializer};
+    /// use serde::de::{DeserializerExt, IntoDeserializer};
+    /// use serde::Deserialize;
+    ///
+    /// let mut calls = Vec::new();
+    /// let de: U32Deserializer<Error> = 1996u32.into_deserializer();
+    /// let _: u32 = Deserialize::deserialize(de.traced(|method| calls.push(method))).unwrap();
+    /// assert_eq!(calls, ["deserialize_u32"]);
+    /// ```
+    fn traced<F>(self, trace: F) -> Traced<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&'static str),
+    {
+        Traced { de: self, trace }
+    }
+}
+
+impl<'de, D> DeserializerExt<'de> for D where D: Deserializer<'de> {}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// A deserializer returned by [`DeserializerExt::with_context`].
+pub struct WithContext<D, F> {
+    de: D,
+    context: F,
+}
+
+fn annotate<C, E>(context: C, error: E) -> E
+where
+    C: Display,
+    E: Error,
+{
+    struct Annotated<C, E> {
+        context: C,
+        error: E,
+    }
+
+    impl<C, E> Display for Annotated<C, E>
+    where
+        C: Display,
+        E: Display,
+    {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "{}: {}", self.context, self.error)
+        }
+    }
+
+    E::custom(Annotated { context, error })
+}
+
+macro_rules! forward_with_context {
+    ($($func:ident)*) => {
+        $(
+            fn $func<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                let WithContext { de, context } = self;
+                de.$func(visitor).map_err(|error| annotate(context(), error))
+            }
+        )*
+    };
+}
+
+impl<'de, D, F, C> Deserializer<'de> for WithContext<D, F>
+where
+    D: Deserializer<'de>,
+    F: FnOnce() -> C,
+    C: Display,
+{
+    type Error = D::Error;
+
+    forward_with_context! {
+        deserialize_any deserialize_bool
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64 deserialize_i128
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_u128
+        deserialize_f32 deserialize_f64 deserialize_char
+        deserialize_str deserialize_string deserialize_bytes deserialize_byte_buf
+        deserialize_option deserialize_unit deserialize_seq deserialize_map
+        deserialize_identifier deserialize_ignored_any
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let WithContext { de, context } = self;
+        de.deserialize_unit_struct(name, visitor)
+            .map_err(|error| annotate(context(), error))
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let WithContext { de, context } = self;
+        de.deserialize_newtype_struct(name, visitor)
+            .map_err(|error| annotate(context(), error))
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let WithContext { de, context } = self;
+        de.deserialize_tuple(len, visitor)
+            .map_err(|error| annotate(context(), error))
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let WithContext { de, context } = self;
+        de.deserialize_tuple_struct(name, len, visitor)
+            .map_err(|error| annotate(context(), error))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let WithContext { de, context } = self;
+        de.deserialize_struct(name, fields, visitor)
+            .map_err(|error| annotate(context(), error))
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let WithContext { de, context } = self;
+        de.deserialize_enum(name, variants, visitor)
+            .map_err(|error| annotate(context(), error))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// A deserializer returned by [`DeserializerExt::depth_limit`].
+pub struct DepthLimit<D> {
+    de: D,
+    remaining: usize,
+}
+
+fn exceeded_depth<E>() -> E
+where
+    E: Error,
+{
+    E::custom("exceeded recursion depth limit")
+}
+
+macro_rules! forward_depth_limit {
+    ($($func:ident)*) => {
+        $(
+            fn $func<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.de.$func(DepthVisitor {
+                    visitor,
+                    remaining: self.remaining,
+                })
+            }
+        )*
+    };
+}
+
+impl<'de, D> Deserializer<'de> for DepthLimit<D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    forward_depth_limit! {
+        deserialize_any deserialize_bool
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64 deserialize_i128
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_u128
+        deserialize_f32 deserialize_f64 deserialize_char
+        deserialize_str deserialize_string deserialize_bytes deserialize_byte_buf
+        deserialize_option deserialize_unit deserialize_seq deserialize_map
+        deserialize_identifier deserialize_ignored_any
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.de.deserialize_unit_struct(
+            name,
+            DepthVisitor {
+                visitor,
+                remaining: self.remaining,
+            },
+        )
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.de.deserialize_newtype_struct(
+            name,
+            DepthVisitor {
+                visitor,
+                remaining: self.remaining,
+            },
+        )
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.de.deserialize_tuple(
+            len,
+            DepthVisitor {
+                visitor,
+                remaining: self.remaining,
+            },
+        )
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.de.deserialize_tuple_struct(
+            name,
+            len,
+            DepthVisitor {
+                visitor,
+                remaining: self.remaining,
+            },
+        )
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.de.deserialize_struct(
+            name,
+            fields,
+            DepthVisitor {
+                visitor,
+                remaining: self.remaining,
+            },
+        )
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.de.deserialize_enum(
+            name,
+            variants,
+            DepthVisitor {
+                visitor,
+                remaining: self.remaining,
+            },
+        )
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+struct DepthVisitor<V> {
+    visitor: V,
+    remaining: usize,
+}
+
+macro_rules! forward_scalar_visits {
+    ($($func:ident: $ty:ty,)*) => {
+        $(
+            fn $func<E>(self, v: $ty) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                self.visitor.$func(v)
+            }
+        )*
+    };
+}
+
+impl<'de, V> Visitor<'de> for DepthVisitor<V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    forward_scalar_visits! {
+        visit_bool: bool,
+        visit_i8: i8,
+        visit_i16: i16,
+        visit_i32: i32,
+        visit_i64: i64,
+        visit_i128: i128,
+        visit_u8: u8,
+        visit_u16: u16,
+        visit_u32: u32,
+        visit_u64: u64,
+        visit_u128: u128,
+        visit_f32: f32,
+        visit_f64: f64,
+        visit_char: char,
+        visit_str: &str,
+        visit_bytes: &[u8],
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.visitor.visit_borrowed_str(v)
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.visitor.visit_borrowed_bytes(v)
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.visitor.visit_string(v)
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.visitor.visit_byte_buf(v)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.visitor.visit_none()
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.visitor.visit_unit()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if self.remaining == 0 {
+            return Err(exceeded_depth());
+        }
+        self.visitor.visit_some(DepthLimit {
+            de: deserializer,
+            remaining: self.remaining - 1,
+        })
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if self.remaining == 0 {
+            return Err(exceeded_depth());
+        }
+        self.visitor.visit_newtype_struct(DepthLimit {
+            de: deserializer,
+            remaining: self.remaining - 1,
+        })
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        if self.remaining == 0 {
+            return Err(exceeded_depth());
+        }
+        self.visitor.visit_seq(DepthSeqAccess {
+            access: seq,
+            remaining: self.remaining - 1,
+        })
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        if self.remaining == 0 {
+            return Err(exceeded_depth());
+        }
+        self.visitor.visit_map(DepthMapAccess {
+            access: map,
+            remaining: self.remaining - 1,
+        })
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        if self.remaining == 0 {
+            return Err(exceeded_depth());
+        }
+        self.visitor.visit_enum(DepthEnumAccess {
+            access: data,
+            remaining: self.remaining - 1,
+        })
+    }
+}
+
+struct DepthSeed<S> {
+    seed: S,
+    remaining: usize,
+}
+
+impl<'de, S> DeserializeSeed<'de> for DepthSeed<S>
+where
+    S: DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.seed.deserialize(DepthLimit {
+            de: deserializer,
+            remaining: self.remaining,
+        })
+    }
+}
+
+struct DepthSeqAccess<A> {
+    access: A,
+    remaining: usize,
+}
+
+impl<'de, A> SeqAccess<'de> for DepthSeqAccess<A>
+where
+    A: SeqAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.access.next_element_seed(DepthSeed {
+            seed,
+            remaining: self.remaining,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.access.size_hint()
+    }
+}
+
+struct DepthMapAccess<A> {
+    access: A,
+    remaining: usize,
+}
+
+impl<'de, A> MapAccess<'de> for DepthMapAccess<A>
+where
+    A: MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.access.next_key_seed(DepthSeed {
+            seed,
+            remaining: self.remaining,
+        })
+    }
+
+    fn next_value_seed<T>(&mut self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.access.next_value_seed(DepthSeed {
+            seed,
+            remaining: self.remaining,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.access.size_hint()
+    }
+}
+
+struct DepthEnumAccess<A> {
+    access: A,
+    remaining: usize,
+}
+
+impl<'de, A> EnumAccess<'de> for DepthEnumAccess<A>
+where
+    A: EnumAccess<'de>,
+{
+    type Error = A::Error;
+    type Variant = DepthVariantAccess<A::Variant>;
+
+    fn variant_seed<T>(self, seed: T) -> Result<(T::Value, Self::Variant), Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let (value, variant) = tri!(self.access.variant_seed(DepthSeed {
+            seed,
+            remaining: self.remaining,
+        }));
+        Ok((
+            value,
+            DepthVariantAccess {
+                access: variant,
+                remaining: self.remaining,
+            },
+        ))
+    }
+}
+
+struct DepthVariantAccess<A> {
+    access: A,
+    remaining: usize,
+}
+
+impl<'de, A> VariantAccess<'de> for DepthVariantAccess<A>
+where
+    A: VariantAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.access.unit_variant()
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.access.newtype_variant_seed(DepthSeed {
+            seed,
+            remaining: self.remaining,
+        })
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.access.tuple_variant(
+            len,
+            DepthVisitor {
+                visitor,
+                remaining: self.remaining,
+            },
+        )
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.access.struct_variant(
+            fields,
+            DepthVisitor {
+                visitor,
+                remaining: self.remaining,
+            },
+        )
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// A deserializer returned by [`DeserializerExt::traced`].
+pub struct Traced<D, F> {
+    de: D,
+    trace: F,
+}
+
+macro_rules! forward_traced {
+    ($($func:ident)*) => {
+        $(
+            fn $func<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                (self.trace)(stringify!($func));
+                self.de.$func(TracedVisitor {
+                    visitor,
+                    trace: self.trace,
+                })
+            }
+        )*
+    };
+}
+
+impl<'de, D, F> Deserializer<'de> for Traced<D, F>
+where
+    D: Deserializer<'de>,
+    F: FnMut(&'static str),
+{
+    type Error = D::Error;
+
+    forward_traced! {
+        deserialize_any deserialize_bool
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64 deserialize_i128
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_u128
+        deserialize_f32 deserialize_f64 deserialize_char
+        deserialize_str deserialize_string deserialize_bytes deserialize_byte_buf
+        deserialize_option deserialize_unit deserialize_seq deserialize_map
+        deserialize_identifier deserialize_ignored_any
+    }
+
+    fn deserialize_unit_struct<V>(
+        mut self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        (self.trace)("deserialize_unit_struct");
+        self.de.deserialize_unit_struct(
+            name,
+            TracedVisitor {
+                visitor,
+                trace: self.trace,
+            },
+        )
+    }
+
+    fn deserialize_newtype_struct<V>(
+        mut self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        (self.trace)("deserialize_newtype_struct");
+        self.de.deserialize_newtype_struct(
+            name,
+            TracedVisitor {
+                visitor,
+                trace: self.trace,
+            },
+        )
+    }
+
+    fn deserialize_tuple<V>(mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        (self.trace)("deserialize_tuple");
+        self.de.deserialize_tuple(
+            len,
+            TracedVisitor {
+                visitor,
+                trace: self.trace,
+            },
+        )
+    }
+
+    fn deserialize_tuple_struct<V>(
+        mut self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        (self.trace)("deserialize_tuple_struct");
+        self.de.deserialize_tuple_struct(
+            name,
+            len,
+            TracedVisitor {
+                visitor,
+                trace: self.trace,
+            },
+        )
+    }
+
+    fn deserialize_struct<V>(
+        mut self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        (self.trace)("deserialize_struct");
+        self.de.deserialize_struct(
+            name,
+            fields,
+            TracedVisitor {
+                visitor,
+                trace: self.trace,
+            },
+        )
+    }
+
+    fn deserialize_enum<V>(
+        mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        (self.trace)("deserialize_enum");
+        self.de.deserialize_enum(
+            name,
+            variants,
+            TracedVisitor {
+                visitor,
+                trace: self.trace,
+            },
+        )
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+struct TracedVisitor<V, F> {
+    visitor: V,
+    trace: F,
+}
+
+impl<'de, V, F> Visitor<'de> for TracedVisitor<V, F>
+where
+    V: Visitor<'de>,
+    F: FnMut(&'static str),
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    forward_scalar_visits! {
+        visit_bool: bool,
+        visit_i8: i8,
+        visit_i16: i16,
+        visit_i32: i32,
+        visit_i64: i64,
+        visit_i128: i128,
+        visit_u8: u8,
+        visit_u16: u16,
+        visit_u32: u32,
+        visit_u64: u64,
+        visit_u128: u128,
+        visit_f32: f32,
+        visit_f64: f64,
+        visit_char: char,
+        visit_str: &str,
+        visit_bytes: &[u8],
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.visitor.visit_borrowed_str(v)
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.visitor.visit_borrowed_bytes(v)
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.visitor.visit_string(v)
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.visitor.visit_byte_buf(v)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.visitor.visit_none()
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        self.visitor.visit_unit()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.visitor.visit_some(Traced {
+            de: deserializer,
+            trace: self.trace,
+        })
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.visitor.visit_newtype_struct(Traced {
+            de: deserializer,
+            trace: self.trace,
+        })
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        self.visitor.visit_seq(TracedSeqAccess {
+            access: seq,
+            trace: self.trace,
+        })
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        self.visitor.visit_map(TracedMapAccess {
+            access: map,
+            trace: self.trace,
+        })
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        self.visitor.visit_enum(TracedEnumAccess {
+            access: data,
+            trace: self.trace,
+        })
+    }
+}
+
+struct TracedSeed<S, F> {
+    seed: S,
+    trace: F,
+}
+
+impl<'de, S, F> DeserializeSeed<'de> for TracedSeed<S, F>
+where
+    S: DeserializeSeed<'de>,
+    F: FnMut(&'static str),
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.seed.deserialize(Traced {
+            de: deserializer,
+            trace: self.trace,
+        })
+    }
+}
+
+struct TracedSeqAccess<A, F> {
+    access: A,
+    trace: F,
+}
+
+impl<'de, A, F> SeqAccess<'de> for TracedSeqAccess<A, F>
+where
+    A: SeqAccess<'de>,
+    F: FnMut(&'static str),
+{
+    type Error = A::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.access.next_element_seed(TracedSeed {
+            seed,
+            trace: &mut self.trace,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.access.size_hint()
+    }
+}
+
+struct TracedMapAccess<A, F> {
+    access: A,
+    trace: F,
+}
+
+impl<'de, A, F> MapAccess<'de> for TracedMapAccess<A, F>
+where
+    A: MapAccess<'de>,
+    F: FnMut(&'static str),
+{
+    type Error = A::Error;
+
+    fn next_key_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.access.next_key_seed(TracedSeed {
+            seed,
+            trace: &mut self.trace,
+        })
+    }
+
+    fn next_value_seed<T>(&mut self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.access.next_value_seed(TracedSeed {
+            seed,
+            trace: &mut self.trace,
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.access.size_hint()
+    }
+}
+
+struct TracedEnumAccess<A, F> {
+    access: A,
+    trace: F,
+}
+
+impl<'de, A, F> EnumAccess<'de> for TracedEnumAccess<A, F>
+where
+    A: EnumAccess<'de>,
+    F: FnMut(&'static str),
+{
+    type Error = A::Error;
+    type Variant = TracedVariantAccess<A::Variant, F>;
+
+    fn variant_seed<T>(self, seed: T) -> Result<(T::Value, Self::Variant), Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let TracedEnumAccess { access, mut trace } = self;
+        let (value, variant) = tri!(access.variant_seed(TracedSeed {
+            seed,
+            trace: &mut trace,
+        }));
+        Ok((
+            value,
+            TracedVariantAccess {
+                access: variant,
+                trace,
+            },
+        ))
+    }
+}
+
+struct TracedVariantAccess<A, F> {
+    access: A,
+    trace: F,
+}
+
+impl<'de, A, F> VariantAccess<'de> for TracedVariantAccess<A, F>
+where
+    A: VariantAccess<'de>,
+    F: FnMut(&'static str),
+{
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.access.unit_variant()
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.access.newtype_variant_seed(TracedSeed {
+            seed,
+            trace: self.trace,
+        })
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.access.tuple_variant(
+            len,
+            TracedVisitor {
+                visitor,
+                trace: self.trace,
+            },
+        )
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.access.struct_variant(
+            fields,
+            TracedVisitor {
+                visitor,
+                trace: self.trace,
+            },
+        )
+    }
+}
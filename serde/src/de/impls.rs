@@ -1,8 +1,10 @@
 use crate::lib::*;
+#[cfg(not(no_maybe_uninit))]
+use core::mem::{self, MaybeUninit};
 
 use crate::de::{
-    Deserialize, Deserializer, EnumAccess, Error, MapAccess, SeqAccess, Unexpected, VariantAccess,
-    Visitor,
+    Deserialize, Deserializer, EnumAccess, Error, Expected, MapAccess, SeqAccess, Unexpected,
+    VariantAccess, Visitor,
 };
 
 use crate::seed::InPlaceSeed;
@@ -380,11 +382,127 @@ macro_rules! uint_to_self {
     };
 }
 
+// Negative and non-negative 128-bit input must check against opposite bounds:
+// casting a negative i128 to u128, or a u128 above i128::MAX to i128, wraps
+// and would pass or fail the wrong comparison.
+macro_rules! num_128 {
+    (i128 : $visit:ident) => {
+        fn $visit<E>(self, v: i128) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            let in_range = if v < 0 {
+                v >= Self::Value::min_value() as i128
+            } else {
+                v as u128 <= Self::Value::max_value() as u128
+            };
+            if in_range {
+                Ok(v as Self::Value)
+            } else {
+                Err(Error::custom(format_args!(
+                    "invalid value: integer `{}`, expected {}",
+                    v, &self as &dyn Expected,
+                )))
+            }
+        }
+    };
+
+    (u128 : $visit:ident) => {
+        fn $visit<E>(self, v: u128) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            if v <= Self::Value::max_value() as u128 {
+                Ok(v as Self::Value)
+            } else {
+                Err(Error::custom(format_args!(
+                    "invalid value: integer `{}`, expected {}",
+                    v, &self as &dyn Expected,
+                )))
+            }
+        }
+    };
+
+    (nonzero $primitive:ident i128 : $visit:ident) => {
+        fn $visit<E>(self, v: i128) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            let in_range = if v < 0 {
+                v >= $primitive::min_value() as i128
+            } else {
+                v as u128 <= $primitive::max_value() as u128
+            };
+            if in_range {
+                if let Some(nonzero) = Self::Value::new(v as $primitive) {
+                    Ok(nonzero)
+                } else {
+                    Err(Error::invalid_value(Unexpected::Unsigned(0), &self))
+                }
+            } else {
+                Err(Error::custom(format_args!(
+                    "invalid value: integer `{}`, expected {}",
+                    v, &self as &dyn Expected,
+                )))
+            }
+        }
+    };
+
+    (nonzero $primitive:ident u128 : $visit:ident) => {
+        fn $visit<E>(self, v: u128) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            if v <= $primitive::max_value() as u128 {
+                if let Some(nonzero) = Self::Value::new(v as $primitive) {
+                    Ok(nonzero)
+                } else {
+                    Err(Error::invalid_value(Unexpected::Unsigned(0), &self))
+                }
+            } else {
+                Err(Error::custom(format_args!(
+                    "invalid value: integer `{}`, expected {}",
+                    v, &self as &dyn Expected,
+                )))
+            }
+        }
+    };
+
+    (saturating $primitive:ident i128 : $visit:ident) => {
+        fn $visit<E>(self, v: i128) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            if v < $primitive::MIN as i128 {
+                Ok(Saturating($primitive::MIN))
+            } else if v > 0 && ($primitive::MAX as u128) < v as u128 {
+                Ok(Saturating($primitive::MAX))
+            } else {
+                Ok(Saturating(v as $primitive))
+            }
+        }
+    };
+
+    (saturating $primitive:ident u128 : $visit:ident) => {
+        fn $visit<E>(self, v: u128) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            if v <= $primitive::MAX as u128 {
+                Ok(Saturating(v as $primitive))
+            } else {
+                Ok(Saturating($primitive::MAX))
+            }
+        }
+    };
+}
+
 impl_deserialize_num! {
     i8, NonZeroI8 cfg(not(no_num_nonzero_signed)), deserialize_i8
     num_self!(i8:visit_i8);
     int_to_int!(i16:visit_i16 i32:visit_i32 i64:visit_i64);
     uint_to_self!(u8:visit_u8 u16:visit_u16 u32:visit_u32 u64:visit_u64);
+    num_128!(i128:visit_i128 u128:visit_u128);
 }
 
 impl_deserialize_num! {
@@ -393,6 +511,7 @@ impl_deserialize_num! {
     num_as_self!(i8:visit_i8);
     int_to_int!(i32:visit_i32 i64:visit_i64);
     uint_to_self!(u8:visit_u8 u16:visit_u16 u32:visit_u32 u64:visit_u64);
+    num_128!(i128:visit_i128 u128:visit_u128);
 }
 
 impl_deserialize_num! {
@@ -401,6 +520,7 @@ impl_deserialize_num! {
     num_as_self!(i8:visit_i8 i16:visit_i16);
     int_to_int!(i64:visit_i64);
     uint_to_self!(u8:visit_u8 u16:visit_u16 u32:visit_u32 u64:visit_u64);
+    num_128!(i128:visit_i128 u128:visit_u128);
 }
 
 impl_deserialize_num! {
@@ -408,6 +528,7 @@ impl_deserialize_num! {
     num_self!(i64:visit_i64);
     num_as_self!(i8:visit_i8 i16:visit_i16 i32:visit_i32);
     uint_to_self!(u8:visit_u8 u16:visit_u16 u32:visit_u32 u64:visit_u64);
+    num_128!(i128:visit_i128 u128:visit_u128);
 }
 
 impl_deserialize_num! {
@@ -415,6 +536,7 @@ impl_deserialize_num! {
     num_as_self!(i8:visit_i8 i16:visit_i16);
     int_to_int!(i32:visit_i32 i64:visit_i64);
     uint_to_self!(u8:visit_u8 u16:visit_u16 u32:visit_u32 u64:visit_u64);
+    num_128!(i128:visit_i128 u128:visit_u128);
 }
 
 impl_deserialize_num! {
@@ -422,6 +544,7 @@ impl_deserialize_num! {
     num_self!(u8:visit_u8);
     int_to_uint!(i8:visit_i8 i16:visit_i16 i32:visit_i32 i64:visit_i64);
     uint_to_self!(u16:visit_u16 u32:visit_u32 u64:visit_u64);
+    num_128!(i128:visit_i128 u128:visit_u128);
 }
 
 impl_deserialize_num! {
@@ -430,6 +553,7 @@ impl_deserialize_num! {
     num_as_self!(u8:visit_u8);
     int_to_uint!(i8:visit_i8 i16:visit_i16 i32:visit_i32 i64:visit_i64);
     uint_to_self!(u32:visit_u32 u64:visit_u64);
+    num_128!(i128:visit_i128 u128:visit_u128);
 }
 
 impl_deserialize_num! {
@@ -438,6 +562,7 @@ impl_deserialize_num! {
     num_as_self!(u8:visit_u8 u16:visit_u16);
     int_to_uint!(i8:visit_i8 i16:visit_i16 i32:visit_i32 i64:visit_i64);
     uint_to_self!(u64:visit_u64);
+    num_128!(i128:visit_i128 u128:visit_u128);
 }
 
 impl_deserialize_num! {
@@ -445,6 +570,7 @@ impl_deserialize_num! {
     num_self!(u64:visit_u64);
     num_as_self!(u8:visit_u8 u16:visit_u16 u32:visit_u32);
     int_to_uint!(i8:visit_i8 i16:visit_i16 i32:visit_i32 i64:visit_i64);
+    num_128!(i128:visit_i128 u128:visit_u128);
 }
 
 impl_deserialize_num! {
@@ -452,6 +578,7 @@ impl_deserialize_num! {
     num_as_self!(u8:visit_u8 u16:visit_u16);
     int_to_uint!(i8:visit_i8 i16:visit_i16 i32:visit_i32 i64:visit_i64);
     uint_to_self!(u32:visit_u32 u64:visit_u64);
+    num_128!(i128:visit_i128 u128:visit_u128);
 }
 
 impl_deserialize_num! {
@@ -470,63 +597,6 @@ impl_deserialize_num! {
     num_as_self!(u8:visit_u8 u16:visit_u16 u32:visit_u32 u64:visit_u64);
 }
 
-macro_rules! num_128 {
-    ($ty:ident : $visit:ident) => {
-        fn $visit<E>(self, v: $ty) -> Result<Self::Value, E>
-        where
-            E: Error,
-        {
-            if v as i128 >= Self::Value::min_value() as i128
-                && v as u128 <= Self::Value::max_value() as u128
-            {
-                Ok(v as Self::Value)
-            } else {
-                Err(Error::invalid_value(
-                    Unexpected::Other(stringify!($ty)),
-                    &self,
-                ))
-            }
-        }
-    };
-
-    (nonzero $primitive:ident $ty:ident : $visit:ident) => {
-        fn $visit<E>(self, v: $ty) -> Result<Self::Value, E>
-        where
-            E: Error,
-        {
-            if v as i128 >= $primitive::min_value() as i128
-                && v as u128 <= $primitive::max_value() as u128
-            {
-                if let Some(nonzero) = Self::Value::new(v as $primitive) {
-                    Ok(nonzero)
-                } else {
-                    Err(Error::invalid_value(Unexpected::Unsigned(0), &self))
-                }
-            } else {
-                Err(Error::invalid_value(
-                    Unexpected::Other(stringify!($ty)),
-                    &self,
-                ))
-            }
-        }
-    };
-
-    (saturating $primitive:ident $ty:ident : $visit:ident) => {
-        fn $visit<E>(self, v: $ty) -> Result<Self::Value, E>
-        where
-            E: Error,
-        {
-            if (v as i128) < $primitive::MIN as i128 {
-                Ok(Saturating($primitive::MIN))
-            } else if ($primitive::MAX as u128) < v as u128 {
-                Ok(Saturating($primitive::MAX))
-            } else {
-                Ok(Saturating(v as $primitive))
-            }
-        }
-    };
-}
-
 impl_deserialize_num! {
     i128, NonZeroI128 cfg(not(no_num_nonzero_signed)), deserialize_i128
     num_self!(i128:visit_i128);
@@ -1288,6 +1358,54 @@ macro_rules! array_impls {
                     formatter.write_str(concat!("an array of length ", $len))
                 }
 
+                #[cfg(not(no_maybe_uninit))]
+                #[inline]
+                fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where
+                    A: SeqAccess<'de>,
+                {
+                    // Each element is written directly into its final slot.
+                    // The guard tracks how many slots have been initialized
+                    // so far and drops exactly those if deserializing a later
+                    // element fails.
+                    struct PartialArray<T> {
+                        data: [MaybeUninit<T>; $len],
+                        init: usize,
+                    }
+
+                    impl<T> Drop for PartialArray<T> {
+                        fn drop(&mut self) {
+                            for elem in &mut self.data[..self.init] {
+                                unsafe { ptr::drop_in_place(elem.as_mut_ptr()) };
+                            }
+                        }
+                    }
+
+                    let mut partial = PartialArray::<T> {
+                        // An uninitialized array of MaybeUninit does not
+                        // require initialization.
+                        data: unsafe { MaybeUninit::uninit().assume_init() },
+                        init: 0,
+                    };
+                    while partial.init < $len {
+                        match tri!(seq.next_element()) {
+                            Some(val) => {
+                                partial.data[partial.init] = MaybeUninit::new(val);
+                                partial.init += 1;
+                            }
+                            None => return Err(Error::invalid_length(partial.init, &self)),
+                        }
+                    }
+                    // Every slot is initialized; take the array out without
+                    // running the guard.
+                    let array = unsafe { ptr::read(partial.data.as_ptr().cast::<[T; $len]>()) };
+                    mem::forget(partial);
+                    Ok(array)
+                }
+
+                // MaybeUninit stabilized in Rust 1.36. Older compilers build
+                // each element into a temporary and move the batch at the end.
+                #[cfg(no_maybe_uninit)]
                 #[inline]
                 fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
                 where
@@ -1962,6 +2080,44 @@ impl<'de> Deserialize<'de> for OsString {
 
 ////////////////////////////////////////////////////////////////////////////////
 
+// Opaque error produced by deserializing an error trait object. Its `Display`
+// and `Debug` representations are the deserialized message. Serialization of
+// an error writes its `Display` representation, so a round trip preserves the
+// message but not the concrete error type.
+#[cfg(feature = "std")]
+struct ErrorMessage(String);
+
+#[cfg(feature = "std")]
+impl Display for ErrorMessage {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.0, formatter)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Debug for ErrorMessage {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(&self.0, formatter)
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for ErrorMessage {}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<'de> Deserialize<'de> for Box<dyn error::Error + Send + Sync> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let message = tri!(String::deserialize(deserializer));
+        Ok(Box::new(ErrorMessage(message)))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
 forwarded_impl! {
     #[cfg(any(feature = "std", feature = "alloc"))]
     #[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
@@ -1986,6 +2142,11 @@ forwarded_impl! {
     (), Box<OsStr>, OsString::into_boxed_os_str
 }
 
+// This impl always produces `Cow::Owned`. Serde's data model has borrowed
+// visits only for `str` and `[u8]`, so there is no way to hand a visitor a
+// borrowed `[T]` for other element types; `#[serde(borrow)]` covers the two
+// borrowable cases. `Cow::Borrowed` values only arise when constructed in
+// Rust, and serialization does not distinguish the two.
 #[cfg(any(feature = "std", feature = "alloc"))]
 #[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
 impl<'de, 'a, T> Deserialize<'de> for Cow<'a, T>
@@ -2882,6 +3043,37 @@ mod range_to {
 
 ////////////////////////////////////////////////////////////////////////////////
 
+struct RangeFullVisitor;
+
+impl<'de> Visitor<'de> for RangeFullVisitor {
+    type Value = RangeFull;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("unit")
+    }
+
+    #[inline]
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(RangeFull)
+    }
+}
+
+// Zero-sized like PhantomData, and deserialized the same way: as a unit
+// struct.
+impl<'de> Deserialize<'de> for RangeFull {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_unit_struct("RangeFull", RangeFullVisitor)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
 impl<'de, T> Deserialize<'de> for Bound<T>
 where
     T: Deserialize<'de>,
@@ -100,7 +100,48 @@
 //!    - SocketAddrV4
 //!    - SocketAddrV6
 //!
+//! # Supporting flatten and untagged enums in a data format
+//!
+//! The `#[serde(flatten)]` attribute and untagged or internally tagged enum
+//! representations work by buffering part of the input and replaying it
+//! against several candidate types. This imposes a few requirements on a
+//! [`Deserializer`] beyond what plain derived structs need. Formats that meet
+//! them work with these attributes out of the box; formats that do not will
+//! see hard-to-diagnose errors only when a user combines them with flatten.
+//!
+//!  - **The format must be self-describing.** Buffering is driven through
+//!    [`Deserializer::deserialize_any`], so the input alone must determine
+//!    whether the next value is a map, sequence, string, number, etc.
+//!    Non-self-describing binary formats cannot support flatten.
+//!
+//!  - **Strings and byte arrays that borrow from the input must be passed to
+//!    the visitor with [`Visitor::visit_borrowed_str`] and
+//!    [`Visitor::visit_borrowed_bytes`].** The buffer preserves the
+//!    distinction between borrowed and transient data; calling the transient
+//!    methods for borrowed data unnecessarily disables zero-copy
+//!    deserialization through flatten and untagged enums, and in the presence
+//!    of `#[serde(borrow)]` produces errors about lifetimes.
+//!
+//!  - **Map keys must be deserializable without looking at their values.**
+//!    The generated code probes keys through [`MapAccess::next_key_seed`] and
+//!    may skip or buffer the corresponding value afterwards, so a format must
+//!    not require key and value to be consumed in one call.
+//!
+//!  - **[`Deserializer::is_human_readable`] must return the same value for
+//!    the outer deserializer and for any deserializers it hands out for
+//!    nested values**, otherwise data buffered from one representation is
+//!    replayed into a type expecting the other.
+//!
+//! The serde test suite exercises these requirements through the
+//! `serde_test` token deserializer, which format authors can mirror to check
+//! their own implementations.
+//!
 //! [Implementing `Deserialize`]: https://serde.rs/impl-deserialize.html
+//! [`Deserializer::deserialize_any`]: trait.Deserializer.html#tymethod.deserialize_any
+//! [`Deserializer::is_human_readable`]: trait.Deserializer.html#method.is_human_readable
+//! [`Visitor::visit_borrowed_str`]: trait.Visitor.html#method.visit_borrowed_str
+//! [`Visitor::visit_borrowed_bytes`]: trait.Visitor.html#method.visit_borrowed_bytes
+//! [`MapAccess::next_key_seed`]: trait.MapAccess.html#tymethod.next_key_seed
 //! [`Deserialize`]: ../trait.Deserialize.html
 //! [`Deserializer`]: ../trait.Deserializer.html
 //! [`LinkedHashMap<K, V>`]: https://docs.rs/linked-hash-map/*/linked_hash_map/struct.LinkedHashMap.html
@@ -116,8 +157,20 @@ use crate::lib::*;
 
 ////////////////////////////////////////////////////////////////////////////////
 
+pub mod budget;
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+pub mod event;
+pub mod ext;
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+pub mod or_else;
 pub mod value;
 
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub use self::event::from_iter;
+pub use self::ext::DeserializerExt;
+
 mod ignored_any;
 mod impls;
 pub(crate) mod size_hint;
@@ -251,6 +304,13 @@ macro_rules! declare_error_trait {
                         "unknown variant `{}`, there are no variants",
                         variant
                     ))
+                } else if let Some(suggestion) = did_you_mean(variant, expected) {
+                    Error::custom(format_args!(
+                        "unknown variant `{}`, expected {}, did you mean `{}`?",
+                        variant,
+                        OneOf { names: expected },
+                        suggestion
+                    ))
                 } else {
                     Error::custom(format_args!(
                         "unknown variant `{}`, expected {}",
@@ -269,6 +329,13 @@ macro_rules! declare_error_trait {
                         "unknown field `{}`, there are no fields",
                         field
                     ))
+                } else if let Some(suggestion) = did_you_mean(field, expected) {
+                    Error::custom(format_args!(
+                        "unknown field `{}`, expected {}, did you mean `{}`?",
+                        field,
+                        OneOf { names: expected },
+                        suggestion
+                    ))
                 } else {
                     Error::custom(format_args!(
                         "unknown field `{}`, expected {}",
@@ -299,6 +366,10 @@ macro_rules! declare_error_trait {
 #[cfg(feature = "std")]
 declare_error_trait!(Error: Sized + StdError);
 
+// Without std, the error trait deliberately has no `StdError` supertrait. A
+// no_std data format's error type needs only `Debug`, `Display`, and `custom`,
+// independent of the polyfilled `std_error` module used on toolchains that
+// predate `core::error::Error`.
 #[cfg(not(feature = "std"))]
 declare_error_trait!(Error: Sized + Debug + Display);
 
@@ -1816,6 +1887,18 @@ pub trait MapAccess<'de> {
     where
         V: DeserializeSeed<'de>;
 
+    /// This returns `Ok(Some(key))` for the next key in the map deserialized
+    /// as a `u64`, or `Ok(None)` if there are no more remaining entries.
+    ///
+    /// Formats that store map keys as native integers may override this to
+    /// produce the key directly, without routing it through
+    /// `DeserializeSeed`. The default implementation is equivalent to
+    /// `next_key_seed` with a `u64` seed and is correct for every format.
+    #[inline]
+    fn next_key_u64(&mut self) -> Result<Option<u64>, Self::Error> {
+        self.next_key_seed(PhantomData::<u64>)
+    }
+
     /// This returns `Ok(Some((key, value)))` for the next (key-value) pair in
     /// the map, or `Ok(None)` if there are no more remaining items.
     ///
@@ -1916,6 +1999,11 @@ where
         (**self).next_value_seed(seed)
     }
 
+    #[inline]
+    fn next_key_u64(&mut self) -> Result<Option<u64>, Self::Error> {
+        (**self).next_key_u64()
+    }
+
     #[inline]
     fn next_entry_seed<K, V>(
         &mut self,
@@ -2297,6 +2385,55 @@ impl Display for OneOf {
     }
 }
 
+/// Returns the expected name closest to the unrecognized one, if any of them
+/// is close enough that the user plausibly meant it. Used to offer "did you
+/// mean" suggestions in unknown field and unknown variant messages.
+fn did_you_mean(name: &str, expected: &'static [&'static str]) -> Option<&'static str> {
+    let mut best: Option<(usize, &'static str)> = None;
+    for &candidate in expected {
+        let len = Ord::max(name.len(), candidate.len());
+        // Suggesting among very short names is noise, and a third of the
+        // name's length is as far as a plausible typo gets.
+        if len < 3 {
+            continue;
+        }
+        let distance = match levenshtein(name, candidate) {
+            Some(distance) => distance,
+            None => continue,
+        };
+        if distance <= len / 3 && best.map_or(true, |(best_distance, _)| distance < best_distance) {
+            best = Some((distance, candidate));
+        }
+    }
+    best.map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein edit distance between two short byte strings, or `None` if
+/// either is too long to bother with.
+fn levenshtein(a: &str, b: &str) -> Option<usize> {
+    const MAX: usize = 32;
+    let a = a.as_bytes();
+    let b = b.as_bytes();
+    if a.len() > MAX || b.len() > MAX {
+        return None;
+    }
+    let mut row = [0usize; MAX + 1];
+    for (j, cell) in row.iter_mut().enumerate().take(b.len() + 1) {
+        *cell = j;
+    }
+    for (i, &byte_a) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &byte_b) in b.iter().enumerate() {
+            let up = row[j + 1];
+            let substitute = diagonal + (byte_a != byte_b) as usize;
+            row[j + 1] = Ord::min(Ord::min(up + 1, row[j] + 1), substitute);
+            diagonal = up;
+        }
+    }
+    Some(row[b.len()])
+}
+
 struct WithDecimalPoint(f64);
 
 impl Display for WithDecimalPoint {
@@ -0,0 +1,109 @@
+//! Chained fallback deserialization for self-describing formats.
+//!
+//! A `Deserialize` impl sometimes needs to accept more than one wire
+//! representation of the same type — typically the current one plus a legacy
+//! one that old producers still emit. Writing that by hand means either an
+//! untagged enum workaround or a custom `Visitor` covering both shapes.
+//!
+//! [`OrElseDeserializer`] buffers the input once, using the same machinery as
+//! derived untagged enums, and then lets the impl attempt any number of
+//! representations against the buffered value. Because the input must be
+//! buffered before the first attempt, this works only with self-describing
+//! formats such as JSON; the buffering itself is driven through
+//! [`deserialize_any`].
+//!
+//! ```edition2021
+//! use serde::de::or_else::OrElseDeserializer;
+//! use serde::de::value::{Error, MapDeserializer, SeqDeserializer};
+//! use serde::de::{Deserialize, Deserializer};
+//! use serde_derive::Deserialize;
+//!
+//! #[derive(Deserialize, PartialEq, Debug)]
+//! struct Point {
+//!     x: i32,
+//!     y: i32,
+//! }
+//!
+//! #[derive(PartialEq, Debug)]
+//! struct Wire(Point);
+//!
+//! impl<'de> Deserialize<'de> for Wire {
+//!     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+//!     where
+//!         D: Deserializer<'de>,
+//!     {
+//!         let buffered = OrElseDeserializer::new(deserializer)?;
+//!         // Current representation: a map with named fields.
+//!         match buffered.try_deserialize::<Point>() {
+//!             Ok(point) => Ok(Wire(point)),
+//!             // Legacy representation: a bare two-element sequence.
+//!             Err(_) => {
+//!                 let (x, y) = buffered.into_deserialize::<(i32, i32)>()?;
+//!                 Ok(Wire(Point { x, y }))
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! let current = MapDeserializer::<_, Error>::new([("x", 1), ("y", 2)].into_iter());
+//! assert_eq!(Wire::deserialize(current).unwrap(), Wire(Point { x: 1, y: 2 }));
+//!
+//! let legacy = SeqDeserializer::<_, Error>::new([1, 2].into_iter());
+//! assert_eq!(Wire::deserialize(legacy).unwrap(), Wire(Point { x: 1, y: 2 }));
+//! ```
+//!
+//! [`deserialize_any`]: crate::Deserializer::deserialize_any
+
+use crate::lib::*;
+
+use crate::__private::de::{Content, ContentDeserializer, ContentRefDeserializer};
+use crate::de::{self, Deserialize, Deserializer};
+
+/// A buffered copy of a deserializer's input, against which multiple
+/// representations can be attempted in turn.
+///
+/// See the [module documentation](self) for an example.
+pub struct OrElseDeserializer<'de, E> {
+    content: Content<'de>,
+    error: PhantomData<E>,
+}
+
+impl<'de, E> OrElseDeserializer<'de, E>
+where
+    E: de::Error,
+{
+    /// Buffer the given deserializer's input.
+    ///
+    /// This drives the deserializer to completion through
+    /// `deserialize_any`, so it fails on formats that are not
+    /// self-describing, or if the input itself is malformed.
+    pub fn new<D>(deserializer: D) -> Result<Self, E>
+    where
+        D: Deserializer<'de, Error = E>,
+    {
+        Ok(OrElseDeserializer {
+            content: tri!(Content::deserialize(deserializer)),
+            error: PhantomData,
+        })
+    }
+
+    /// Attempt to deserialize a `T` from the buffered input, without
+    /// consuming it. On failure the buffer is untouched and another
+    /// representation can be tried.
+    pub fn try_deserialize<T>(&self) -> Result<T, E>
+    where
+        T: Deserialize<'de>,
+    {
+        T::deserialize(ContentRefDeserializer::new(&self.content))
+    }
+
+    /// Deserialize a `T` from the buffered input, consuming it. Use this for
+    /// the final representation in a chain: it hands borrowed and owned data
+    /// in the buffer to `T` by value instead of re-borrowing it.
+    pub fn into_deserialize<T>(self) -> Result<T, E>
+    where
+        T: Deserialize<'de>,
+    {
+        T::deserialize(ContentDeserializer::new(self.content))
+    }
+}
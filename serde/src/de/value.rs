@@ -119,6 +119,9 @@ impl error::Error for Error {
     }
 }
 
+#[cfg(all(not(feature = "std"), not(no_core_error)))]
+impl core::error::Error for Error {}
+
 ////////////////////////////////////////////////////////////////////////////////
 
 impl<'de, E> IntoDeserializer<'de, E> for ()
@@ -1727,6 +1730,145 @@ where
 
 ////////////////////////////////////////////////////////////////////////////////
 
+/// An `EnumAccess` for formats in which an enum variant is nothing more than
+/// its name or index, with no associated data.
+///
+/// The wrapped deserializer produces the variant identifier, typically a
+/// string or an integer. Only unit variants can be deserialized through this
+/// access; data-carrying variants fail with a type error. This lets simple
+/// formats such as environment variables or query strings support fieldless
+/// enums in their `deserialize_enum` with a few lines:
+///
+/// ```edition2021
+/// use serde::de::value::{BorrowedStrDeserializer, UnitOnlyEnumAccess};
+/// use serde::de::{value, Deserialize, Visitor};
+/// use serde_derive::Deserialize;
+///
+/// #[derive(Deserialize, PartialEq, Debug)]
+/// enum Level {
+///     Debug,
+///     Info,
+/// }
+///
+/// struct EnumVisitor;
+///
+/// impl<'de> Visitor<'de> for EnumVisitor {
+///     type Value = Level;
+///
+///     fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+///         formatter.write_str("a variant name")
+///     }
+///
+///     fn visit_enum<A>(self, access: A) -> Result<Self::Value, A::Error>
+///     where
+///         A: serde::de::EnumAccess<'de>,
+///     {
+///         Level::deserialize(serde::de::value::EnumAccessDeserializer::new(access))
+///     }
+/// }
+///
+/// let access = UnitOnlyEnumAccess::new(BorrowedStrDeserializer::<value::Error>::new("Info"));
+/// let level = EnumVisitor.visit_enum(access).unwrap();
+/// assert_eq!(level, Level::Info);
+/// ```
+#[derive(Clone, Debug)]
+pub struct UnitOnlyEnumAccess<D> {
+    variant: D,
+}
+
+impl<D> UnitOnlyEnumAccess<D> {
+    /// Construct a new `UnitOnlyEnumAccess<D>` from a deserializer for the
+    /// variant identifier.
+    pub fn new(variant: D) -> Self {
+        UnitOnlyEnumAccess { variant }
+    }
+}
+
+impl<'de, D> de::EnumAccess<'de> for UnitOnlyEnumAccess<D>
+where
+    D: de::Deserializer<'de>,
+{
+    type Error = D::Error;
+    type Variant = UnitOnlyVariantAccess<D::Error>;
+
+    fn variant_seed<T>(self, seed: T) -> Result<(T::Value, Self::Variant), Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.variant).map(|value| {
+            (
+                value,
+                UnitOnlyVariantAccess {
+                    marker: PhantomData,
+                },
+            )
+        })
+    }
+}
+
+/// A `VariantAccess` for variants that carry no data.
+///
+/// This is the `Variant` type of [`UnitOnlyEnumAccess`]. `unit_variant`
+/// succeeds; newtype, tuple, and struct variants fail with a type error.
+pub struct UnitOnlyVariantAccess<E> {
+    marker: PhantomData<E>,
+}
+
+impl_copy_clone!(UnitOnlyVariantAccess);
+
+impl<E> Debug for UnitOnlyVariantAccess<E> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("UnitOnlyVariantAccess").finish()
+    }
+}
+
+impl<'de, E> de::VariantAccess<'de> for UnitOnlyVariantAccess<E>
+where
+    E: de::Error,
+{
+    type Error = E;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, _seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        Err(de::Error::invalid_type(
+            de::Unexpected::UnitVariant,
+            &"newtype variant",
+        ))
+    }
+
+    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(de::Error::invalid_type(
+            de::Unexpected::UnitVariant,
+            &"tuple variant",
+        ))
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(de::Error::invalid_type(
+            de::Unexpected::UnitVariant,
+            &"struct variant",
+        ))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
 mod private {
     use crate::lib::*;
 
@@ -0,0 +1,123 @@
+//! Deterministic handling of floating point values for canonical formats.
+//!
+//! The default `Serialize` and `Deserialize` impls for `f32` and `f64` pass
+//! values through bit-for-bit, including negative zero and every NaN bit
+//! pattern. Formats that hash or compare encoded output — content-addressed
+//! storage, consensus protocols, canonical CBOR — need those values
+//! normalized or rejected consistently. The modules here plug into the
+//! `with` attribute so such systems do not need to fork float handling:
+//!
+//! ```edition2021
+//! use serde_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Measurement {
+//!     #[serde(with = "serde::float::canonical_nan")]
+//!     value: f64,
+//!     #[serde(with = "serde::float::reject_nan")]
+//!     weight: f32,
+//! }
+//! ```
+
+use crate::lib::*;
+
+use crate::de::{self, Deserialize, Deserializer};
+use crate::ser::{self, Serialize, Serializer};
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for f32 {}
+    impl Sealed for f64 {}
+}
+
+/// A floating point type that can be canonicalized. Implemented for `f32`
+/// and `f64`, and not intended to be implemented outside of serde.
+pub trait Float: private::Sealed + Copy + Serialize {
+    /// The canonical form of this value: any NaN becomes the positive quiet
+    /// NaN and negative zero becomes positive zero. All other values are
+    /// returned unchanged.
+    fn canonical(self) -> Self;
+
+    /// Whether this value is NaN.
+    fn is_nan(self) -> bool;
+}
+
+macro_rules! impl_float {
+    ($ty:ident) => {
+        impl Float for $ty {
+            fn canonical(self) -> Self {
+                if Float::is_nan(self) {
+                    $ty::NAN
+                } else if self == 0.0 {
+                    0.0
+                } else {
+                    self
+                }
+            }
+
+            fn is_nan(self) -> bool {
+                $ty::is_nan(self)
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
+
+/// Serialize and deserialize floats in canonical form: every NaN as the
+/// positive quiet NaN, and negative zero as positive zero.
+pub mod canonical_nan {
+    use super::*;
+
+    /// Serializes the canonical form of `value`.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Float,
+        S: Serializer,
+    {
+        value.canonical().serialize(serializer)
+    }
+
+    /// Deserializes a float and canonicalizes it.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Float + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Float::canonical)
+    }
+}
+
+/// Serialize and deserialize floats, treating NaN as an error in both
+/// directions.
+pub mod reject_nan {
+    use super::*;
+
+    /// Serializes `value`, failing if it is NaN.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Float,
+        S: Serializer,
+    {
+        if value.is_nan() {
+            Err(ser::Error::custom("NaN is not an allowed value"))
+        } else {
+            value.serialize(serializer)
+        }
+    }
+
+    /// Deserializes a float, failing if the input is NaN.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Float + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let value = tri!(T::deserialize(deserializer));
+        if value.is_nan() {
+            Err(de::Error::custom("NaN is not an allowed value"))
+        } else {
+            Ok(value)
+        }
+    }
+}
@@ -0,0 +1,479 @@
+//! Helper modules for use with the `#[serde(with = "...")]` attribute.
+
+/// Serialize and deserialize a map through string keys.
+///
+/// Formats like JSON only support string keys in maps, so maps keyed by
+/// integers or other `Display` types cannot be serialized directly. This
+/// module converts keys to strings with [`Display`] on the way out and parses
+/// them back with [`FromStr`] on the way in, while values pass through
+/// unchanged.
+///
+/// ```edition2021
+/// use serde_derive::{Deserialize, Serialize};
+/// use std::collections::HashMap;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Scores {
+///     #[serde(with = "serde::helpers::map_keys_as_strings")]
+///     by_user_id: HashMap<u64, u32>,
+/// }
+/// ```
+///
+/// [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+/// [`FromStr`]: https://doc.rust-lang.org/std/str/trait.FromStr.html
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+pub mod map_keys_as_strings {
+    use crate::lib::*;
+
+    use crate::de::{self, size_hint, Deserialize, Deserializer, MapAccess, Visitor};
+    use crate::ser::{Serialize, Serializer};
+
+    struct KeyAsString<'a, K: ?Sized>(&'a K);
+
+    impl<'a, K> Serialize for KeyAsString<'a, K>
+    where
+        K: ?Sized + Display,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_str(self.0)
+        }
+    }
+
+    /// Serializes each key with its `Display` impl.
+    pub fn serialize<M, K, V, S>(map: &M, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        M: ?Sized,
+        for<'a> &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+        K: Display,
+        V: Serialize,
+        S: Serializer,
+    {
+        serializer.collect_map(
+            map.into_iter()
+                .map(|(key, value)| (KeyAsString(key), value)),
+        )
+    }
+
+    /// Deserializes string keys and parses each with `FromStr`.
+    pub fn deserialize<'de, M, K, V, D>(deserializer: D) -> Result<M, D::Error>
+    where
+        M: iter::FromIterator<(K, V)>,
+        K: str::FromStr,
+        K::Err: Display,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        struct MapKeysVisitor<M, K, V> {
+            marker: PhantomData<fn() -> (M, K, V)>,
+        }
+
+        impl<'de, M, K, V> Visitor<'de> for MapKeysVisitor<M, K, V>
+        where
+            M: iter::FromIterator<(K, V)>,
+            K: str::FromStr,
+            K::Err: Display,
+            V: Deserialize<'de>,
+        {
+            type Value = M;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map with string keys")
+            }
+
+            fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut entries =
+                    Vec::with_capacity(size_hint::cautious::<(K, V)>(access.size_hint()));
+                while let Some((key, value)) = tri!(access.next_entry::<Cow<str>, V>()) {
+                    let key = tri!(K::from_str(&key).map_err(de::Error::custom));
+                    entries.push((key, value));
+                }
+                Ok(entries.into_iter().collect())
+            }
+        }
+
+        deserializer.deserialize_map(MapKeysVisitor {
+            marker: PhantomData,
+        })
+    }
+}
+
+/// Deserialize a sequence, skipping elements that fail to deserialize.
+///
+/// One malformed element — most commonly an internally tagged enum with an
+/// unrecognized tag — normally aborts deserialization of the whole sequence.
+/// For log and event ingestion it is often preferable to drop such elements
+/// and keep the rest. Each element is buffered and deserialized individually;
+/// elements that fail are discarded. Serialization passes the sequence
+/// through unchanged.
+///
+/// ```edition2021
+/// use serde_derive::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// #[serde(tag = "type")]
+/// enum Event {
+///     Created { id: u32 },
+///     Deleted { id: u32 },
+/// }
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Log {
+///     #[serde(with = "serde::helpers::skip_undecodable")]
+///     events: Vec<Event>,
+/// }
+/// ```
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+pub mod skip_undecodable {
+    use crate::lib::*;
+
+    use crate::__private::de::{Content, ContentDeserializer};
+    use crate::de::{size_hint, Deserialize, Deserializer, SeqAccess, Visitor};
+    use crate::ser::{Serialize, Serializer};
+
+    /// Serializes the sequence unchanged.
+    pub fn serialize<T, S>(elements: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        elements.serialize(serializer)
+    }
+
+    /// Deserializes each element individually, dropping any that fail.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        struct SkipVisitor<T> {
+            marker: PhantomData<fn() -> T>,
+        }
+
+        impl<'de, T> Visitor<'de> for SkipVisitor<T>
+        where
+            T: Deserialize<'de>,
+        {
+            type Value = Vec<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a sequence")
+            }
+
+            fn visit_seq<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut elements = Vec::with_capacity(size_hint::cautious::<T>(access.size_hint()));
+                while let Some(content) = tri!(access.next_element::<Content>()) {
+                    if let Ok(element) =
+                        T::deserialize(ContentDeserializer::<A::Error>::new(content))
+                    {
+                        elements.push(element);
+                    }
+                }
+                Ok(elements)
+            }
+        }
+
+        deserializer.deserialize_seq(SkipVisitor {
+            marker: PhantomData,
+        })
+    }
+}
+
+/// Serialize and deserialize a `Cow<'a, [T]>` slice of arbitrary elements.
+///
+/// Serde's data model has borrowed visits only for `str` and `[u8]`, so a
+/// `Cow<'a, [T]>` of any other element type can never come out of
+/// deserialization as `Cow::Borrowed`; there is no typed-slice parallel to
+/// `visit_borrowed_bytes` for a format to call. This module makes that
+/// clone-on-write protocol explicit at the field: serialization writes the
+/// slice as a sequence whether borrowed or owned, and deserialization always
+/// collects into `Cow::Owned`. For `Cow<'a, str>` and `Cow<'a, [u8]>` use
+/// `#[serde(borrow)]` instead, which does borrow when the format allows it.
+///
+/// ```edition2021
+/// use serde_derive::{Deserialize, Serialize};
+/// use std::borrow::Cow;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Samples<'a> {
+///     #[serde(with = "serde::helpers::cow_slice", borrow)]
+///     readings: Cow<'a, [u16]>,
+/// }
+/// ```
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+pub mod cow_slice {
+    use crate::lib::*;
+
+    use crate::de::{size_hint, Deserialize, Deserializer, SeqAccess, Visitor};
+    use crate::ser::{Serialize, Serializer};
+
+    /// Serializes the slice as a sequence, whether borrowed or owned.
+    pub fn serialize<T, S>(slice: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(slice)
+    }
+
+    /// Deserializes a sequence of elements into `Cow::Owned`.
+    pub fn deserialize<'de, 'a, T, D>(deserializer: D) -> Result<Cow<'a, [T]>, D::Error>
+    where
+        T: Deserialize<'de> + Clone,
+        D: Deserializer<'de>,
+    {
+        struct CowSliceVisitor<T> {
+            marker: PhantomData<fn() -> T>,
+        }
+
+        impl<'de, T> Visitor<'de> for CowSliceVisitor<T>
+        where
+            T: Deserialize<'de>,
+        {
+            type Value = Vec<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a sequence")
+            }
+
+            fn visit_seq<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut elements = Vec::with_capacity(size_hint::cautious::<T>(access.size_hint()));
+                while let Some(element) = tri!(access.next_element()) {
+                    elements.push(element);
+                }
+                Ok(elements)
+            }
+        }
+
+        deserializer
+            .deserialize_seq(CowSliceVisitor {
+                marker: PhantomData,
+            })
+            .map(Cow::Owned)
+    }
+}
+
+/// Deserialize a string leniently, replacing invalid UTF-8 sequences.
+///
+/// Strings in legacy data are not always valid UTF-8, and a hard error on
+/// one bad byte can be unacceptable when ingesting such data. With this
+/// module, byte input that is not valid UTF-8 is converted with
+/// `String::from_utf8_lossy`, replacing invalid sequences with U+FFFD
+/// instead of failing. Well-formed string input and serialization behave
+/// exactly as they do for a plain `String` field.
+///
+/// ```edition2021
+/// use serde_derive::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Record {
+///     #[serde(with = "serde::helpers::lossy_utf8")]
+///     message: String,
+/// }
+/// ```
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+pub mod lossy_utf8 {
+    use crate::lib::*;
+
+    use crate::de::{Deserializer, Visitor};
+    use crate::ser::Serializer;
+
+    /// Serializes the string unchanged.
+    pub fn serialize<S>(string: &str, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(string)
+    }
+
+    /// Deserializes a string, replacing invalid UTF-8 in byte input.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct LossyStringVisitor;
+
+        impl<'de> Visitor<'de> for LossyStringVisitor {
+            type Value = String;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(v.to_owned())
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+                Ok(v)
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(String::from_utf8_lossy(v).into_owned())
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(match String::from_utf8(v) {
+                    Ok(string) => string,
+                    Err(err) => String::from_utf8_lossy(err.as_bytes()).into_owned(),
+                })
+            }
+        }
+
+        deserializer.deserialize_string(LossyStringVisitor)
+    }
+}
+
+/// Serialize and deserialize `Box<[u8]>` as bytes rather than as a sequence.
+///
+/// The blanket `Box<[T]>` impls treat `Box<[u8]>` as a sequence of
+/// individual integers, which cannot take advantage of a format's byte
+/// string representation. With this module the field serializes through
+/// `serialize_bytes`, and deserialization takes ownership of the buffer a
+/// format hands to `visit_byte_buf` instead of copying it element by
+/// element. (`Box<str>` needs no such helper: it deserializes through
+/// `String`, whose impl already takes ownership in `visit_string`.)
+///
+/// Sequence input remains accepted for compatibility with data written by
+/// the blanket impl.
+///
+/// ```edition2021
+/// use serde_derive::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Packet {
+///     #[serde(with = "serde::helpers::boxed_bytes")]
+///     payload: Box<[u8]>,
+/// }
+/// ```
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+pub mod boxed_bytes {
+    use crate::lib::*;
+
+    use crate::de::{size_hint, Deserializer, SeqAccess, Visitor};
+    use crate::ser::Serializer;
+
+    /// Serializes the bytes with `serialize_bytes`.
+    pub fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(bytes)
+    }
+
+    /// Deserializes a byte array, reusing the format's owned buffer when one
+    /// is offered.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Box<[u8]>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct BoxedBytesVisitor;
+
+        impl<'de> Visitor<'de> for BoxedBytesVisitor {
+            type Value = Box<[u8]>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a byte array")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Ok(Box::from(v))
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+                Ok(v.into_boxed_slice())
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(Box::from(v.as_bytes()))
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+                Ok(v.into_bytes().into_boxed_slice())
+            }
+
+            fn visit_seq<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut bytes = Vec::with_capacity(size_hint::cautious::<u8>(access.size_hint()));
+                while let Some(byte) = tri!(access.next_element()) {
+                    bytes.push(byte);
+                }
+                Ok(bytes.into_boxed_slice())
+            }
+        }
+
+        deserializer.deserialize_byte_buf(BoxedBytesVisitor)
+    }
+}
+
+/// Serialize and deserialize `OsString` portably as lossy UTF-8.
+///
+/// The default impls for [`OsString`] preserve the platform encoding
+/// exactly — Unix bytes or Windows UTF-16 code units, tagged with the
+/// platform — which makes the data unreadable on the other platform. With
+/// this module the value is serialized as a plain string, converting with
+/// [`to_string_lossy`] on the way out, so the data can be exchanged across
+/// machines.
+///
+/// The tradeoff is exactness: file names that are not valid Unicode have
+/// their unencodable sequences replaced with U+FFFD and do not round-trip.
+/// Use the default impls where the data stays on one platform and must be
+/// bit-exact, and this module for cross-platform tooling where paths are
+/// expected to be Unicode.
+///
+/// ```edition2021
+/// use serde_derive::{Deserialize, Serialize};
+/// use std::ffi::OsString;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Manifest {
+///     #[serde(with = "serde::helpers::os_string_lossy")]
+///     source: OsString,
+/// }
+/// ```
+///
+/// [`OsString`]: std::ffi::OsString
+/// [`to_string_lossy`]: std::ffi::OsStr::to_string_lossy
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod os_string_lossy {
+    use crate::lib::*;
+
+    use crate::de::{Deserialize, Deserializer};
+    use crate::ser::Serializer;
+
+    /// Serializes the value as a string, replacing non-Unicode sequences
+    /// with U+FFFD.
+    pub fn serialize<S>(os_str: &OsStr, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(&os_str.to_string_lossy())
+    }
+
+    /// Deserializes a string into an `OsString`.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<OsString, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(OsString::from)
+    }
+}
@@ -114,7 +114,7 @@
     // clippy bug: https://github.com/rust-lang/rust-clippy/issues/7768
     clippy::semicolon_if_nothing_returned,
     // not available in our oldest supported compiler
-    clippy::empty_enum,
+    clippy::empty_enums,
     clippy::type_repetition_in_bounds, // https://github.com/rust-lang/rust-clippy/issues/8772
     // integer and float ser/de requires these sorts of casts
     clippy::cast_possible_truncation,
@@ -189,7 +189,7 @@ mod lib {
     pub use self::core::fmt::{self, Debug, Display, Write as FmtWrite};
     pub use self::core::marker::{self, PhantomData};
     pub use self::core::num::Wrapping;
-    pub use self::core::ops::{Bound, Range, RangeFrom, RangeInclusive, RangeTo};
+    pub use self::core::ops::{Bound, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo};
     pub use self::core::option;
     pub use self::core::result;
     pub use self::core::time::Duration;
@@ -308,8 +308,26 @@ mod macros;
 #[macro_use]
 mod integer128;
 
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+pub mod convenience;
 pub mod de;
+pub mod float;
+pub mod helpers;
+pub mod num;
+#[cfg(all(feature = "rc", any(feature = "std", feature = "alloc")))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(feature = "rc", any(feature = "std", feature = "alloc"))))
+)]
+pub mod rc;
 pub mod ser;
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+pub mod small_string;
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+mod unknown_fields;
 
 mod format;
 
@@ -317,6 +335,9 @@ mod format;
 pub use crate::de::{Deserialize, Deserializer};
 #[doc(inline)]
 pub use crate::ser::{Serialize, Serializer};
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+pub use crate::unknown_fields::UnknownFields;
 
 // Used by generated code and doc tests. Not public API.
 #[doc(hidden)]
@@ -346,3 +367,12 @@ pub use serde_derive::{Deserialize, Serialize};
 mod actually_private {
     pub struct T;
 }
+
+// No-op when the derive macro's generated code is built against the serde
+// facade crate, as opposed to serde_core which turns this into a compile
+// error. Not public API.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __require_serde_not_serde_core {
+    () => {};
+}
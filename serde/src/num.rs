@@ -0,0 +1,638 @@
+//! A unified numeric carrier for lossless transcoding.
+//!
+//! Middleware that rewrites or inspects values, and catch-all fields in
+//! loosely typed schemas, often have to hold "whatever number the format
+//! produced" without committing to a single primitive early. Picking `f64`
+//! loses large integers; picking `i64` loses fractions and large unsigned
+//! values. [`Number`] stores any value visited through the numeric methods
+//! of [`Visitor`] exactly, and serializes it back out through the matching
+//! `Serializer` method.
+//!
+//! ```edition2021
+//! use serde::num::Number;
+//! use serde_derive::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Reading {
+//!     sensor: String,
+//!     // Accepts 17, -4, 2.5, or an integer beyond the range of i64,
+//!     // and writes it back out unchanged.
+//!     value: Number,
+//! }
+//! ```
+//!
+//! [`Visitor`]: crate::de::Visitor
+//!
+//! The [`saturating`] and [`clamp`] modules plug into the `with` attribute
+//! for fields that should accept out-of-range numbers by clamping them to
+//! the target type instead of rejecting the whole value.
+
+use crate::lib::*;
+
+use crate::de::{self, Deserialize, Deserializer, Unexpected, Visitor};
+use crate::ser::{Serialize, Serializer};
+
+/// A number deserialized from any format, stored losslessly.
+///
+/// Integers are normalized so that equal values compare equal regardless of
+/// the visitor method they arrived through: non-negative integers are stored
+/// as `u64` when they fit, negative integers as `i64` when they fit, and
+/// 128-bit storage is used only for values outside those ranges. Floats are
+/// kept as `f64` and never conflated with integers — `Number::from(1u8)`
+/// and `Number::from(1.0)` are not equal.
+#[derive(Clone, PartialEq)]
+pub struct Number {
+    n: N,
+}
+
+#[derive(Clone, PartialEq)]
+enum N {
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    I128(i128),
+    U128(u128),
+    /// The exact decimal text of a number that a format chose not to bind
+    /// to a primitive, such as an arbitrary precision value.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    Decimal(String),
+}
+
+impl Number {
+    fn from_unsigned(value: u128) -> Self {
+        let n = if value <= u64::MAX as u128 {
+            N::U64(value as u64)
+        } else {
+            N::U128(value)
+        };
+        Number { n }
+    }
+
+    fn from_signed(value: i128) -> Self {
+        if value >= 0 {
+            Number::from_unsigned(value as u128)
+        } else if value >= i64::MIN as i128 {
+            Number {
+                n: N::I64(value as i64),
+            }
+        } else {
+            Number { n: N::I128(value) }
+        }
+    }
+
+    /// Creates a `Number` holding the exact decimal text of a number, for
+    /// formats that support higher precision than any primitive.
+    ///
+    /// Returns `None` unless `text` is an optionally signed decimal number
+    /// with an optional fraction and exponent, like `-11.5` or `3e300`.
+    /// A `Number` built this way serializes the text with `serialize_str`;
+    /// values that fit a primitive should use the `From` impls instead.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+    pub fn from_decimal_str(text: &str) -> Option<Self> {
+        if is_decimal(text) {
+            Some(Number {
+                n: N::Decimal(text.to_owned()),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Whether this number is an integer in the range of `i64`.
+    pub fn is_i64(&self) -> bool {
+        self.as_i64().is_some()
+    }
+
+    /// Whether this number is an integer in the range of `u64`.
+    pub fn is_u64(&self) -> bool {
+        self.as_u64().is_some()
+    }
+
+    /// Whether this number is a float.
+    pub fn is_f64(&self) -> bool {
+        match self.n {
+            N::F64(_) => true,
+            _ => false,
+        }
+    }
+
+    /// The value as an `i64` if it is an integer in range.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.n {
+            N::I64(v) => Some(v),
+            N::U64(v) if v <= i64::MAX as u64 => Some(v as i64),
+            _ => None,
+        }
+    }
+
+    /// The value as a `u64` if it is a non-negative integer in range.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.n {
+            N::U64(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// The value as an `i128` if it is an integer in range.
+    pub fn as_i128(&self) -> Option<i128> {
+        match self.n {
+            N::I64(v) => Some(v as i128),
+            N::U64(v) => Some(v as i128),
+            N::I128(v) => Some(v),
+            N::U128(v) if v <= i128::MAX as u128 => Some(v as i128),
+            _ => None,
+        }
+    }
+
+    /// The value as a `u128` if it is a non-negative integer.
+    pub fn as_u128(&self) -> Option<u128> {
+        match self.n {
+            N::U64(v) => Some(v as u128),
+            N::U128(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// The value as an `f64`. Floats are returned exactly; integers are
+    /// converted, rounding if they exceed 53 bits; decimal text is not
+    /// parsed and returns `None`.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.n {
+            N::I64(v) => Some(v as f64),
+            N::U64(v) => Some(v as f64),
+            N::F64(v) => Some(v),
+            N::I128(v) => Some(v as f64),
+            N::U128(v) => Some(v as f64),
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            N::Decimal(_) => None,
+        }
+    }
+
+    /// The exact decimal text, if this number was built from one.
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+    pub fn as_decimal_str(&self) -> Option<&str> {
+        match self.n {
+            N::Decimal(ref text) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! from_signed {
+    ($($ty:ident)*) => {
+        $(
+            impl From<$ty> for Number {
+                fn from(value: $ty) -> Self {
+                    Number::from_signed(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! from_unsigned {
+    ($($ty:ident)*) => {
+        $(
+            impl From<$ty> for Number {
+                fn from(value: $ty) -> Self {
+                    Number::from_unsigned(value as u128)
+                }
+            }
+        )*
+    };
+}
+
+from_signed!(i8 i16 i32 i64 isize i128);
+from_unsigned!(u8 u16 u32 u64 usize u128);
+
+impl From<f32> for Number {
+    fn from(value: f32) -> Self {
+        Number {
+            n: N::F64(value as f64),
+        }
+    }
+}
+
+impl From<f64> for Number {
+    fn from(value: f64) -> Self {
+        Number { n: N::F64(value) }
+    }
+}
+
+impl Display for Number {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.n {
+            N::I64(v) => Display::fmt(&v, formatter),
+            N::U64(v) => Display::fmt(&v, formatter),
+            N::F64(v) => Display::fmt(&v, formatter),
+            N::I128(v) => Display::fmt(&v, formatter),
+            N::U128(v) => Display::fmt(&v, formatter),
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            N::Decimal(ref text) => formatter.write_str(text),
+        }
+    }
+}
+
+impl Debug for Number {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_tuple("Number")
+            .field(&format_args!("{}", self))
+            .finish()
+    }
+}
+
+impl Serialize for Number {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.n {
+            N::I64(v) => serializer.serialize_i64(v),
+            N::U64(v) => serializer.serialize_u64(v),
+            N::F64(v) => serializer.serialize_f64(v),
+            N::I128(v) => serializer.serialize_i128(v),
+            N::U128(v) => serializer.serialize_u128(v),
+            #[cfg(any(feature = "std", feature = "alloc"))]
+            N::Decimal(ref text) => serializer.serialize_str(text),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Number {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct NumberVisitor;
+
+        impl<'de> Visitor<'de> for NumberVisitor {
+            type Value = Number;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a number")
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Number, E> {
+                Ok(Number::from(v))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Number, E> {
+                Ok(Number::from(v))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<Number, E> {
+                Ok(Number::from(v))
+            }
+
+            fn visit_i128<E>(self, v: i128) -> Result<Number, E> {
+                Ok(Number::from(v))
+            }
+
+            fn visit_u128<E>(self, v: u128) -> Result<Number, E> {
+                Ok(Number::from(v))
+            }
+        }
+
+        deserializer.deserialize_any(NumberVisitor)
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+    impl Sealed for i8 {}
+    impl Sealed for i16 {}
+    impl Sealed for i32 {}
+    impl Sealed for i64 {}
+    impl Sealed for i128 {}
+    impl Sealed for isize {}
+    impl Sealed for u8 {}
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
+    impl Sealed for u64 {}
+    impl Sealed for u128 {}
+    impl Sealed for usize {}
+}
+
+/// An integer type that can be deserialized with saturation. Implemented
+/// for the primitive integer types, and not intended to be implemented
+/// outside of serde.
+pub trait Integer: private::Sealed + Copy + Serialize {
+    /// Calls the `Deserializer` method matching this type's signedness and
+    /// width with the given visitor.
+    #[doc(hidden)]
+    fn drive<'de, D, V>(deserializer: D, visitor: V) -> Result<V::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+        V: Visitor<'de>;
+
+    /// The nearest representable value to the given integer.
+    #[doc(hidden)]
+    fn from_i128_saturating(value: i128) -> Self;
+
+    /// The nearest representable value to the given integer.
+    #[doc(hidden)]
+    fn from_u128_saturating(value: u128) -> Self;
+
+    /// The given float truncated toward zero and clamped into range, or
+    /// `None` if it is NaN.
+    #[doc(hidden)]
+    fn from_f64_clamped(value: f64) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    (signed $ty:ident, $deserialize:ident) => {
+        impl Integer for $ty {
+            impl_integer!(common $deserialize);
+
+            fn from_i128_saturating(value: i128) -> Self {
+                if value < $ty::MIN as i128 {
+                    $ty::MIN
+                } else if value > $ty::MAX as i128 {
+                    $ty::MAX
+                } else {
+                    value as $ty
+                }
+            }
+
+            fn from_u128_saturating(value: u128) -> Self {
+                if value > $ty::MAX as u128 {
+                    $ty::MAX
+                } else {
+                    value as $ty
+                }
+            }
+
+            impl_integer!(clamped $ty);
+        }
+    };
+
+    (unsigned $ty:ident, $deserialize:ident) => {
+        impl Integer for $ty {
+            impl_integer!(common $deserialize);
+
+            fn from_i128_saturating(value: i128) -> Self {
+                if value < 0 {
+                    0
+                } else {
+                    $ty::from_u128_saturating(value as u128)
+                }
+            }
+
+            fn from_u128_saturating(value: u128) -> Self {
+                if value > $ty::MAX as u128 {
+                    $ty::MAX
+                } else {
+                    value as $ty
+                }
+            }
+
+            impl_integer!(clamped $ty);
+        }
+    };
+
+    (common $deserialize:ident) => {
+        fn drive<'de, D, V>(deserializer: D, visitor: V) -> Result<V::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+            V: Visitor<'de>,
+        {
+            deserializer.$deserialize(visitor)
+        }
+    };
+
+    (clamped $ty:ident) => {
+        fn from_f64_clamped(value: f64) -> Option<Self> {
+            if value.is_nan() {
+                None
+            } else {
+                // `as` saturates at the bounds of the target type.
+                Some(value as $ty)
+            }
+        }
+    };
+}
+
+impl_integer!(signed i8, deserialize_i8);
+impl_integer!(signed i16, deserialize_i16);
+impl_integer!(signed i32, deserialize_i32);
+impl_integer!(signed i64, deserialize_i64);
+impl_integer!(signed i128, deserialize_i128);
+impl_integer!(signed isize, deserialize_i64);
+impl_integer!(unsigned u8, deserialize_u8);
+impl_integer!(unsigned u16, deserialize_u16);
+impl_integer!(unsigned u32, deserialize_u32);
+impl_integer!(unsigned u64, deserialize_u64);
+impl_integer!(unsigned u128, deserialize_u128);
+impl_integer!(unsigned usize, deserialize_u64);
+
+/// Deserialize an integer, saturating instead of erroring when the input is
+/// outside the range of the target type.
+///
+/// An input of 300 deserializes into a `u8` field as 255, and -1 as 0.
+/// Useful for telemetry ingestion where a clipped reading is better than
+/// rejecting the whole record. Serialization passes the value through
+/// unchanged. Non-integer input is still an error; see [`clamp`] to accept
+/// floats as well.
+///
+/// ```edition2021
+/// use serde_derive::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Sample {
+///     #[serde(with = "serde::num::saturating")]
+///     level: u8,
+/// }
+/// ```
+///
+/// [`clamp`]: crate::num::clamp
+pub mod saturating {
+    use super::*;
+
+    /// Serializes `value` unchanged.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Integer,
+        S: Serializer,
+    {
+        value.serialize(serializer)
+    }
+
+    /// Deserializes an integer, saturating at the bounds of `T`.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Integer,
+        D: Deserializer<'de>,
+    {
+        T::drive(deserializer, SaturatingVisitor(PhantomData))
+    }
+
+    pub(super) struct SaturatingVisitor<T>(pub(super) PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for SaturatingVisitor<T>
+    where
+        T: Integer,
+    {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an integer")
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<T, E> {
+            Ok(T::from_i128_saturating(v as i128))
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<T, E> {
+            Ok(T::from_u128_saturating(v as u128))
+        }
+
+        fn visit_i128<E>(self, v: i128) -> Result<T, E> {
+            Ok(T::from_i128_saturating(v))
+        }
+
+        fn visit_u128<E>(self, v: u128) -> Result<T, E> {
+            Ok(T::from_u128_saturating(v))
+        }
+    }
+}
+
+/// Deserialize an integer like [`saturating`], additionally accepting
+/// floats by truncating them toward zero and clamping them into range.
+///
+/// An input of 2.7 deserializes into a `u8` field as 2, and 1e99 as 255.
+/// NaN has no nearest integer and is still an error. Serialization passes
+/// the value through unchanged.
+///
+/// ```edition2021
+/// use serde_derive::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Sample {
+///     #[serde(with = "serde::num::clamp")]
+///     level: u8,
+/// }
+/// ```
+///
+/// [`saturating`]: crate::num::saturating
+pub mod clamp {
+    use super::*;
+
+    /// Serializes `value` unchanged.
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Integer,
+        S: Serializer,
+    {
+        value.serialize(serializer)
+    }
+
+    /// Deserializes a number, clamping it into the range of `T`.
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Integer,
+        D: Deserializer<'de>,
+    {
+        T::drive(deserializer, ClampingVisitor(PhantomData))
+    }
+
+    struct ClampingVisitor<T>(PhantomData<T>);
+
+    impl<'de, T> Visitor<'de> for ClampingVisitor<T>
+    where
+        T: Integer,
+    {
+        type Value = T;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a number")
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            super::saturating::SaturatingVisitor(PhantomData).visit_i64(v)
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            super::saturating::SaturatingVisitor(PhantomData).visit_u64(v)
+        }
+
+        fn visit_i128<E>(self, v: i128) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            super::saturating::SaturatingVisitor(PhantomData).visit_i128(v)
+        }
+
+        fn visit_u128<E>(self, v: u128) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            super::saturating::SaturatingVisitor(PhantomData).visit_u128(v)
+        }
+
+        fn visit_f64<E>(self, v: f64) -> Result<T, E>
+        where
+            E: de::Error,
+        {
+            match T::from_f64_clamped(v) {
+                Some(value) => Ok(value),
+                None => Err(de::Error::invalid_value(
+                    Unexpected::Float(v),
+                    &"a number that is not NaN",
+                )),
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+fn is_decimal(text: &str) -> bool {
+    let mut bytes = text.as_bytes();
+    if bytes.first() == Some(&b'-') || bytes.first() == Some(&b'+') {
+        bytes = &bytes[1..];
+    }
+    bytes = match eat_digits(bytes) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    if bytes.first() == Some(&b'.') {
+        bytes = match eat_digits(&bytes[1..]) {
+            Some(rest) => rest,
+            None => return false,
+        };
+    }
+    if bytes.first() == Some(&b'e') || bytes.first() == Some(&b'E') {
+        bytes = &bytes[1..];
+        if bytes.first() == Some(&b'-') || bytes.first() == Some(&b'+') {
+            bytes = &bytes[1..];
+        }
+        bytes = match eat_digits(bytes) {
+            Some(rest) => rest,
+            None => return false,
+        };
+    }
+    bytes.is_empty()
+}
+
+/// Consumes at least one leading ASCII digit, returning the rest of the
+/// input, or `None` if there is no leading digit.
+#[cfg(any(feature = "std", feature = "alloc"))]
+fn eat_digits(bytes: &[u8]) -> Option<&[u8]> {
+    let mut digits = 0;
+    while digits < bytes.len() && bytes[digits].is_ascii_digit() {
+        digits += 1;
+    }
+    if digits == 0 {
+        None
+    } else {
+        Some(&bytes[digits..])
+    }
+}
@@ -2,12 +2,12 @@ use crate::lib::*;
 
 use crate::de::value::{BorrowedBytesDeserializer, BytesDeserializer};
 use crate::de::{
-    Deserialize, DeserializeSeed, Deserializer, EnumAccess, Error, IntoDeserializer, VariantAccess,
-    Visitor,
+    Deserialize, DeserializeSeed, Deserializer, EnumAccess, Error, IntoDeserializer, MapAccess,
+    SeqAccess, VariantAccess, Visitor,
 };
 
 #[cfg(any(feature = "std", feature = "alloc"))]
-use crate::de::{MapAccess, Unexpected};
+use crate::de::Unexpected;
 
 #[cfg(any(feature = "std", feature = "alloc"))]
 pub use self::content::{
@@ -18,6 +18,90 @@ pub use self::content::{
 
 pub use crate::seed::InPlaceSeed;
 
+/// Used by generated code for `#[serde(bytes)]` on `[u8; N]` fields.
+/// Not public API.
+#[cfg(not(no_const_generics))]
+pub fn deserialize_byte_array<'de, D, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct ByteArrayVisitor<const N: usize>;
+
+    impl<'de, const N: usize> Visitor<'de> for ByteArrayVisitor<N> {
+        type Value = [u8; N];
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "a byte array of length {}", N)
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            if v.len() == N {
+                let mut bytes = [0; N];
+                bytes.copy_from_slice(v);
+                Ok(bytes)
+            } else {
+                Err(Error::invalid_length(v.len(), &self))
+            }
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut bytes = [0; N];
+            for (idx, byte) in bytes.iter_mut().enumerate() {
+                *byte = match tri!(seq.next_element()) {
+                    Some(value) => value,
+                    None => return Err(Error::invalid_length(idx, &self)),
+                };
+            }
+            Ok(bytes)
+        }
+    }
+
+    deserializer.deserialize_bytes(ByteArrayVisitor::<N>)
+}
+
+/// Used by generated code for `#[serde(require_only(expect = "..."))]`
+/// fields. Consumes a string value and fails unless it is equal to the
+/// expected literal. Not public API.
+pub struct ExpectStr {
+    pub expected: &'static str,
+}
+
+impl<'de> DeserializeSeed<'de> for ExpectStr {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ExpectStr {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "the string {:?}", self.expected)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        if v == self.expected {
+            Ok(())
+        } else {
+            Err(Error::invalid_value(crate::de::Unexpected::Str(v), &self))
+        }
+    }
+}
+
 /// If the missing field is of type `Option<T>` then treat is as `None`,
 /// otherwise it is an error.
 pub fn missing_field<'de, V, E>(field: &'static str) -> Result<V, E>
@@ -194,6 +278,151 @@ where
         .map(From::from)
 }
 
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub fn borrow_cow_str_option<'de: 'a, 'a, D, R>(deserializer: D) -> Result<Option<R>, D::Error>
+where
+    D: Deserializer<'de>,
+    R: From<Cow<'a, str>>,
+{
+    struct CowStrOptionVisitor;
+
+    impl<'a> Visitor<'a> for CowStrOptionVisitor {
+        type Value = Option<Cow<'a, str>>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an optional string")
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_none<E>(self) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'a>,
+        {
+            borrow_cow_str(deserializer).map(Some)
+        }
+    }
+
+    deserializer
+        .deserialize_option(CowStrOptionVisitor)
+        .map(|opt| opt.map(From::from))
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub fn borrow_cow_bytes_option<'de: 'a, 'a, D, R>(deserializer: D) -> Result<Option<R>, D::Error>
+where
+    D: Deserializer<'de>,
+    R: From<Cow<'a, [u8]>>,
+{
+    struct CowBytesOptionVisitor;
+
+    impl<'a> Visitor<'a> for CowBytesOptionVisitor {
+        type Value = Option<Cow<'a, [u8]>>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("an optional byte array")
+        }
+
+        fn visit_unit<E>(self) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_none<E>(self) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            Ok(None)
+        }
+
+        fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'a>,
+        {
+            borrow_cow_bytes(deserializer).map(Some)
+        }
+    }
+
+    deserializer
+        .deserialize_option(CowBytesOptionVisitor)
+        .map(|opt| opt.map(From::from))
+}
+
+/// Used by generated code for `#[serde(one_or_many)]` on `Vec` fields. A
+/// sequence deserializes element by element; anything else deserializes as a
+/// single element and is promoted to a one-element vector. Not public API.
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub fn one_or_many<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    match tri!(Content::deserialize(deserializer)) {
+        Content::Seq(elements) => elements
+            .into_iter()
+            .map(|element| T::deserialize(ContentDeserializer::new(element)))
+            .collect(),
+        single => {
+            let element = tri!(T::deserialize(ContentDeserializer::new(single)));
+            Ok(iter::once(element).collect())
+        }
+    }
+}
+
+/// Used by generated code for `#[serde(allow_unknown_variants_in = "...")]` to
+/// decide whether a failed enum deserialization rejected the variant tag
+/// itself, as opposed to a recognized variant whose payload was malformed.
+/// Error kinds are not introspectable, so this checks whether the Display
+/// output carries the prefix produced by `Error::unknown_variant`. Not public
+/// API.
+pub fn is_unknown_variant_error<E>(error: &E) -> bool
+where
+    E: Display,
+{
+    struct PrefixMatcher {
+        expected: &'static str,
+        matched: bool,
+    }
+
+    impl fmt::Write for PrefixMatcher {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            // `expected` is ASCII, so slicing it at any byte index is safe.
+            let len = core::cmp::min(s.len(), self.expected.len());
+            if s.as_bytes()[..len] != self.expected.as_bytes()[..len] {
+                return Err(fmt::Error);
+            }
+            self.expected = &self.expected[len..];
+            if self.expected.is_empty() {
+                self.matched = true;
+                // Seen enough; abort the rest of the formatting.
+                return Err(fmt::Error);
+            }
+            Ok(())
+        }
+    }
+
+    let mut matcher = PrefixMatcher {
+        expected: "unknown variant ",
+        matched: false,
+    };
+    let _ = write!(matcher, "{}", error);
+    matcher.matched
+}
+
 #[cfg(any(feature = "std", feature = "alloc"))]
 mod content {
     // This module is private and nothing here should be used outside of
@@ -263,7 +492,7 @@ mod content {
         }
 
         #[cold]
-        fn unexpected(&self) -> Unexpected {
+        fn unexpected(&self) -> Unexpected<'_> {
             match *self {
                 Content::Bool(b) => Unexpected::Bool(b),
                 Content::U8(n) => Unexpected::Unsigned(n as u64),
@@ -2802,3 +3031,378 @@ where
         )
     }
 }
+
+/// Used by generated code when `#[serde(deny_unknown_fields)]` is combined
+/// with `#[serde(from = "...")]` or `#[serde(try_from = "...")]`. The proxy
+/// type's own impl would silently ignore unknown keys, so the attribute is
+/// forwarded by wrapping the deserializer handed to the proxy: map keys of the
+/// outermost struct that match none of the proxy's fields are rejected with an
+/// unknown-field error. Not public API.
+pub struct DenyUnknownFields<D> {
+    de: D,
+}
+
+impl<D> DenyUnknownFields<D> {
+    pub fn new(de: D) -> Self {
+        DenyUnknownFields { de }
+    }
+}
+
+macro_rules! forward_deny_unknown_deserialize {
+    ($($func:ident $(($($arg:ident: $ty:ty),*))*,)*) => {
+        $(
+            fn $func<V>(self $(, $($arg: $ty),*)*, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.de.$func($($($arg,)*)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, D> Deserializer<'de> for DenyUnknownFields<D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    forward_deny_unknown_deserialize! {
+        deserialize_any,
+        deserialize_bool,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_i128,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_u128,
+        deserialize_f32,
+        deserialize_f64,
+        deserialize_char,
+        deserialize_str,
+        deserialize_string,
+        deserialize_bytes,
+        deserialize_byte_buf,
+        deserialize_option,
+        deserialize_unit,
+        deserialize_seq,
+        deserialize_map,
+        deserialize_identifier,
+        deserialize_ignored_any,
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.de.deserialize_struct(
+            name,
+            fields,
+            FieldCheckVisitor {
+                inner: visitor,
+                fields,
+            },
+        )
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+struct FieldCheckVisitor<V> {
+    inner: V,
+    fields: &'static [&'static str],
+}
+
+impl<'de, V> Visitor<'de> for FieldCheckVisitor<V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    // Sequence encodings of a struct carry no field names to check.
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        self.inner.visit_seq(seq)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        self.inner.visit_map(FieldCheckMapAccess {
+            inner: map,
+            fields: self.fields,
+        })
+    }
+}
+
+struct FieldCheckMapAccess<A> {
+    inner: A,
+    fields: &'static [&'static str],
+}
+
+impl<'de, A> MapAccess<'de> for FieldCheckMapAccess<A>
+where
+    A: MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        self.inner.next_key_seed(FieldCheckSeed {
+            inner: seed,
+            fields: self.fields,
+        })
+    }
+
+    fn next_value_seed<T>(&mut self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.inner.next_value_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.inner.size_hint()
+    }
+}
+
+struct FieldCheckSeed<T> {
+    inner: T,
+    fields: &'static [&'static str],
+}
+
+impl<'de, T> DeserializeSeed<'de> for FieldCheckSeed<T>
+where
+    T: DeserializeSeed<'de>,
+{
+    type Value = T::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.inner.deserialize(FieldCheckKeyDeserializer {
+            de: deserializer,
+            fields: self.fields,
+        })
+    }
+}
+
+struct FieldCheckKeyDeserializer<D> {
+    de: D,
+    fields: &'static [&'static str],
+}
+
+macro_rules! forward_field_check_deserialize {
+    ($($func:ident $(($($arg:ident: $ty:ty),*))*,)*) => {
+        $(
+            fn $func<V>(self $(, $($arg: $ty),*)*, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.de.$func(
+                    $($($arg,)*)*
+                    FieldCheckKeyVisitor {
+                        inner: visitor,
+                        fields: self.fields,
+                    },
+                )
+            }
+        )*
+    };
+}
+
+impl<'de, D> Deserializer<'de> for FieldCheckKeyDeserializer<D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    forward_field_check_deserialize! {
+        deserialize_any,
+        deserialize_bool,
+        deserialize_i8,
+        deserialize_i16,
+        deserialize_i32,
+        deserialize_i64,
+        deserialize_i128,
+        deserialize_u8,
+        deserialize_u16,
+        deserialize_u32,
+        deserialize_u64,
+        deserialize_u128,
+        deserialize_f32,
+        deserialize_f64,
+        deserialize_char,
+        deserialize_str,
+        deserialize_string,
+        deserialize_bytes,
+        deserialize_byte_buf,
+        deserialize_option,
+        deserialize_unit,
+        deserialize_seq,
+        deserialize_map,
+        deserialize_identifier,
+        deserialize_ignored_any,
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.de.is_human_readable()
+    }
+}
+
+struct FieldCheckKeyVisitor<V> {
+    inner: V,
+    fields: &'static [&'static str],
+}
+
+impl<V> FieldCheckKeyVisitor<V> {
+    fn check_str<E>(&self, field: &str) -> Result<(), E>
+    where
+        E: Error,
+    {
+        if self.fields.contains(&field) {
+            Ok(())
+        } else {
+            Err(Error::unknown_field(field, self.fields))
+        }
+    }
+
+    fn check_bytes<E>(&self, field: &[u8]) -> Result<(), E>
+    where
+        E: Error,
+    {
+        if self.fields.iter().any(|name| name.as_bytes() == field) {
+            Ok(())
+        } else {
+            Err(Error::unknown_field(
+                &crate::__private::from_utf8_lossy(field),
+                self.fields,
+            ))
+        }
+    }
+}
+
+macro_rules! forward_field_check_scalar {
+    ($($func:ident($ty:ty),)*) => {
+        $(
+            fn $func<E>(self, v: $ty) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                self.inner.$func(v)
+            }
+        )*
+    };
+}
+
+impl<'de, V> Visitor<'de> for FieldCheckKeyVisitor<V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.inner.expecting(formatter)
+    }
+
+    // Keys in formats that identify fields by index or other non-string
+    // values are passed through; there is no name to compare against.
+    forward_field_check_scalar! {
+        visit_bool(bool),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+        visit_char(char),
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        tri!(self.check_str(v));
+        self.inner.visit_str(v)
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        tri!(self.check_str(v));
+        self.inner.visit_borrowed_str(v)
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        tri!(self.check_str(&v));
+        self.inner.visit_string(v)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        tri!(self.check_bytes(v));
+        self.inner.visit_bytes(v)
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        tri!(self.check_bytes(v));
+        self.inner.visit_borrowed_bytes(v)
+    }
+
+    #[cfg(any(feature = "std", feature = "alloc"))]
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        tri!(self.check_bytes(&v));
+        self.inner.visit_byte_buf(v)
+    }
+}
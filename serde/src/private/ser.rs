@@ -1,4 +1,5 @@
 use crate::lib::*;
+use core::borrow::Borrow;
 
 use crate::ser::{self, Impossible, Serialize, SerializeMap, SerializeStruct, Serializer};
 
@@ -13,6 +14,30 @@ pub fn constrain<T: ?Sized>(t: &T) -> &T {
     t
 }
 
+/// Used to check that serde(getter) attributes return the field type, a
+/// reference to it, or anything else that borrows as it, such as a Cow.
+/// Not public API.
+pub fn constrain_borrowed<T, R>(r: &R) -> &T
+where
+    T: ?Sized,
+    R: ?Sized + Borrow<T>,
+{
+    r.borrow()
+}
+
+/// Used by generated code for `#[serde(bytes)]` on `[u8; N]` fields.
+/// Not public API.
+#[cfg(not(no_const_generics))]
+pub fn serialize_byte_array<S, const N: usize>(
+    bytes: &[u8; N],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_bytes(bytes)
+}
+
 /// Not public API.
 pub fn serialize_tagged_newtype<S, T>(
     serializer: S,
@@ -1198,6 +1223,10 @@ where
         self.0.serialize_key(key)
     }
 
+    fn serialize_key_str(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        self.0.serialize_key_str(key)
+    }
+
     fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
         T: ?Sized + Serialize,
@@ -1233,7 +1262,8 @@ where
     where
         T: ?Sized + Serialize,
     {
-        self.0.serialize_entry(key, value)
+        tri!(self.0.serialize_key_str(key));
+        self.0.serialize_value(value)
     }
 
     fn end(self) -> Result<(), Self::Error> {
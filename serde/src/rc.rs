@@ -0,0 +1,197 @@
+//! Serialization policies for weak reference-counted pointers.
+//!
+//! The default `Serialize` impls for [`rc::Weak`] and [`sync::Weak`] write
+//! the result of upgrading as an `Option`, and deserialization always
+//! produces a dangling weak pointer. That is one of several reasonable
+//! policies for graph-like structures; the modules here make the choice
+//! explicit per field through the `with` attribute:
+//!
+//! - [`weak_none`] never traverses the target: a weak back-edge in a cyclic
+//!   structure serializes as none, guaranteeing termination.
+//! - [`weak_upgrade`] is the default policy spelled out: the target value
+//!   when the pointer is alive, none when it is dangling.
+//! - [`weak_reject_dangling`] treats a dangling pointer as a bug and fails
+//!   serialization instead of silently writing none.
+//!
+//! # Cycles
+//!
+//! Serde traverses values as a tree, so a cycle among strong pointers
+//! recurses until the stack or the format's depth limit is exhausted. The
+//! sanctioned pattern for cyclic structures is to serialize the owning
+//! direction strongly and cross the back-edges with `weak_none`, then
+//! re-link the weak pointers after deserialization; a deserialized `Weak`
+//! is always dangling regardless of policy.
+//!
+//! ```edition2021
+//! use serde_derive::{Deserialize, Serialize};
+//! use std::rc::{Rc, Weak};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct TreeNode {
+//!     value: u32,
+//!     children: Vec<Rc<TreeNode>>,
+//!     #[serde(with = "serde::rc::weak_none")]
+//!     parent: Weak<TreeNode>,
+//! }
+//! ```
+//!
+//! [`rc::Weak`]: std::rc::Weak
+//! [`sync::Weak`]: std::sync::Weak
+
+use crate::lib::*;
+use core::ops::Deref;
+
+use crate::de::{Deserialize, Deserializer, IgnoredAny};
+use crate::ser::{self, Serialize, Serializer};
+
+mod private {
+    use crate::lib::*;
+
+    pub trait Sealed {}
+    impl<T> Sealed for RcWeak<T> {}
+    impl<T> Sealed for ArcWeak<T> {}
+}
+
+/// A weak reference-counted pointer. Implemented for [`rc::Weak`] and
+/// [`sync::Weak`], and not intended to be implemented outside of serde.
+///
+/// [`rc::Weak`]: std::rc::Weak
+/// [`sync::Weak`]: std::sync::Weak
+pub trait WeakRef: private::Sealed + Sized {
+    /// The type this pointer refers to.
+    type Target;
+
+    /// The strong pointer produced by a successful upgrade.
+    #[doc(hidden)]
+    type Strong: Deref<Target = Self::Target>;
+
+    #[doc(hidden)]
+    fn upgrade(&self) -> Option<Self::Strong>;
+
+    #[doc(hidden)]
+    fn new() -> Self;
+}
+
+impl<T> WeakRef for RcWeak<T> {
+    type Target = T;
+    type Strong = Rc<T>;
+
+    fn upgrade(&self) -> Option<Rc<T>> {
+        RcWeak::upgrade(self)
+    }
+
+    fn new() -> Self {
+        RcWeak::new()
+    }
+}
+
+impl<T> WeakRef for ArcWeak<T> {
+    type Target = T;
+    type Strong = Arc<T>;
+
+    fn upgrade(&self) -> Option<Arc<T>> {
+        ArcWeak::upgrade(self)
+    }
+
+    fn new() -> Self {
+        ArcWeak::new()
+    }
+}
+
+/// Serialize a weak pointer as none without ever upgrading it.
+///
+/// The target value is not traversed, so this policy is safe on the
+/// back-edges of cyclic structures. Deserialization accepts the none (or
+/// any value produced by another policy) and returns a dangling pointer.
+pub mod weak_none {
+    use super::*;
+
+    /// Serializes none, ignoring the pointer.
+    pub fn serialize<W, S>(_weak: &W, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        W: WeakRef,
+        S: Serializer,
+    {
+        serializer.serialize_none()
+    }
+
+    /// Consumes an optional value and returns a dangling pointer.
+    pub fn deserialize<'de, W, D>(deserializer: D) -> Result<W, D::Error>
+    where
+        W: WeakRef,
+        D: Deserializer<'de>,
+    {
+        tri!(Option::<IgnoredAny>::deserialize(deserializer));
+        Ok(W::new())
+    }
+}
+
+/// Serialize a weak pointer by upgrading it: the target value if it is
+/// alive, none if it is dangling.
+///
+/// This is the same behavior as the default `Serialize` impl for `Weak`,
+/// stated explicitly. The target must not lead back to this pointer through
+/// strong references, or serialization will recurse without bound.
+pub mod weak_upgrade {
+    use super::*;
+
+    /// Serializes the upgraded target, or none if the pointer is dangling.
+    pub fn serialize<W, S>(weak: &W, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        W: WeakRef,
+        W::Target: Serialize,
+        S: Serializer,
+    {
+        match weak.upgrade() {
+            Some(strong) => serializer.serialize_some(&*strong),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// Consumes an optional value and returns a dangling pointer.
+    pub fn deserialize<'de, W, D>(deserializer: D) -> Result<W, D::Error>
+    where
+        W: WeakRef,
+        W::Target: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        tri!(Option::<W::Target>::deserialize(deserializer));
+        Ok(W::new())
+    }
+}
+
+/// Serialize the target of a weak pointer, failing if it is dangling.
+///
+/// The value is written directly rather than as an `Option`. For data where
+/// the target is expected to outlive the pointer, a dangling reference at
+/// serialization time is a bug worth surfacing instead of silently writing
+/// none.
+pub mod weak_reject_dangling {
+    use super::*;
+
+    /// Serializes the upgraded target, failing if the pointer is dangling.
+    pub fn serialize<W, S>(weak: &W, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        W: WeakRef,
+        W::Target: Serialize,
+        S: Serializer,
+    {
+        match weak.upgrade() {
+            Some(strong) => strong.serialize(serializer),
+            None => Err(ser::Error::custom(
+                "dangling weak pointer cannot be serialized",
+            )),
+        }
+    }
+
+    /// Consumes a value and returns a dangling pointer.
+    pub fn deserialize<'de, W, D>(deserializer: D) -> Result<W, D::Error>
+    where
+        W: WeakRef,
+        W::Target: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        tri!(W::Target::deserialize(deserializer));
+        Ok(W::new())
+    }
+}
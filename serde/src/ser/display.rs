@@ -0,0 +1,57 @@
+use crate::lib::*;
+use crate::ser::{Serialize, Serializer};
+
+/// A reference to a [`Display`] value, serialized as its display string.
+///
+/// This makes it possible to embed trait objects such as `&dyn Display` or
+/// error references in a derived struct without a `serialize_with` attribute
+/// on the field; the wrapper's `Serialize` impl forwards to
+/// [`Serializer::collect_str`].
+///
+/// ```edition2021
+/// use serde::ser::DisplayWrapper;
+/// use serde_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct LogRecord<'a> {
+///     level: &'a str,
+///     message: DisplayWrapper<'a>,
+/// }
+///
+/// let error = "connection reset by peer";
+/// let record = LogRecord {
+///     level: "error",
+///     message: DisplayWrapper(&error),
+/// };
+/// ```
+///
+/// [`Display`]: core::fmt::Display
+#[derive(Copy, Clone)]
+pub struct DisplayWrapper<'a>(
+    /// The wrapped value.
+    pub &'a dyn Display,
+);
+
+impl<'a> Serialize for DisplayWrapper<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self.0)
+    }
+}
+
+impl<'a> Display for DisplayWrapper<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(self.0, formatter)
+    }
+}
+
+impl<'a> Debug for DisplayWrapper<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_tuple("DisplayWrapper")
+            .field(&format_args!("{}", self.0))
+            .finish()
+    }
+}
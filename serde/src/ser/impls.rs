@@ -1,6 +1,6 @@
 use crate::lib::*;
 
-use crate::ser::{Error, Serialize, SerializeTuple, Serializer};
+use crate::ser::{Error, InlineNewtype, Serialize, SerializeTuple, Serializer};
 
 ////////////////////////////////////////////////////////////////////////////////
 
@@ -303,6 +303,19 @@ where
 
 ////////////////////////////////////////////////////////////////////////////////
 
+// Zero-sized like PhantomData, and serialized the same way: as a unit struct.
+impl Serialize for RangeFull {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_unit_struct("RangeFull")
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
 impl<Idx> Serialize for RangeInclusive<Idx>
 where
     Idx: Serialize,
@@ -1015,6 +1028,21 @@ impl Serialize for OsString {
 
 ////////////////////////////////////////////////////////////////////////////////
 
+// The error is serialized as its `Display` representation. Round-tripping
+// through Deserialize preserves the message but not the concrete error type.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl Serialize for Box<dyn error::Error + Send + Sync> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
 impl<T> Serialize for Wrapping<T>
 where
     T: Serialize,
@@ -1094,3 +1122,47 @@ atomic_impl! {
     AtomicI64 "64"
     AtomicU64 "64"
 }
+
+////////////////////////////////////////////////////////////////////////////////
+
+macro_rules! inline_newtype_impl {
+    ($($ty:ty)*) => {
+        $(
+            impl InlineNewtype for $ty {}
+        )*
+    };
+}
+
+inline_newtype_impl! {
+    bool isize i8 i16 i32 i64 i128 usize u8 u16 u32 u64 u128 f32 f64 char str
+}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+impl InlineNewtype for String {}
+
+impl<'a, T> InlineNewtype for &'a T where T: ?Sized + InlineNewtype {}
+
+impl<'a, T> InlineNewtype for &'a mut T where T: ?Sized + InlineNewtype {}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+impl<T> InlineNewtype for Box<T> where T: ?Sized + InlineNewtype {}
+
+#[cfg(any(feature = "std", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "alloc"))))]
+impl<'a, T> InlineNewtype for Cow<'a, T> where T: ?Sized + InlineNewtype + ToOwned {}
+
+#[cfg(all(feature = "rc", any(feature = "std", feature = "alloc")))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(feature = "rc", any(feature = "std", feature = "alloc"))))
+)]
+impl<T> InlineNewtype for Rc<T> where T: ?Sized + InlineNewtype {}
+
+#[cfg(all(feature = "rc", any(feature = "std", feature = "alloc")))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(all(feature = "rc", any(feature = "std", feature = "alloc"))))
+)]
+impl<T> InlineNewtype for Arc<T> where T: ?Sized + InlineNewtype {}
@@ -0,0 +1,49 @@
+use crate::lib::*;
+use crate::ser::Error;
+use core::convert::Infallible;
+
+/// Serializers that cannot fail — token capture, size counters, in-memory
+/// trees — may use [`Infallible`] as their error type. Raising an error
+/// through such a serializer is a bug in the calling `Serialize` impl, so
+/// `custom` panics with the given message.
+///
+/// [`Infallible`]: core::convert::Infallible
+impl Error for Infallible {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: Display,
+    {
+        panic!("error in infallible serializer: {}", msg)
+    }
+}
+
+/// Unwraps a `Result` whose error type can never be constructed.
+///
+/// For serializers with `Error = Infallible` this removes the `Result`
+/// handling from every call site without a runtime check:
+///
+/// ```edition2021
+/// use serde::ser::UnwrapInfallible;
+/// use std::convert::Infallible;
+///
+/// let result: Result<u32, Infallible> = Ok(1996);
+/// assert_eq!(result.unwrap_infallible(), 1996);
+/// ```
+pub trait UnwrapInfallible {
+    /// The type of the success value.
+    type Ok;
+
+    /// Returns the success value; statically cannot panic.
+    fn unwrap_infallible(self) -> Self::Ok;
+}
+
+impl<T> UnwrapInfallible for Result<T, Infallible> {
+    type Ok = T;
+
+    fn unwrap_infallible(self) -> T {
+        match self {
+            Ok(ok) => ok,
+            Err(never) => match never {},
+        }
+    }
+}
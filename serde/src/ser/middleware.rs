@@ -0,0 +1,455 @@
+//! Composable middleware for wrapping serializers.
+//!
+//! The [`Wrap`] adapter layers a [`Middleware`] on top of any existing
+//! [`Serializer`] without that serializer having to know about it. The
+//! middleware is consulted for every struct field and every string value that
+//! passes directly through the wrapped serializer, which is enough to redact
+//! or drop sensitive fields before they reach the output format.
+//!
+//! ```edition2021
+//! use serde::ser::middleware::{RedactFields, Wrap};
+//! use serde::{Serialize, Serializer};
+//! use serde_derive::Serialize;
+//!
+//! #[derive(Serialize)]
+//! struct User {
+//!     name: String,
+//!     password: String,
+//! }
+//!
+//! struct Audited<'a>(&'a User);
+//!
+//! impl<'a> Serialize for Audited<'a> {
+//!     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+//!     where
+//!         S: Serializer,
+//!     {
+//!         self.0
+//!             .serialize(Wrap::new(serializer, RedactFields::new(&["password"])))
+//!     }
+//! }
+//! ```
+//!
+//! Middleware applies to data structures serialized directly with the wrapped
+//! serializer; values nested inside sequences and maps are handed to the
+//! underlying format unchanged.
+//!
+//! For redacting individual fields of a derived `Serialize` impl without
+//! wrapping a serializer, the `#[serde(redact)]` field attribute serializes
+//! the field as the fixed string `"<redacted>"` regardless of its value.
+
+use crate::lib::*;
+use crate::ser::{Serialize, SerializeStruct, SerializeStructVariant, Serializer};
+
+/// A hook that decides how struct fields and string values are serialized.
+///
+/// All methods have pass-through defaults, so an implementation only needs to
+/// override the behavior it wants to change.
+pub trait Middleware {
+    /// Decides what happens to the struct field `field` of the struct or
+    /// struct variant named `name`.
+    fn field_action(&self, name: &'static str, field: &'static str) -> FieldAction {
+        let _ = (name, field);
+        FieldAction::Keep
+    }
+
+    /// Transforms a string value before it is handed to the underlying
+    /// serializer.
+    fn transform_str<'a>(&self, value: &'a str) -> &'a str {
+        value
+    }
+}
+
+/// What a [`Middleware`] wants done with one struct field.
+pub enum FieldAction {
+    /// Serialize the field value unchanged.
+    Keep,
+    /// Omit the field, as if it were marked `skip_serializing`.
+    Skip,
+    /// Serialize the given placeholder string in place of the field value.
+    Redact(&'static str),
+}
+
+/// Middleware compose left to right: the first middleware that asks for a
+/// field to be skipped or redacted wins, and string transformations are
+/// applied in order.
+impl<A, B> Middleware for (A, B)
+where
+    A: Middleware,
+    B: Middleware,
+{
+    fn field_action(&self, name: &'static str, field: &'static str) -> FieldAction {
+        match self.0.field_action(name, field) {
+            FieldAction::Keep => self.1.field_action(name, field),
+            action => action,
+        }
+    }
+
+    fn transform_str<'a>(&self, value: &'a str) -> &'a str {
+        self.1.transform_str(self.0.transform_str(value))
+    }
+}
+
+/// Middleware that replaces the listed fields with a placeholder string.
+///
+/// The placeholder defaults to `"<redacted>"`.
+pub struct RedactFields<'a> {
+    fields: &'a [&'a str],
+    placeholder: &'static str,
+}
+
+impl<'a> RedactFields<'a> {
+    /// Redacts fields with the given names in every struct.
+    pub fn new(fields: &'a [&'a str]) -> Self {
+        RedactFields {
+            fields,
+            placeholder: "<redacted>",
+        }
+    }
+
+    /// Replaces redacted fields with `placeholder` instead of `"<redacted>"`.
+    pub fn with_placeholder(mut self, placeholder: &'static str) -> Self {
+        self.placeholder = placeholder;
+        self
+    }
+}
+
+impl<'a> Middleware for RedactFields<'a> {
+    fn field_action(&self, _name: &'static str, field: &'static str) -> FieldAction {
+        if self.fields.contains(&field) {
+            FieldAction::Redact(self.placeholder)
+        } else {
+            FieldAction::Keep
+        }
+    }
+}
+
+/// A serializer that applies a [`Middleware`] to everything serialized
+/// through it, delegating the actual output to another serializer.
+pub struct Wrap<S, M> {
+    serializer: S,
+    middleware: M,
+}
+
+impl<S, M> Wrap<S, M>
+where
+    S: Serializer,
+    M: Middleware,
+{
+    /// Wraps `serializer` so that `middleware` is consulted for every struct
+    /// field and string value serialized through it.
+    pub fn new(serializer: S, middleware: M) -> Self {
+        Wrap {
+            serializer,
+            middleware,
+        }
+    }
+}
+
+impl<S, M> Serializer for Wrap<S, M>
+where
+    S: Serializer,
+    M: Middleware,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    type SerializeSeq = S::SerializeSeq;
+    type SerializeTuple = S::SerializeTuple;
+    type SerializeTupleStruct = S::SerializeTupleStruct;
+    type SerializeTupleVariant = S::SerializeTupleVariant;
+    type SerializeMap = S::SerializeMap;
+    type SerializeStruct = WrapStruct<S::SerializeStruct, M>;
+    type SerializeStructVariant = WrapStructVariant<S::SerializeStructVariant, M>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_bool(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_i8(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_i16(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_i32(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_i64(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_i128(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_u8(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_u16(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_u32(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_u64(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_u128(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_f32(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_f64(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_char(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        self.serializer
+            .serialize_str(self.middleware.transform_str(v))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_bytes(v)
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_none()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.serializer.serialize_some(value)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_unit()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.serializer.serialize_unit_struct(name)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.serializer
+            .serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.serializer.serialize_newtype_struct(name, value)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.serializer
+            .serialize_newtype_variant(name, variant_index, variant, value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.serializer.serialize_seq(len)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serializer.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serializer.serialize_tuple_struct(name, len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.serializer
+            .serialize_tuple_variant(name, variant_index, variant, len)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        self.serializer.serialize_map(len)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        let inner = tri!(self.serializer.serialize_struct(name, len));
+        Ok(WrapStruct {
+            inner,
+            middleware: self.middleware,
+            name,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        let inner =
+            tri!(self
+                .serializer
+                .serialize_struct_variant(name, variant_index, variant, len));
+        Ok(WrapStructVariant {
+            inner,
+            middleware: self.middleware,
+            name: variant,
+        })
+    }
+
+    fn collect_str<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Display,
+    {
+        self.serializer.collect_str(value)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.serializer.is_human_readable()
+    }
+}
+
+/// Struct serializer returned by [`Wrap`], applying the middleware's
+/// [`field_action`] to each field.
+///
+/// [`field_action`]: Middleware::field_action
+pub struct WrapStruct<S, M> {
+    inner: S,
+    middleware: M,
+    name: &'static str,
+}
+
+impl<S, M> SerializeStruct for WrapStruct<S, M>
+where
+    S: SerializeStruct,
+    M: Middleware,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        match self.middleware.field_action(self.name, key) {
+            FieldAction::Keep => self.inner.serialize_field(key, value),
+            FieldAction::Skip => self.inner.skip_field(key),
+            FieldAction::Redact(placeholder) => self.inner.serialize_field(key, placeholder),
+        }
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        self.inner.skip_field(key)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.end()
+    }
+}
+
+/// Struct variant serializer returned by [`Wrap`], applying the middleware's
+/// [`field_action`] to each field.
+///
+/// [`field_action`]: Middleware::field_action
+pub struct WrapStructVariant<S, M> {
+    inner: S,
+    middleware: M,
+    name: &'static str,
+}
+
+impl<S, M> SerializeStructVariant for WrapStructVariant<S, M>
+where
+    S: SerializeStructVariant,
+    M: Middleware,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        match self.middleware.field_action(self.name, key) {
+            FieldAction::Keep => self.inner.serialize_field(key, value),
+            FieldAction::Skip => self.inner.skip_field(key),
+            FieldAction::Redact(placeholder) => self.inner.serialize_field(key, placeholder),
+        }
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        self.inner.skip_field(key)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.end()
+    }
+}
+
+/// Serialize any value as the fixed string `"<redacted>"`.
+///
+/// This is the function behind the `#[serde(redact)]` field attribute. It can
+/// also be named directly with `serialize_with`:
+///
+/// ```edition2021
+/// use serde_derive::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Credentials {
+///     username: String,
+///     #[serde(redact)]
+///     password: String,
+/// }
+/// ```
+pub fn redacted<T, S>(_value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized,
+    S: Serializer,
+{
+    serializer.serialize_str("<redacted>")
+}
@@ -109,11 +109,18 @@
 
 use crate::lib::*;
 
+mod display;
 mod fmt;
 mod impls;
 mod impossible;
+#[cfg(not(no_core_try_from))]
+mod infallible;
+pub mod middleware;
 
+pub use self::display::DisplayWrapper;
 pub use self::impossible::Impossible;
+#[cfg(not(no_core_try_from))]
+pub use self::infallible::UnwrapInfallible;
 
 #[cfg(all(not(feature = "std"), no_core_error))]
 #[doc(no_inline)]
@@ -185,6 +192,10 @@ macro_rules! declare_error_trait {
 #[cfg(feature = "std")]
 declare_error_trait!(Error: Sized + StdError);
 
+// Without std, the error trait deliberately has no `StdError` supertrait. A
+// no_std data format's error type needs only `Debug`, `Display`, and `custom`,
+// independent of the polyfilled `std_error` module used on toolchains that
+// predate `core::error::Error`.
 #[cfg(not(feature = "std"))]
 declare_error_trait!(Error: Sized + Debug + Display);
 
@@ -1327,6 +1338,79 @@ pub trait Serializer: Sized {
         serializer.end()
     }
 
+    /// Collect an iterator with a known length as a sequence.
+    ///
+    /// Unlike [`collect_seq`], the length passed to [`serialize_seq`] is
+    /// guaranteed to be `Some` because it comes from
+    /// [`ExactSizeIterator::len`] rather than from [`Iterator::size_hint`].
+    /// This matters to formats like bincode which serialize sequences of
+    /// unknown length much less efficiently than ones whose length is known
+    /// up front.
+    ///
+    /// The default implementation serializes each item yielded by the iterator
+    /// using [`serialize_seq`]. Implementors should not need to override this
+    /// method.
+    ///
+    /// ```edition2021
+    /// use serde::{Serialize, Serializer};
+    ///
+    /// struct SecretlyOneHigher {
+    ///     data: Vec<i32>,
+    /// }
+    ///
+    /// impl Serialize for SecretlyOneHigher {
+    ///     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    ///     where
+    ///         S: Serializer,
+    ///     {
+    ///         serializer.collect_seq_exact(self.data.iter().map(|x| x + 1))
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`collect_seq`]: #method.collect_seq
+    /// [`serialize_seq`]: #tymethod.serialize_seq
+    /// [`ExactSizeIterator::len`]: https://doc.rust-lang.org/std/iter/trait.ExactSizeIterator.html#method.len
+    /// [`Iterator::size_hint`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.size_hint
+    fn collect_seq_exact<I>(self, iter: I) -> Result<Self::Ok, Self::Error>
+    where
+        I: IntoIterator,
+        <I as IntoIterator>::IntoIter: ExactSizeIterator,
+        <I as IntoIterator>::Item: Serialize,
+    {
+        let mut iter = iter.into_iter();
+        let mut serializer = tri!(self.serialize_seq(Some(iter.len())));
+        tri!(iter.try_for_each(|item| serializer.serialize_element(&item)));
+        serializer.end()
+    }
+
+    /// Collect an iterator with a known length as a map.
+    ///
+    /// Unlike [`collect_map`], the length passed to [`serialize_map`] is
+    /// guaranteed to be `Some` because it comes from
+    /// [`ExactSizeIterator::len`] rather than from [`Iterator::size_hint`].
+    ///
+    /// The default implementation serializes each pair yielded by the iterator
+    /// using [`serialize_map`]. Implementors should not need to override this
+    /// method.
+    ///
+    /// [`collect_map`]: #method.collect_map
+    /// [`serialize_map`]: #tymethod.serialize_map
+    /// [`ExactSizeIterator::len`]: https://doc.rust-lang.org/std/iter/trait.ExactSizeIterator.html#method.len
+    /// [`Iterator::size_hint`]: https://doc.rust-lang.org/std/iter/trait.Iterator.html#method.size_hint
+    fn collect_map_exact<K, V, I>(self, iter: I) -> Result<Self::Ok, Self::Error>
+    where
+        K: Serialize,
+        V: Serialize,
+        I: IntoIterator<Item = (K, V)>,
+        <I as IntoIterator>::IntoIter: ExactSizeIterator,
+    {
+        let mut iter = iter.into_iter();
+        let mut serializer = tri!(self.serialize_map(Some(iter.len())));
+        tri!(iter.try_for_each(|(key, value)| serializer.serialize_entry(&key, &value)));
+        serializer.end()
+    }
+
     /// Serialize a string produced by an implementation of `Display`.
     ///
     /// The default implementation builds a heap-allocated [`String`] and
@@ -1781,6 +1865,21 @@ pub trait SerializeMap {
     where
         T: ?Sized + Serialize;
 
+    /// Serialize a map key that is a string known at compile time.
+    ///
+    /// Struct fields flattened into a surrounding map pass their names
+    /// through this method. The default implementation forwards to
+    /// [`serialize_key`], so formats observe the same data either way;
+    /// formats that intern or precompute the encoding of static keys can
+    /// override it to bypass the generic `Serialize` machinery in hot
+    /// serialization loops.
+    ///
+    /// [`serialize_key`]: #tymethod.serialize_key
+    #[inline]
+    fn serialize_key_str(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        self.serialize_key(key)
+    }
+
     /// Serialize a map value.
     ///
     /// # Panics
@@ -1868,6 +1967,11 @@ pub trait SerializeStruct {
 
     /// Indicate that a struct field has been skipped.
     ///
+    /// The derive macro calls this for fields omitted by
+    /// `#[serde(skip_serializing)]` and `#[serde(skip_serializing_if)]`.
+    /// Formats that require a complete fixed layout, or that want to write an
+    /// explicit null for missing fields, can use it to emit a placeholder.
+    ///
     /// The default implementation does nothing.
     #[inline]
     fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
@@ -1932,6 +2036,11 @@ pub trait SerializeStructVariant {
 
     /// Indicate that a struct variant field has been skipped.
     ///
+    /// The derive macro calls this for fields omitted by
+    /// `#[serde(skip_serializing)]` and `#[serde(skip_serializing_if)]`.
+    /// Formats that require a complete fixed layout, or that want to write an
+    /// explicit null for missing fields, can use it to emit a placeholder.
+    ///
     /// The default implementation does nothing.
     #[inline]
     fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
@@ -1943,6 +2052,23 @@ pub trait SerializeStructVariant {
     fn end(self) -> Result<Self::Ok, Self::Error>;
 }
 
+/// A type that serializes as a bare value, without adding a wrapper of its
+/// own.
+///
+/// This trait is implemented for the primitive types and string types, and is
+/// implemented automatically when `Serialize` is derived for a container
+/// marked [`#[serde(transparent)]`]. It carries no methods; its only purpose
+/// is to let `#[serde(inline_newtypes)]` on a container assert at compile
+/// time that every field serializes as its inner value — typically to catch a
+/// strong-typed newtype wrapper such as `UserId(u64)` that forgot the
+/// `transparent` attribute and would otherwise change the wire format.
+///
+/// Hand-written `Serialize` impls that forward directly to the inner value
+/// may implement this trait manually to make the same promise.
+///
+/// [`#[serde(transparent)]`]: https://serde.rs/container-attrs.html#transparent
+pub trait InlineNewtype: Serialize {}
+
 fn iterator_len_hint<I>(iter: &I) -> Option<usize>
 where
     I: Iterator,
@@ -0,0 +1,243 @@
+//! A small-string type that deserializes without heap allocation.
+//!
+//! Data with many field-like identifiers — map keys, enum tags, interned
+//! symbols — tends to consist of short strings that are deserialized over and
+//! over. [`SmallString`] stores strings of up to 23 bytes inline and only
+//! falls back to a heap `String` beyond that, so deserializing such data does
+//! not allocate for the common case.
+//!
+//! ```edition2021
+//! use serde::small_string::SmallString;
+//! use serde_derive::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Record {
+//!     kind: SmallString,
+//! }
+//! ```
+
+use crate::lib::*;
+
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+
+use crate::de::{Deserialize, Deserializer, Error, Unexpected, Visitor};
+use crate::ser::{Serialize, Serializer};
+
+/// Maximum length in bytes that a `SmallString` stores inline.
+pub const INLINE_CAPACITY: usize = 23;
+
+/// A string that stores up to [`INLINE_CAPACITY`] bytes inline.
+///
+/// Deserializing a `SmallString` does not allocate unless the input exceeds
+/// the inline capacity. It dereferences to `str`, so it can be used wherever
+/// a borrowed string is expected.
+#[derive(Clone)]
+pub struct SmallString {
+    repr: Repr,
+}
+
+#[derive(Clone)]
+enum Repr {
+    Inline { len: u8, buf: [u8; INLINE_CAPACITY] },
+    Heap(String),
+}
+
+impl SmallString {
+    /// Construct an empty `SmallString`.
+    pub fn new() -> Self {
+        SmallString {
+            repr: Repr::Inline {
+                len: 0,
+                buf: [0; INLINE_CAPACITY],
+            },
+        }
+    }
+
+    /// View the contents as a string slice.
+    pub fn as_str(&self) -> &str {
+        match &self.repr {
+            Repr::Inline { len, buf } => {
+                let slice = &buf[..*len as usize];
+                // The inline buffer is only ever filled from a `str`.
+                unsafe { str::from_utf8_unchecked(slice) }
+            }
+            Repr::Heap(string) => string,
+        }
+    }
+
+    /// Whether the contents are stored inline rather than on the heap.
+    pub fn is_inline(&self) -> bool {
+        match self.repr {
+            Repr::Inline { .. } => true,
+            Repr::Heap(_) => false,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for SmallString {
+    fn from(s: &'a str) -> Self {
+        if s.len() <= INLINE_CAPACITY {
+            let mut buf = [0; INLINE_CAPACITY];
+            buf[..s.len()].copy_from_slice(s.as_bytes());
+            SmallString {
+                repr: Repr::Inline {
+                    len: s.len() as u8,
+                    buf,
+                },
+            }
+        } else {
+            SmallString {
+                repr: Repr::Heap(s.to_owned()),
+            }
+        }
+    }
+}
+
+impl From<String> for SmallString {
+    fn from(s: String) -> Self {
+        if s.len() <= INLINE_CAPACITY {
+            SmallString::from(s.as_str())
+        } else {
+            SmallString {
+                repr: Repr::Heap(s),
+            }
+        }
+    }
+}
+
+impl Default for SmallString {
+    fn default() -> Self {
+        SmallString::new()
+    }
+}
+
+impl Deref for SmallString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for SmallString {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Debug for SmallString {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(self.as_str(), formatter)
+    }
+}
+
+impl Display for SmallString {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(self.as_str(), formatter)
+    }
+}
+
+impl PartialEq for SmallString {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for SmallString {}
+
+impl PartialEq<str> for SmallString {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for SmallString {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialOrd for SmallString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SmallString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Hash for SmallString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl Serialize for SmallString {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self)
+    }
+}
+
+struct SmallStringVisitor;
+
+impl<'de> Visitor<'de> for SmallStringVisitor {
+    type Value = SmallString;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(SmallString::from(v))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(SmallString::from(v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match str::from_utf8(v) {
+            Ok(s) => Ok(SmallString::from(s)),
+            Err(_) => Err(Error::invalid_value(Unexpected::Bytes(v), &self)),
+        }
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        match String::from_utf8(v) {
+            Ok(s) => Ok(SmallString::from(s)),
+            Err(e) => Err(Error::invalid_value(
+                Unexpected::Bytes(&e.into_bytes()),
+                &self,
+            )),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SmallString {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(SmallStringVisitor)
+    }
+}
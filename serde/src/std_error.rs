@@ -40,6 +40,13 @@ use crate::lib::{Debug, Display};
 /// ```edition2021
 /// impl serde::ser::StdError for MySerError {}
 /// ```
+///
+/// None of this is required for no\_std formats. Without the "std" feature,
+/// [`serde::ser::Error`] and [`serde::de::Error`] require only `Debug` and
+/// `Display` as supertraits, so an embedded format's error type never needs
+/// to mention this module. On toolchains where `core::error::Error` exists
+/// (Rust 1.81+), `StdError` re-exports that trait instead of this polyfill;
+/// this module is compiled only for older compilers.
 pub trait Error: Debug + Display {
     /// The underlying cause of this error, if any.
     fn source(&self) -> Option<&(Error + 'static)> {
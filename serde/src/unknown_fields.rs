@@ -0,0 +1,172 @@
+//! Lossless round-tripping of fields that a struct does not declare.
+
+use crate::lib::*;
+
+use crate::__private::de::Content;
+use crate::de::{Deserializer, MapAccess, Visitor};
+use crate::ser::{Serialize, Serializer};
+
+/// An opaque bag of fields that were not declared by the containing struct.
+///
+/// Evolving documents are routinely read by programs that only know some of
+/// the fields, modified, and written back; dropping the unrecognized fields
+/// on the way corrupts the document. Adding an `UnknownFields` field marked
+/// with `#[serde(unknown_fields)]` captures every unrecognized key together
+/// with its value in a format-erased buffer, and re-serializes the captured
+/// entries verbatim on output.
+///
+/// ```edition2021
+/// use serde::UnknownFields;
+/// use serde_derive::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Document {
+///     id: u32,
+///     #[serde(unknown_fields)]
+///     rest: UnknownFields,
+/// }
+/// ```
+///
+/// Like a `#[serde(flatten)]` map, a struct containing an `UnknownFields`
+/// field serializes as a map rather than a struct, and cannot be combined
+/// with `deny_unknown_fields`.
+#[derive(Clone, Debug, Default)]
+pub struct UnknownFields {
+    entries: Vec<(Content<'static>, Content<'static>)>,
+}
+
+impl UnknownFields {
+    /// An empty bag, as a freshly created document would have.
+    pub fn new() -> Self {
+        UnknownFields {
+            entries: Vec::new(),
+        }
+    }
+
+    /// The number of captured fields.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// True if no unrecognized fields were captured.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn into_owned(content: Content) -> Content<'static> {
+    match content {
+        Content::Bool(b) => Content::Bool(b),
+        Content::U8(u) => Content::U8(u),
+        Content::U16(u) => Content::U16(u),
+        Content::U32(u) => Content::U32(u),
+        Content::U64(u) => Content::U64(u),
+        Content::I8(i) => Content::I8(i),
+        Content::I16(i) => Content::I16(i),
+        Content::I32(i) => Content::I32(i),
+        Content::I64(i) => Content::I64(i),
+        Content::F32(f) => Content::F32(f),
+        Content::F64(f) => Content::F64(f),
+        Content::Char(c) => Content::Char(c),
+        Content::String(s) => Content::String(s),
+        Content::Str(s) => Content::String(s.to_owned()),
+        Content::ByteBuf(b) => Content::ByteBuf(b),
+        Content::Bytes(b) => Content::ByteBuf(b.to_vec()),
+        Content::None => Content::None,
+        Content::Some(c) => Content::Some(Box::new(into_owned(*c))),
+        Content::Unit => Content::Unit,
+        Content::Newtype(c) => Content::Newtype(Box::new(into_owned(*c))),
+        Content::Seq(elements) => Content::Seq(elements.into_iter().map(into_owned).collect()),
+        Content::Map(entries) => Content::Map(
+            entries
+                .into_iter()
+                .map(|(key, value)| (into_owned(key), into_owned(value)))
+                .collect(),
+        ),
+    }
+}
+
+// The buffered `Content` type is deserialization-only and does not implement
+// `Serialize` itself.
+struct SerializeContent<'a>(&'a Content<'static>);
+
+impl<'a> Serialize for SerializeContent<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self.0 {
+            Content::Bool(b) => serializer.serialize_bool(b),
+            Content::U8(u) => serializer.serialize_u8(u),
+            Content::U16(u) => serializer.serialize_u16(u),
+            Content::U32(u) => serializer.serialize_u32(u),
+            Content::U64(u) => serializer.serialize_u64(u),
+            Content::I8(i) => serializer.serialize_i8(i),
+            Content::I16(i) => serializer.serialize_i16(i),
+            Content::I32(i) => serializer.serialize_i32(i),
+            Content::I64(i) => serializer.serialize_i64(i),
+            Content::F32(f) => serializer.serialize_f32(f),
+            Content::F64(f) => serializer.serialize_f64(f),
+            Content::Char(c) => serializer.serialize_char(c),
+            Content::String(ref s) => serializer.serialize_str(s),
+            Content::Str(s) => serializer.serialize_str(s),
+            Content::ByteBuf(ref b) => serializer.serialize_bytes(b),
+            Content::Bytes(b) => serializer.serialize_bytes(b),
+            Content::None => serializer.serialize_none(),
+            Content::Some(ref c) => serializer.serialize_some(&SerializeContent(c)),
+            Content::Unit => serializer.serialize_unit(),
+            Content::Newtype(ref c) => SerializeContent(c).serialize(serializer),
+            Content::Seq(ref elements) => {
+                serializer.collect_seq(elements.iter().map(SerializeContent))
+            }
+            Content::Map(ref entries) => serializer.collect_map(
+                entries
+                    .iter()
+                    .map(|(key, value)| (SerializeContent(key), SerializeContent(value))),
+            ),
+        }
+    }
+}
+
+impl Serialize for UnknownFields {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_map(
+            self.entries
+                .iter()
+                .map(|(key, value)| (SerializeContent(key), SerializeContent(value))),
+        )
+    }
+}
+
+impl<'de> crate::de::Deserialize<'de> for UnknownFields {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct UnknownFieldsVisitor;
+
+        impl<'de> Visitor<'de> for UnknownFieldsVisitor {
+            type Value = UnknownFields;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a map")
+            }
+
+            fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut entries = Vec::new();
+                while let Some((key, value)) = tri!(access.next_entry::<Content, Content>()) {
+                    entries.push((into_owned(key), into_owned(value)));
+                }
+                Ok(UnknownFields { entries })
+            }
+        }
+
+        deserializer.deserialize_map(UnknownFieldsVisitor)
+    }
+}
@@ -2,10 +2,10 @@ use crate::fragment::{Expr, Fragment, Match, Stmts};
 use crate::internals::ast::{Container, Data, Field, Style, Variant};
 use crate::internals::name::Name;
 use crate::internals::{attr, replace_receiver, ungroup, Ctxt, Derive};
-use crate::{bound, dummy, pretend, this};
+use crate::{bound, doc, dummy, pretend, this, warnings};
 use proc_macro2::{Literal, Span, TokenStream};
 use quote::{quote, quote_spanned, ToTokens};
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::ptr;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
@@ -20,6 +20,7 @@ pub fn expand_derive_deserialize(input: &mut syn::DeriveInput) -> syn::Result<To
         None => return Err(ctxt.check().unwrap_err()),
     };
     precondition(&ctxt, &cont);
+    let useless_attrs = warnings::render(&ctxt);
     ctxt.check()?;
 
     let ident = &cont.ident;
@@ -44,28 +45,104 @@ pub fn expand_derive_deserialize(input: &mut syn::DeriveInput) -> syn::Result<To
                 }
             }
         }
+    } else if let Some(with) = cont.attrs.deserialize_with() {
+        let vis = &input.vis;
+        quote! {
+            #[automatically_derived]
+            impl #de_impl_generics #serde::Deserialize<#delife> for #ident #ty_generics #where_clause {
+                fn deserialize<__D>(__deserializer: __D) -> #serde::__private::Result<Self, __D::Error>
+                where
+                    __D: #serde::Deserializer<#delife>,
+                {
+                    #with(__deserializer)
+                }
+            }
+
+            #[automatically_derived]
+            impl #de_impl_generics #ident #ty_generics #where_clause {
+                /// Derived deserialization of this type, for the container's
+                /// `deserialize_with` function to delegate to.
+                #vis fn deserialize_fields<__D>(__deserializer: __D) -> #serde::__private::Result<Self, __D::Error>
+                where
+                    __D: #serde::Deserializer<#delife>,
+                {
+                    #body
+                }
+            }
+        }
     } else {
         let fn_deserialize_in_place = deserialize_in_place_body(&cont, &params);
 
+        let convenience_api = if cont.attrs.convenience_api() {
+            let (orig_impl_generics, orig_ty_generics, orig_where_clause) =
+                cont.generics.split_for_impl();
+            Some(quote! {
+                #[automatically_derived]
+                impl #orig_impl_generics #ident #orig_ty_generics #orig_where_clause {
+                    /// Deserializes an instance from bytes in the given data format.
+                    pub fn from_bytes<'de, __F>(__bytes: &'de [u8]) -> #serde::__private::Result<Self, __F::DeserializeError>
+                    where
+                        __F: #serde::convenience::BytesFormat,
+                        Self: #serde::Deserialize<'de>,
+                    {
+                        __F::deserialize_from_bytes(__bytes)
+                    }
+                }
+            })
+        } else {
+            None
+        };
+
+        // There is no value to destructure here, so check exhaustiveness of
+        // the strict_fields list against a pattern match on an absent value.
+        let strict_fields_check = cont.attrs.strict_fields().map(|fields| {
+            quote! {
+                match _serde::__private::None::<Self> {
+                    _serde::__private::Some(Self { #(#fields: _,)* }) => {}
+                    _serde::__private::None => {}
+                }
+            }
+        });
+        let wire_format_doc = if cont.attrs.document_impl() {
+            let doc = doc::wire_format_doc(&cont);
+            Some(quote!(#[doc = #doc]))
+        } else {
+            None
+        };
         quote! {
+            #wire_format_doc
             #[automatically_derived]
             impl #de_impl_generics #serde::Deserialize<#delife> for #ident #ty_generics #where_clause {
                 fn deserialize<__D>(__deserializer: __D) -> #serde::__private::Result<Self, __D::Error>
                 where
                     __D: #serde::Deserializer<#delife>,
                 {
+                    #strict_fields_check
                     #body
                 }
 
                 #fn_deserialize_in_place
             }
+
+            #convenience_api
         }
     };
 
-    Ok(dummy::wrap_in_const(
+    let generated = dummy::wrap_in_const(
         cont.attrs.custom_serde_path(),
-        impl_block,
-    ))
+        quote! {
+            #useless_attrs
+            #impl_block
+        },
+    );
+
+    Ok(match cont.attrs.de_cfg() {
+        Some(predicate) => quote! {
+            #[cfg(#predicate)]
+            #generated
+        },
+        None => generated,
+    })
 }
 
 fn precondition(cx: &Ctxt, cont: &Container) {
@@ -277,9 +354,13 @@ fn deserialize_body(cont: &Container, params: &Parameters) -> Fragment {
     if cont.attrs.transparent() {
         deserialize_transparent(cont, params)
     } else if let Some(type_from) = cont.attrs.type_from() {
-        deserialize_from(type_from)
+        deserialize_from(type_from, &cont.attrs)
     } else if let Some(type_try_from) = cont.attrs.type_try_from() {
-        deserialize_try_from(type_try_from)
+        deserialize_try_from(type_try_from, &cont.attrs)
+    } else if let Some(type_builder) = cont.attrs.type_builder() {
+        deserialize_builder(type_builder)
+    } else if let Some(format) = cont.attrs.as_string_format() {
+        deserialize_as_string(cont, params, format)
     } else if let attr::Identifier::No = cont.attrs.identifier() {
         match &cont.data {
             Data::Enum(variants) => deserialize_enum(params, variants, &cont.attrs),
@@ -308,6 +389,8 @@ fn deserialize_in_place_body(cont: &Container, params: &Parameters) -> Option<St
     if cont.attrs.transparent()
         || cont.attrs.type_from().is_some()
         || cont.attrs.type_try_from().is_some()
+        || cont.attrs.type_builder().is_some()
+        || cont.attrs.as_string_format().is_some()
         || cont.attrs.identifier().is_some()
         || cont
             .data
@@ -391,22 +474,161 @@ fn deserialize_transparent(cont: &Container, params: &Parameters) -> Fragment {
     }
 }
 
-fn deserialize_from(type_from: &syn::Type) -> Fragment {
+// Container attributes that make sense for the proxy deserialization are
+// forwarded by wrapping the deserializer handed to the proxy type.
+fn proxy_deserializer(cattrs: &attr::Container) -> TokenStream {
+    if cattrs.deny_unknown_fields() {
+        quote!(_serde::__private::de::DenyUnknownFields::new(
+            __deserializer
+        ))
+    } else {
+        quote!(__deserializer)
+    }
+}
+
+fn deserialize_from(type_from: &syn::Type, cattrs: &attr::Container) -> Fragment {
+    let deserializer = proxy_deserializer(cattrs);
     quote_block! {
         _serde::__private::Result::map(
-            <#type_from as _serde::Deserialize>::deserialize(__deserializer),
+            <#type_from as _serde::Deserialize>::deserialize(#deserializer),
             _serde::__private::From::from)
     }
 }
 
-fn deserialize_try_from(type_try_from: &syn::Type) -> Fragment {
+fn deserialize_try_from(type_try_from: &syn::Type, cattrs: &attr::Container) -> Fragment {
+    let deserializer = proxy_deserializer(cattrs);
     quote_block! {
         _serde::__private::Result::and_then(
-            <#type_try_from as _serde::Deserialize>::deserialize(__deserializer),
+            <#type_try_from as _serde::Deserialize>::deserialize(#deserializer),
             |v| _serde::__private::TryFrom::try_from(v).map_err(_serde::de::Error::custom))
     }
 }
 
+fn deserialize_builder(type_builder: &syn::Type) -> Fragment {
+    quote_block! {
+        _serde::__private::Result::and_then(
+            <#type_builder as _serde::Deserialize>::deserialize(__deserializer),
+            |__builder| __builder.build().map_err(_serde::de::Error::custom))
+    }
+}
+
+fn deserialize_as_string(
+    cont: &Container,
+    params: &Parameters,
+    format: &attr::AsStringFormat,
+) -> Fragment {
+    let fields = match &cont.data {
+        Data::Struct(Style::Struct, fields) => fields,
+        _ => unreachable!("checked in serde_derive_internals"),
+    };
+
+    let this_type = &params.this_type;
+    let this_value = &params.this_value;
+    let (de_impl_generics, de_ty_generics, ty_generics, where_clause) =
+        split_with_de_lifetime(params);
+    let delife = params.borrowed.de_lifetime();
+
+    let expecting = format!("a string in the format {:?}", format.pattern);
+    let expecting = cont.attrs.expecting().unwrap_or(&expecting);
+
+    let fail = quote! {
+        _serde::de::Error::invalid_value(_serde::de::Unexpected::Str(__value), &self)
+    };
+
+    let mut parse = Vec::new();
+    let mut construct = Vec::new();
+    let segments = &format.segments;
+    for (i, segment) in segments.iter().enumerate() {
+        match segment {
+            attr::AsStringSegment::Literal(literal) => {
+                parse.push(quote! {
+                    let __rest = match str::strip_prefix(__rest, #literal) {
+                        _serde::__private::Some(__rest) => __rest,
+                        _serde::__private::None => return _serde::__private::Err(#fail),
+                    };
+                });
+            }
+            attr::AsStringSegment::Field(name) => {
+                let field = fields
+                    .iter()
+                    .find(|field| match &field.member {
+                        Member::Named(ident) => ident == name,
+                        Member::Unnamed(_) => false,
+                    })
+                    .expect("checked in serde_derive_internals");
+                let member = &field.member;
+                let ty = field.ty;
+                let var = Ident::new(&format!("__field_{}", name), Span::call_site());
+                // Adjacent placeholders are rejected while parsing the
+                // attribute, so the next segment is a literal if any.
+                match segments.get(i + 1) {
+                    Some(attr::AsStringSegment::Literal(literal)) => parse.push(quote! {
+                        let (__part, __rest) = match str::find(__rest, #literal) {
+                            _serde::__private::Some(__pos) => (&__rest[..__pos], &__rest[__pos..]),
+                            _serde::__private::None => return _serde::__private::Err(#fail),
+                        };
+                    }),
+                    Some(attr::AsStringSegment::Field(_)) => {
+                        unreachable!("checked in serde_derive_internals")
+                    }
+                    None => parse.push(quote! {
+                        let __part = __rest;
+                    }),
+                }
+                parse.push(quote! {
+                    let #var: #ty = match str::parse(__part) {
+                        _serde::__private::Ok(#var) => #var,
+                        _serde::__private::Err(_) => return _serde::__private::Err(#fail),
+                    };
+                });
+                construct.push(quote!(#member: #var));
+            }
+        }
+    }
+
+    if let Some(attr::AsStringSegment::Literal(_)) = segments.last() {
+        parse.push(quote! {
+            if !str::is_empty(__rest) {
+                return _serde::__private::Err(#fail);
+            }
+        });
+    }
+
+    quote_block! {
+        #[doc(hidden)]
+        struct __Visitor #de_impl_generics #where_clause {
+            marker: _serde::__private::PhantomData<#this_type #ty_generics>,
+            lifetime: _serde::__private::PhantomData<&#delife ()>,
+        }
+
+        #[automatically_derived]
+        impl #de_impl_generics _serde::de::Visitor<#delife> for __Visitor #de_ty_generics #where_clause {
+            type Value = #this_type #ty_generics;
+
+            fn expecting(&self, __formatter: &mut _serde::__private::Formatter) -> _serde::__private::fmt::Result {
+                _serde::__private::Formatter::write_str(__formatter, #expecting)
+            }
+
+            fn visit_str<__E>(self, __value: &str) -> _serde::__private::Result<Self::Value, __E>
+            where
+                __E: _serde::de::Error,
+            {
+                let __rest = __value;
+                #(#parse)*
+                _serde::__private::Ok(#this_value { #(#construct),* })
+            }
+        }
+
+        _serde::Deserializer::deserialize_str(
+            __deserializer,
+            __Visitor {
+                marker: _serde::__private::PhantomData::<#this_type #ty_generics>,
+                lifetime: _serde::__private::PhantomData,
+            },
+        )
+    }
+}
+
 fn deserialize_unit_struct(params: &Parameters, cattrs: &attr::Container) -> Fragment {
     let this_type = &params.this_type;
     let this_value = &params.this_value;
@@ -713,25 +935,52 @@ fn deserialize_seq(
                 let #var = #default;
             }
         } else {
-            let visit = match field.attrs.deserialize_with() {
-                None => {
-                    let field_ty = field.ty;
-                    let span = field.original.span();
-                    let func =
-                        quote_spanned!(span=> _serde::de::SeqAccess::next_element::<#field_ty>);
-                    quote!(#func(&mut __seq)?)
+            let visit = if let Some(require_only) = field.attrs.require_only() {
+                let consume = match &require_only.expect {
+                    Some(expected) => quote! {
+                        _serde::de::SeqAccess::next_element_seed(
+                            &mut __seq,
+                            _serde::__private::de::ExpectStr { expected: #expected })?
+                    },
+                    None => quote! {
+                        _serde::de::SeqAccess::next_element::<_serde::de::IgnoredAny>(&mut __seq)?
+                    },
+                };
+                quote! {
+                    _serde::__private::Option::map(
+                        #consume,
+                        |_| _serde::__private::Default::default())
                 }
-                Some(path) => {
-                    let (wrapper, wrapper_ty) = wrap_deserialize_field_with(params, field.ty, path);
-                    quote!({
-                        #wrapper
-                        _serde::__private::Option::map(
-                            _serde::de::SeqAccess::next_element::<#wrapper_ty>(&mut __seq)?,
-                            |__wrap| __wrap.value)
-                    })
+            } else {
+                match field.attrs.deserialize_with() {
+                    None => {
+                        let field_ty = field.ty;
+                        let span = field.original.span();
+                        let func =
+                            quote_spanned!(span=> _serde::de::SeqAccess::next_element::<#field_ty>);
+                        quote!(#func(&mut __seq)?)
+                    }
+                    Some(path) => {
+                        let (wrapper, wrapper_ty) =
+                            wrap_deserialize_field_with(params, field.ty, path);
+                        quote!({
+                            #wrapper
+                            _serde::__private::Option::map(
+                                _serde::de::SeqAccess::next_element::<#wrapper_ty>(&mut __seq)?,
+                                |__wrap| __wrap.value)
+                        })
+                    }
                 }
             };
-            let value_if_none = expr_is_missing_seq(None, index_in_seq, field, cattrs, expecting);
+            // For tuple structs and tuple variants, name the missing element
+            // in the error instead of only reporting the overall length.
+            let element_expecting = if is_struct || cattrs.expecting().is_some() {
+                expecting.to_owned()
+            } else {
+                format!("element {} of {}", index_in_seq, expecting)
+            };
+            let value_if_none =
+                expr_is_missing_seq(None, index_in_seq, field, cattrs, &element_expecting);
             let assign = quote! {
                 let #var = match #visit {
                     _serde::__private::Some(__value) => __value,
@@ -815,8 +1064,36 @@ fn deserialize_seq_in_place(
                 self.place.#member = #default;
             }
         } else {
-            let value_if_none = expr_is_missing_seq(Some(quote!(self.place.#member = )), index_in_seq, field, cattrs, expecting);
-            let write = match field.attrs.deserialize_with() {
+            let element_expecting = match (member, cattrs.expecting()) {
+                (syn::Member::Unnamed(_), None) => {
+                    format!("element {} of {}", index_in_seq, expecting)
+                }
+                _ => expecting.to_owned(),
+            };
+            let value_if_none = expr_is_missing_seq(Some(quote!(self.place.#member = )), index_in_seq, field, cattrs, &element_expecting);
+            let write = if let Some(require_only) = field.attrs.require_only() {
+                let consume = match &require_only.expect {
+                    Some(expected) => quote! {
+                        _serde::de::SeqAccess::next_element_seed(
+                            &mut __seq,
+                            _serde::__private::de::ExpectStr { expected: #expected })?
+                    },
+                    None => quote! {
+                        _serde::de::SeqAccess::next_element::<_serde::de::IgnoredAny>(&mut __seq)?
+                    },
+                };
+                quote! {
+                    match #consume {
+                        _serde::__private::Some(_) => {
+                            self.place.#member = _serde::__private::Default::default();
+                        }
+                        _serde::__private::None => {
+                            #value_if_none;
+                        }
+                    }
+                }
+            } else {
+            match field.attrs.deserialize_with() {
                 None => {
                     quote! {
                         if let _serde::__private::None = _serde::de::SeqAccess::next_element_seed(&mut __seq,
@@ -840,6 +1117,7 @@ fn deserialize_seq_in_place(
                         }
                     })
                 }
+            }
             };
             index_in_seq += 1;
             write
@@ -1239,7 +1517,10 @@ fn deserialize_homogeneous_enum(
     }
 }
 
-fn prepare_enum_variant_enum(variants: &[Variant]) -> (TokenStream, Stmts) {
+fn prepare_enum_variant_enum(
+    variants: &[Variant],
+    cattrs: &attr::Container,
+) -> (TokenStream, Stmts) {
     let deserialized_variants = variants
         .iter()
         .enumerate()
@@ -1253,7 +1534,21 @@ fn prepare_enum_variant_enum(variants: &[Variant]) -> (TokenStream, Stmts) {
             quote!(_serde::__private::Ok(__Field::#ignore_variant))
         });
 
-    let variants_stmt = {
+    let variants_stmt = if cattrs.original_variant_names() {
+        // Error messages name the variants as they are spelled in the source,
+        // regardless of how rename_all restyles them on the wire.
+        let variant_names = deserialized_variants.clone().map(|(_i, variant)| {
+            variant
+                .ident
+                .to_string()
+                .trim_start_matches("r#")
+                .to_owned()
+        });
+        quote! {
+            #[doc(hidden)]
+            const VARIANTS: &'static [&'static str] = &[ #(#variant_names),* ];
+        }
+    } else {
         let variant_names = deserialized_variants
             .clone()
             .flat_map(|(_i, variant)| variant.attrs.aliases());
@@ -1295,7 +1590,7 @@ fn deserialize_externally_tagged_enum(
     let expecting = format!("enum {}", params.type_name());
     let expecting = cattrs.expecting().unwrap_or(&expecting);
 
-    let (variants_stmt, variant_visitor) = prepare_enum_variant_enum(variants);
+    let (variants_stmt, variant_visitor) = prepare_enum_variant_enum(variants, cattrs);
 
     // Match arms to extract a variant from a string
     let variant_arms = variants
@@ -1381,7 +1676,7 @@ fn deserialize_internally_tagged_enum(
     cattrs: &attr::Container,
     tag: &str,
 ) -> Fragment {
-    let (variants_stmt, variant_visitor) = prepare_enum_variant_enum(variants);
+    let (variants_stmt, variant_visitor) = prepare_enum_variant_enum(variants, cattrs);
 
     // Match arms to extract a variant from a string
     let variant_arms = variants
@@ -1435,7 +1730,7 @@ fn deserialize_adjacently_tagged_enum(
         split_with_de_lifetime(params);
     let delife = params.borrowed.de_lifetime();
 
-    let (variants_stmt, variant_visitor) = prepare_enum_variant_enum(variants);
+    let (variants_stmt, variant_visitor) = prepare_enum_variant_enum(variants, cattrs);
 
     let variant_arms: &Vec<_> = &variants
         .iter()
@@ -2217,6 +2512,127 @@ fn deserialize_custom_identifier(
     }
 }
 
+// Identifier sets with more names than this are matched through a preliminary
+// match on the identifier's length. A flat match on string literals compiles
+// to a chain of equality comparisons, which is measurably slow for enums with
+// hundreds of variants; bucketing by length first narrows each lookup to the
+// few names of equal length.
+const LENGTH_BUCKETED_MATCH_THRESHOLD: usize = 16;
+
+fn identifier_str_match(
+    this_value: &TokenStream,
+    deserialized_fields: &[FieldWithAliases],
+    miss: TokenStream,
+) -> TokenStream {
+    let name_count: usize = deserialized_fields
+        .iter()
+        .map(|field| field.aliases.len())
+        .sum();
+    if name_count <= LENGTH_BUCKETED_MATCH_THRESHOLD {
+        let str_mapping = deserialized_fields.iter().map(|field| {
+            let ident = &field.ident;
+            let aliases = field.aliases;
+            // `aliases` also contains a main name
+            quote! {
+                #(
+                    #aliases => _serde::__private::Ok(#this_value::#ident),
+                )*
+            }
+        });
+        quote! {
+            match __value {
+                #(#str_mapping)*
+                _ => #miss,
+            }
+        }
+    } else {
+        let mut buckets: BTreeMap<usize, Vec<TokenStream>> = BTreeMap::new();
+        for field in deserialized_fields {
+            let ident = &field.ident;
+            // `aliases` also contains a main name
+            for alias in field.aliases {
+                buckets
+                    .entry(alias.value.len())
+                    .or_default()
+                    .push(quote!(#alias => _serde::__private::Ok(#this_value::#ident),));
+            }
+        }
+        let len_arms = buckets.iter().map(|(len, arms)| {
+            quote! {
+                #len => match __value {
+                    #(#arms)*
+                    _ => #miss,
+                },
+            }
+        });
+        quote! {
+            match str::len(__value) {
+                #(#len_arms)*
+                _ => #miss,
+            }
+        }
+    }
+}
+
+fn identifier_bytes_match(
+    this_value: &TokenStream,
+    deserialized_fields: &[FieldWithAliases],
+    miss: TokenStream,
+) -> TokenStream {
+    let name_count: usize = deserialized_fields
+        .iter()
+        .map(|field| field.aliases.len())
+        .sum();
+    if name_count <= LENGTH_BUCKETED_MATCH_THRESHOLD {
+        let bytes_mapping = deserialized_fields.iter().map(|field| {
+            let ident = &field.ident;
+            // `aliases` also contains a main name
+            let aliases = field
+                .aliases
+                .iter()
+                .map(|alias| Literal::byte_string(alias.value.as_bytes()));
+            quote! {
+                #(
+                    #aliases => _serde::__private::Ok(#this_value::#ident),
+                )*
+            }
+        });
+        quote! {
+            match __value {
+                #(#bytes_mapping)*
+                _ => #miss,
+            }
+        }
+    } else {
+        let mut buckets: BTreeMap<usize, Vec<TokenStream>> = BTreeMap::new();
+        for field in deserialized_fields {
+            let ident = &field.ident;
+            // `aliases` also contains a main name
+            for alias in field.aliases {
+                let literal = Literal::byte_string(alias.value.as_bytes());
+                buckets
+                    .entry(alias.value.len())
+                    .or_default()
+                    .push(quote!(#literal => _serde::__private::Ok(#this_value::#ident),));
+            }
+        }
+        let len_arms = buckets.iter().map(|(len, arms)| {
+            quote! {
+                #len => match __value {
+                    #(#arms)*
+                    _ => #miss,
+                },
+            }
+        });
+        quote! {
+            match <[u8]>::len(__value) {
+                #(#len_arms)*
+                _ => #miss,
+            }
+        }
+    }
+}
+
 fn deserialize_identifier(
     this_value: &TokenStream,
     deserialized_fields: &[FieldWithAliases],
@@ -2226,30 +2642,6 @@ fn deserialize_identifier(
     collect_other_fields: bool,
     expecting: Option<&str>,
 ) -> Fragment {
-    let str_mapping = deserialized_fields.iter().map(|field| {
-        let ident = &field.ident;
-        let aliases = field.aliases;
-        // `aliases` also contains a main name
-        quote! {
-            #(
-                #aliases => _serde::__private::Ok(#this_value::#ident),
-            )*
-        }
-    });
-    let bytes_mapping = deserialized_fields.iter().map(|field| {
-        let ident = &field.ident;
-        // `aliases` also contains a main name
-        let aliases = field
-            .aliases
-            .iter()
-            .map(|alias| Literal::byte_string(alias.value.as_bytes()));
-        quote! {
-            #(
-                #aliases => _serde::__private::Ok(#this_value::#ident),
-            )*
-        }
-    });
-
     let expecting = expecting.unwrap_or(if is_variant {
         "variant identifier"
     } else {
@@ -2435,36 +2827,56 @@ fn deserialize_identifier(
         }
     };
 
+    let str_match = identifier_str_match(
+        this_value,
+        deserialized_fields,
+        quote!({
+            #value_as_str_content
+            #fallthrough_arm
+        }),
+    );
+    let bytes_match = identifier_bytes_match(
+        this_value,
+        deserialized_fields,
+        quote!({
+            #bytes_to_str
+            #value_as_bytes_content
+            #fallthrough_arm
+        }),
+    );
+
     let visit_borrowed = if fallthrough_borrowed.is_some() || collect_other_fields {
-        let str_mapping = str_mapping.clone();
-        let bytes_mapping = bytes_mapping.clone();
         let fallthrough_borrowed_arm = fallthrough_borrowed.as_ref().unwrap_or(fallthrough_arm);
+        let borrowed_str_match = identifier_str_match(
+            this_value,
+            deserialized_fields,
+            quote!({
+                #value_as_borrowed_str_content
+                #fallthrough_borrowed_arm
+            }),
+        );
+        let borrowed_bytes_match = identifier_bytes_match(
+            this_value,
+            deserialized_fields,
+            quote!({
+                #bytes_to_str
+                #value_as_borrowed_bytes_content
+                #fallthrough_borrowed_arm
+            }),
+        );
         Some(quote! {
             fn visit_borrowed_str<__E>(self, __value: &'de str) -> _serde::__private::Result<Self::Value, __E>
             where
                 __E: _serde::de::Error,
             {
-                match __value {
-                    #(#str_mapping)*
-                    _ => {
-                        #value_as_borrowed_str_content
-                        #fallthrough_borrowed_arm
-                    }
-                }
+                #borrowed_str_match
             }
 
             fn visit_borrowed_bytes<__E>(self, __value: &'de [u8]) -> _serde::__private::Result<Self::Value, __E>
             where
                 __E: _serde::de::Error,
             {
-                match __value {
-                    #(#bytes_mapping)*
-                    _ => {
-                        #bytes_to_str
-                        #value_as_borrowed_bytes_content
-                        #fallthrough_borrowed_arm
-                    }
-                }
+                #borrowed_bytes_match
             }
         })
     } else {
@@ -2482,27 +2894,14 @@ fn deserialize_identifier(
         where
             __E: _serde::de::Error,
         {
-            match __value {
-                #(#str_mapping)*
-                _ => {
-                    #value_as_str_content
-                    #fallthrough_arm
-                }
-            }
+            #str_match
         }
 
         fn visit_bytes<__E>(self, __value: &[u8]) -> _serde::__private::Result<Self::Value, __E>
         where
             __E: _serde::de::Error,
         {
-            match __value {
-                #(#bytes_mapping)*
-                _ => {
-                    #bytes_to_str
-                    #value_as_bytes_content
-                    #fallthrough_arm
-                }
-            }
+            #bytes_match
         }
 
         #visit_borrowed
@@ -2553,27 +2952,49 @@ fn deserialize_map(
         .map(|(field, name)| {
             let deser_name = field.attrs.name().deserialize_name();
 
-            let visit = match field.attrs.deserialize_with() {
-                None => {
-                    let field_ty = field.ty;
-                    let span = field.original.span();
-                    let func =
-                        quote_spanned!(span=> _serde::de::MapAccess::next_value::<#field_ty>);
-                    quote! {
-                        #func(&mut __map)?
+            if field.attrs.allow_unknown_variants_in().is_some() {
+                return unknown_variants_fallback_arm(&fields_names, field, name);
+            }
+
+            let visit = if let Some(require_only) = field.attrs.require_only() {
+                let consume = match &require_only.expect {
+                    Some(expected) => quote! {
+                        _serde::de::MapAccess::next_value_seed(
+                            &mut __map,
+                            _serde::__private::de::ExpectStr { expected: #expected })?
+                    },
+                    None => quote! {
+                        _serde::de::MapAccess::next_value::<_serde::de::IgnoredAny>(&mut __map)?
+                    },
+                };
+                quote!({
+                    #consume;
+                    _serde::__private::Default::default()
+                })
+            } else {
+                match field.attrs.deserialize_with() {
+                    None => {
+                        let field_ty = field.ty;
+                        let span = field.original.span();
+                        let func =
+                            quote_spanned!(span=> _serde::de::MapAccess::next_value::<#field_ty>);
+                        quote! {
+                            #func(&mut __map)?
+                        }
                     }
-                }
-                Some(path) => {
-                    let (wrapper, wrapper_ty) = wrap_deserialize_field_with(params, field.ty, path);
-                    quote!({
-                        #wrapper
-                        match _serde::de::MapAccess::next_value::<#wrapper_ty>(&mut __map) {
-                            _serde::__private::Ok(__wrapper) => __wrapper.value,
-                            _serde::__private::Err(__err) => {
-                                return _serde::__private::Err(__err);
+                    Some(path) => {
+                        let (wrapper, wrapper_ty) =
+                            wrap_deserialize_field_with(params, field.ty, path);
+                        quote!({
+                            #wrapper
+                            match _serde::de::MapAccess::next_value::<#wrapper_ty>(&mut __map) {
+                                _serde::__private::Ok(__wrapper) => __wrapper.value,
+                                _serde::__private::Err(__err) => {
+                                    return _serde::__private::Err(__err);
+                                }
                             }
-                        }
-                    })
+                        })
+                    }
                 }
             };
             quote! {
@@ -2649,11 +3070,54 @@ fn deserialize_map(
                 }
                 Some(path) => quote!(#path),
             };
-            quote! {
-                let #name: #field_ty = #func(
+            let deserialize_flat = quote! {
+                #func(
                     _serde::__private::de::FlatMapDeserializer(
                         &mut __collect,
-                        _serde::__private::PhantomData))?;
+                        _serde::__private::PhantomData))?
+            };
+            // A default on a flatten field applies when the flattened type
+            // consumed none of the collected keys: deserializing it from
+            // nothing would otherwise construct it out of its own inner
+            // defaults, silently ignoring the field default. Inner types
+            // with required fields still error when their keys are absent.
+            let default_expr = match field.attrs.default() {
+                attr::Default::Default => {
+                    let span = field.original.span();
+                    let func = quote_spanned!(span=> _serde::__private::Default::default);
+                    Some(quote!(#func()))
+                }
+                attr::Default::Path(path) => Some(quote_spanned!(path.span()=> #path())),
+                attr::Default::None => match cattrs.default() {
+                    attr::Default::Default | attr::Default::Path(_) => {
+                        let member = &field.member;
+                        Some(quote!(__default.#member))
+                    }
+                    attr::Default::None => None,
+                },
+            };
+            match default_expr {
+                Some(default_expr) => quote! {
+                    let #name: #field_ty = {
+                        let __remaining = __collect
+                            .iter()
+                            .filter(|__entry| __entry.is_some())
+                            .count();
+                        let __value = #deserialize_flat;
+                        let __consumed_nothing = __collect
+                            .iter()
+                            .filter(|__entry| __entry.is_some())
+                            .count() == __remaining;
+                        if __consumed_nothing {
+                            #default_expr
+                        } else {
+                            __value
+                        }
+                    };
+                },
+                None => quote! {
+                    let #name: #field_ty = #deserialize_flat;
+                },
             }
         });
 
@@ -2712,6 +3176,11 @@ fn deserialize_map(
         };
     }
 
+    let group_checks = field_group_checks(
+        &fields_names,
+        |name| quote!(_serde::__private::Option::is_some(&#name)),
+    );
+
     quote_block! {
         #(#let_values)*
 
@@ -2719,6 +3188,8 @@ fn deserialize_map(
 
         #match_keys
 
+        #(#group_checks)*
+
         #let_default
 
         #(#extract_values)*
@@ -2768,23 +3239,45 @@ fn deserialize_map_in_place(
             let deser_name = field.attrs.name().deserialize_name();
             let member = &field.member;
 
-            let visit = match field.attrs.deserialize_with() {
-                None => {
-                    quote! {
-                        _serde::de::MapAccess::next_value_seed(&mut __map, _serde::__private::de::InPlaceSeed(&mut self.place.#member))?
+            if field.attrs.allow_unknown_variants_in().is_some() {
+                return unknown_variants_fallback_arm_in_place(&fields_names, field, name);
+            }
+
+            let visit = if let Some(require_only) = field.attrs.require_only() {
+                let consume = match &require_only.expect {
+                    Some(expected) => quote! {
+                        _serde::de::MapAccess::next_value_seed(
+                            &mut __map,
+                            _serde::__private::de::ExpectStr { expected: #expected })?
+                    },
+                    None => quote! {
+                        _serde::de::MapAccess::next_value::<_serde::de::IgnoredAny>(&mut __map)?
+                    },
+                };
+                quote!({
+                    #consume;
+                    self.place.#member = _serde::__private::Default::default();
+                })
+            } else {
+                match field.attrs.deserialize_with() {
+                    None => {
+                        quote! {
+                            _serde::de::MapAccess::next_value_seed(&mut __map, _serde::__private::de::InPlaceSeed(&mut self.place.#member))?
+                        }
+                    }
+                    Some(path) => {
+                        let (wrapper, wrapper_ty) =
+                            wrap_deserialize_field_with(params, field.ty, path);
+                        quote!({
+                            #wrapper
+                            self.place.#member = match _serde::de::MapAccess::next_value::<#wrapper_ty>(&mut __map) {
+                                _serde::__private::Ok(__wrapper) => __wrapper.value,
+                                _serde::__private::Err(__err) => {
+                                    return _serde::__private::Err(__err);
+                                }
+                            };
+                        })
                     }
-                }
-                Some(path) => {
-                    let (wrapper, wrapper_ty) = wrap_deserialize_field_with(params, field.ty, path);
-                    quote!({
-                        #wrapper
-                        self.place.#member = match _serde::de::MapAccess::next_value::<#wrapper_ty>(&mut __map) {
-                            _serde::__private::Ok(__wrapper) => __wrapper.value,
-                            _serde::__private::Err(__err) => {
-                                return _serde::__private::Err(__err);
-                            }
-                        };
-                    })
                 }
             };
             quote! {
@@ -2877,11 +3370,15 @@ fn deserialize_map_in_place(
         }
     };
 
+    let group_checks = field_group_checks(&fields_names, |name| quote!(#name));
+
     quote_block! {
         #(#let_flags)*
 
         #match_keys
 
+        #(#group_checks)*
+
         #let_default
 
         #(#check_flags)*
@@ -2890,6 +3387,193 @@ fn deserialize_map_in_place(
     }
 }
 
+/// Generates one presence check per `#[serde(group = "...")]` among the
+/// fields, to run after the map keys have been visited. `presence` turns a
+/// field's local variable into a bool saying whether the field was seen:
+/// `Option::is_some` for by-value deserialization, the flag itself for
+/// deserialization in place.
+fn field_group_checks(
+    fields_names: &[(&Field, Ident)],
+    presence: fn(&Ident) -> TokenStream,
+) -> Vec<TokenStream> {
+    let mut groups = Vec::<(&str, bool, Vec<&(&Field, Ident)>)>::new();
+    for field_name in fields_names {
+        let (field, _) = field_name;
+        if field.attrs.skip_deserializing() || field.attrs.flatten() {
+            continue;
+        }
+        let group = match field.attrs.group() {
+            Some(group) => group,
+            None => continue,
+        };
+        match groups.iter_mut().find(|(name, _, _)| *name == group) {
+            Some((_, exactly_one, members)) => {
+                *exactly_one |= field.attrs.exactly_one();
+                members.push(field_name);
+            }
+            None => groups.push((group, field.attrs.exactly_one(), vec![field_name])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(group, exactly_one, members)| {
+            let count = members.iter().map(|(_, name)| {
+                let present = presence(name);
+                quote! {
+                    if #present {
+                        __group_count += 1;
+                    }
+                }
+            });
+            let list = members
+                .iter()
+                .map(|(field, _)| format!("`{}`", field.attrs.name().deserialize_name().value))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let over_msg = format!(
+                "group `{}` {} of fields {}, but {{}} were present",
+                group,
+                if exactly_one {
+                    "requires exactly one"
+                } else {
+                    "allows at most one"
+                },
+                list,
+            );
+            let none_check = if exactly_one {
+                let none_msg = format!(
+                    "group `{}` requires exactly one of fields {}, but none were present",
+                    group, list,
+                );
+                Some(quote! {
+                    if __group_count == 0 {
+                        return _serde::__private::Err(<__A::Error as _serde::de::Error>::custom(#none_msg));
+                    }
+                })
+            } else {
+                None
+            };
+            quote! {
+                {
+                    let mut __group_count = 0usize;
+                    #(#count)*
+                    if __group_count > 1 {
+                        return _serde::__private::Err(<__A::Error as _serde::de::Error>::custom(
+                            format_args!(#over_msg, __group_count)));
+                    }
+                    #none_check
+                }
+            }
+        })
+        .collect()
+}
+
+/// Looks up the sibling field designated by `allow_unknown_variants_in`.
+/// Presence and shape of the sibling are validated during attribute checking.
+fn unknown_variants_sibling<'a>(
+    fields_names: &'a [(&'a Field, Ident)],
+    field: &Field,
+) -> (&'a Field<'a>, &'a Ident) {
+    let sibling = field
+        .attrs
+        .allow_unknown_variants_in()
+        .expect("field has an allow_unknown_variants_in attribute");
+    fields_names
+        .iter()
+        .find_map(|(other, other_name)| match &other.member {
+            syn::Member::Named(ident) if ident == sibling => Some((*other, other_name)),
+            _ => None,
+        })
+        .expect("allow_unknown_variants_in target exists")
+}
+
+/// Match arm for a field whose enum value may carry an unrecognized variant
+/// tag. The value is buffered, and content whose variant tag the enum rejects
+/// is captured raw into the designated sibling field instead of failing the
+/// struct. Any other deserialization error propagates as usual.
+fn unknown_variants_fallback_arm(
+    fields_names: &[(&Field, Ident)],
+    field: &Field,
+    name: &Ident,
+) -> TokenStream {
+    let deser_name = field.attrs.name().deserialize_name();
+    let field_ty = field.ty;
+    let (sibling_field, sibling_var) = unknown_variants_sibling(fields_names, field);
+    let sibling_deser_name = sibling_field.attrs.name().deserialize_name();
+    quote! {
+        __Field::#name => {
+            if _serde::__private::Option::is_some(&#name) {
+                return _serde::__private::Err(<__A::Error as _serde::de::Error>::duplicate_field(#deser_name));
+            }
+            let __content = _serde::de::MapAccess::next_value::<_serde::__private::de::Content>(&mut __map)?;
+            match <#field_ty as _serde::Deserialize>::deserialize(
+                _serde::__private::de::ContentRefDeserializer::<__A::Error>::new(&__content),
+            ) {
+                _serde::__private::Ok(__value) => {
+                    #name = _serde::__private::Some(__value);
+                }
+                _serde::__private::Err(__err) => {
+                    // Only an unrecognized variant tag falls through to the
+                    // sibling; a recognized variant with a malformed payload
+                    // is a real error.
+                    if !_serde::__private::de::is_unknown_variant_error(&__err) {
+                        return _serde::__private::Err(__err);
+                    }
+                    if _serde::__private::Option::is_some(&#sibling_var) {
+                        return _serde::__private::Err(<__A::Error as _serde::de::Error>::duplicate_field(#sibling_deser_name));
+                    }
+                    #sibling_var = _serde::__private::Some(_serde::Deserialize::deserialize(
+                        _serde::__private::de::ContentDeserializer::<__A::Error>::new(__content),
+                    )?);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "deserialize_in_place")]
+fn unknown_variants_fallback_arm_in_place(
+    fields_names: &[(&Field, Ident)],
+    field: &Field,
+    name: &Ident,
+) -> TokenStream {
+    let deser_name = field.attrs.name().deserialize_name();
+    let field_ty = field.ty;
+    let member = &field.member;
+    let (sibling_field, sibling_flag) = unknown_variants_sibling(fields_names, field);
+    let sibling_deser_name = sibling_field.attrs.name().deserialize_name();
+    let sibling_member = &sibling_field.member;
+    quote! {
+        __Field::#name => {
+            if #name {
+                return _serde::__private::Err(<__A::Error as _serde::de::Error>::duplicate_field(#deser_name));
+            }
+            let __content = _serde::de::MapAccess::next_value::<_serde::__private::de::Content>(&mut __map)?;
+            match <#field_ty as _serde::Deserialize>::deserialize(
+                _serde::__private::de::ContentRefDeserializer::<__A::Error>::new(&__content),
+            ) {
+                _serde::__private::Ok(__value) => {
+                    self.place.#member = __value;
+                    #name = true;
+                }
+                _serde::__private::Err(__err) => {
+                    if !_serde::__private::de::is_unknown_variant_error(&__err) {
+                        return _serde::__private::Err(__err);
+                    }
+                    if #sibling_flag {
+                        return _serde::__private::Err(<__A::Error as _serde::de::Error>::duplicate_field(#sibling_deser_name));
+                    }
+                    self.place.#sibling_member = _serde::Deserialize::deserialize(
+                        _serde::__private::de::ContentDeserializer::<__A::Error>::new(__content),
+                    )?;
+                    #sibling_flag = true;
+                }
+            }
+        }
+    }
+}
+
 fn field_i(i: usize) -> Ident {
     Ident::new(&format!("__field{}", i), Span::call_site())
 }
@@ -3038,18 +3722,18 @@ fn expr_is_missing(field: &Field, cattrs: &attr::Container) -> Fragment {
     }
 
     let name = field.attrs.name().deserialize_name();
-    match field.attrs.deserialize_with() {
-        None => {
-            let span = field.original.span();
-            let func = quote_spanned!(span=> _serde::__private::de::missing_field);
-            quote_expr! {
-                #func(#name)?
-            }
+    // A `deserialize_with` synthesized for #[serde(borrow)] on an Option<Cow>
+    // field keeps ordinary Option semantics: a missing field is None, not a
+    // missing-field error.
+    if field.attrs.deserialize_with().is_none() || field.attrs.borrowed_cow_option() {
+        let span = field.original.span();
+        let func = quote_spanned!(span=> _serde::__private::de::missing_field);
+        quote_expr! {
+            #func(#name)?
         }
-        Some(_) => {
-            quote_expr! {
-                return _serde::__private::Err(<__A::Error as _serde::de::Error>::missing_field(#name))
-            }
+    } else {
+        quote_expr! {
+            return _serde::__private::Err(<__A::Error as _serde::de::Error>::missing_field(#name))
         }
     }
 }
@@ -3227,9 +3911,9 @@ fn place_lifetime() -> syn::LifetimeParam {
 fn split_with_de_lifetime(
     params: &Parameters,
 ) -> (
-    DeImplGenerics,
-    DeTypeGenerics,
-    syn::TypeGenerics,
+    DeImplGenerics<'_>,
+    DeTypeGenerics<'_>,
+    syn::TypeGenerics<'_>,
     Option<&syn::WhereClause>,
 ) {
     let de_impl_generics = DeImplGenerics(params);
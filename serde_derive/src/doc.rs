@@ -0,0 +1,104 @@
+use crate::internals::ast::{Container, Data, Style};
+use crate::internals::attr::TagType;
+
+/// Builds the rustdoc text placed on the generated impls by
+/// `#[serde(document_impl)]`: the tagging mode or container shape, the
+/// fields or variants whose serialized name differs from the Rust name, and
+/// the fields which fall back to a default when missing from the input.
+pub fn wire_format_doc(cont: &Container) -> String {
+    let mut doc = String::new();
+
+    match &cont.data {
+        Data::Enum(variants) => {
+            match cont.attrs.tag() {
+                TagType::External => {
+                    doc.push_str("Wire format: externally tagged enum.");
+                }
+                TagType::Internal { tag } => {
+                    doc.push_str(&format!(
+                        "Wire format: internally tagged enum; the `{}` key names the variant.",
+                        tag,
+                    ));
+                }
+                TagType::Adjacent { tag, content } => {
+                    doc.push_str(&format!(
+                        "Wire format: adjacently tagged enum; the `{}` key names the variant and the `{}` key holds its data.",
+                        tag, content,
+                    ));
+                }
+                TagType::None => {
+                    doc.push_str(
+                        "Wire format: untagged enum; the variant is chosen by the shape of the data.",
+                    );
+                }
+            }
+
+            let renames: Vec<String> = variants
+                .iter()
+                .filter(|variant| !variant.attrs.skip_serializing())
+                .filter_map(|variant| {
+                    let rust_name = variant.ident.to_string();
+                    let wire_name = &variant.attrs.name().serialize_name().value;
+                    if *wire_name == rust_name {
+                        None
+                    } else {
+                        Some(format!("`{}` as `{}`", rust_name, wire_name))
+                    }
+                })
+                .collect();
+            if !renames.is_empty() {
+                doc.push_str("\n\nRenamed variants: ");
+                doc.push_str(&renames.join(", "));
+                doc.push('.');
+            }
+        }
+        Data::Struct(style, fields) => {
+            if cont.attrs.transparent() {
+                doc.push_str("Wire format: the single field, serialized without a wrapper.");
+            } else {
+                doc.push_str(match style {
+                    Style::Struct => "Wire format: map keyed by field name.",
+                    Style::Tuple => "Wire format: sequence of the fields in declaration order.",
+                    Style::Newtype => "Wire format: the inner value, serialized directly.",
+                    Style::Unit => "Wire format: unit.",
+                });
+            }
+
+            let renames: Vec<String> = fields
+                .iter()
+                .filter(|field| !field.attrs.skip_serializing())
+                .filter_map(|field| {
+                    let rust_name = match &field.member {
+                        syn::Member::Named(ident) => ident.to_string(),
+                        syn::Member::Unnamed(_) => return None,
+                    };
+                    let wire_name = &field.attrs.name().serialize_name().value;
+                    if *wire_name == rust_name {
+                        None
+                    } else {
+                        Some(format!("`{}` as `{}`", rust_name, wire_name))
+                    }
+                })
+                .collect();
+            if !renames.is_empty() {
+                doc.push_str("\n\nRenamed fields: ");
+                doc.push_str(&renames.join(", "));
+                doc.push('.');
+            }
+
+            let defaults: Vec<String> = fields
+                .iter()
+                .filter(|field| !field.attrs.default().is_none() || !cont.attrs.default().is_none())
+                .filter(|field| !field.attrs.skip_deserializing())
+                .map(|field| format!("`{}`", field.attrs.name().deserialize_name().value))
+                .collect();
+            if !defaults.is_empty() {
+                doc.push_str("\n\nDefaulted when missing: ");
+                doc.push_str(&defaults.join(", "));
+                doc.push('.');
+            }
+        }
+    }
+
+    doc
+}
@@ -80,6 +80,9 @@ impl<'a> Container<'a> {
         match &mut data {
             Data::Enum(variants) => {
                 for variant in variants {
+                    if attrs.accept_original_variant_names() {
+                        variant.attrs.accept_source_name();
+                    }
                     variant.attrs.rename_by_rules(attrs.rename_all_rules());
                     for field in &mut variant.fields {
                         field.attrs.rename_by_rules(
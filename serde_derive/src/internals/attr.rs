@@ -6,6 +6,7 @@ use quote::ToTokens;
 use std::borrow::Cow;
 use std::collections::BTreeSet;
 use std::iter::FromIterator;
+use std::mem;
 use syn::meta::ParseNestedMeta;
 use syn::parse::ParseStream;
 use syn::punctuated::Punctuated;
@@ -156,16 +157,26 @@ impl RenameAllRules {
 pub struct Container {
     name: MultiName,
     transparent: bool,
+    inline_newtypes: bool,
     deny_unknown_fields: bool,
+    sort_keys: bool,
+    document_impl: bool,
     default: Default,
     rename_all_rules: RenameAllRules,
     rename_all_fields_rules: RenameAllRules,
+    accept_original_variant_names: bool,
+    original_variant_names: bool,
     ser_bound: Option<Vec<syn::WherePredicate>>,
     de_bound: Option<Vec<syn::WherePredicate>>,
+    ser_cfg: Option<syn::Meta>,
+    de_cfg: Option<syn::Meta>,
+    serialize_with: Option<syn::ExprPath>,
+    deserialize_with: Option<syn::ExprPath>,
     tag: TagType,
     type_from: Option<syn::Type>,
     type_try_from: Option<syn::Type>,
     type_into: Option<syn::Type>,
+    type_builder: Option<syn::Type>,
     remote: Option<syn::Path>,
     identifier: Identifier,
     serde_path: Option<syn::Path>,
@@ -173,6 +184,12 @@ pub struct Container {
     /// Error message generated when type can't be deserialized
     expecting: Option<String>,
     non_exhaustive: bool,
+    as_string_format: Option<AsStringFormat>,
+    convenience_api: bool,
+    strict_fields: Option<Vec<syn::Ident>>,
+    // Reachable through serde_derive_internals, not used by the derive.
+    #[allow(dead_code)]
+    third_party_attrs: Vec<syn::Meta>,
 }
 
 /// Styles of representing an enum.
@@ -239,26 +256,40 @@ impl Container {
         let mut ser_name = Attr::none(cx, RENAME);
         let mut de_name = Attr::none(cx, RENAME);
         let mut transparent = BoolAttr::none(cx, TRANSPARENT);
+        let mut inline_newtypes = BoolAttr::none(cx, INLINE_NEWTYPES);
         let mut deny_unknown_fields = BoolAttr::none(cx, DENY_UNKNOWN_FIELDS);
+        let mut sort_keys = BoolAttr::none(cx, SORT_KEYS);
+        let mut document_impl = BoolAttr::none(cx, DOCUMENT_IMPL);
         let mut default = Attr::none(cx, DEFAULT);
+        let mut accept_original_variant_names = BoolAttr::none(cx, ACCEPT_ORIGINAL_VARIANT_NAMES);
+        let mut original_variant_names = BoolAttr::none(cx, ORIGINAL_VARIANT_NAMES);
         let mut rename_all_ser_rule = Attr::none(cx, RENAME_ALL);
         let mut rename_all_de_rule = Attr::none(cx, RENAME_ALL);
         let mut rename_all_fields_ser_rule = Attr::none(cx, RENAME_ALL_FIELDS);
         let mut rename_all_fields_de_rule = Attr::none(cx, RENAME_ALL_FIELDS);
         let mut ser_bound = Attr::none(cx, BOUND);
         let mut de_bound = Attr::none(cx, BOUND);
+        let mut ser_cfg = Attr::none(cx, CFG);
+        let mut de_cfg = Attr::none(cx, CFG);
+        let mut serialize_with = Attr::none(cx, SERIALIZE_WITH);
+        let mut deserialize_with = Attr::none(cx, DESERIALIZE_WITH);
         let mut untagged = BoolAttr::none(cx, UNTAGGED);
         let mut internal_tag = Attr::none(cx, TAG);
         let mut content = Attr::none(cx, CONTENT);
         let mut type_from = Attr::none(cx, FROM);
         let mut type_try_from = Attr::none(cx, TRY_FROM);
         let mut type_into = Attr::none(cx, INTO);
+        let mut type_builder = Attr::none(cx, BUILDER);
         let mut remote = Attr::none(cx, REMOTE);
         let mut field_identifier = BoolAttr::none(cx, FIELD_IDENTIFIER);
         let mut variant_identifier = BoolAttr::none(cx, VARIANT_IDENTIFIER);
         let mut serde_path = Attr::none(cx, CRATE);
         let mut expecting = Attr::none(cx, EXPECTING);
         let mut non_exhaustive = false;
+        let mut as_string_format = Attr::none(cx, AS_STRING);
+        let mut convenience_api = BoolAttr::none(cx, CONVENIENCE_API);
+        let mut strict_fields = Attr::none(cx, STRICT_FIELDS);
+        let mut third_party_attrs = Vec::new();
 
         for attr in &item.attrs {
             if attr.path() != SERDE {
@@ -342,9 +373,24 @@ impl Container {
                 } else if meta.path == TRANSPARENT {
                     // #[serde(transparent)]
                     transparent.set_true(meta.path);
+                } else if meta.path == INLINE_NEWTYPES {
+                    // #[serde(inline_newtypes)]
+                    inline_newtypes.set_true(meta.path);
                 } else if meta.path == DENY_UNKNOWN_FIELDS {
                     // #[serde(deny_unknown_fields)]
                     deny_unknown_fields.set_true(meta.path);
+                } else if meta.path == ACCEPT_ORIGINAL_VARIANT_NAMES {
+                    // #[serde(accept_original_variant_names)]
+                    accept_original_variant_names.set_true(meta.path);
+                } else if meta.path == ORIGINAL_VARIANT_NAMES {
+                    // #[serde(original_variant_names)]
+                    original_variant_names.set_true(meta.path);
+                } else if meta.path == SORT_KEYS {
+                    // #[serde(sort_keys)]
+                    sort_keys.set_true(meta.path);
+                } else if meta.path == DOCUMENT_IMPL {
+                    // #[serde(document_impl)]
+                    document_impl.set_true(meta.path);
                 } else if meta.path == DEFAULT {
                     if meta.input.peek(Token![=]) {
                         // #[serde(default = "...")]
@@ -397,6 +443,12 @@ impl Container {
                     let (ser, de) = get_where_predicates(cx, &meta)?;
                     ser_bound.set_opt(&meta.path, ser);
                     de_bound.set_opt(&meta.path, de);
+                } else if meta.path == CFG {
+                    // #[serde(cfg = "feature = \"wire\"")]
+                    // #[serde(cfg(serialize = "...", deserialize = "..."))]
+                    let (ser, de) = get_cfg_predicates(cx, &meta)?;
+                    ser_cfg.set_opt(&meta.path, ser);
+                    de_cfg.set_opt(&meta.path, de);
                 } else if meta.path == UNTAGGED {
                     // #[serde(untagged)]
                     match item.data {
@@ -434,6 +486,31 @@ impl Container {
                             }
                         }
                     }
+                } else if meta.path == WRITE_TYPE_NAME {
+                    // #[serde(write_type_name)]
+                    // #[serde(write_type_name = "__type")]
+                    let tag = if meta.input.peek(Token![=]) {
+                        get_lit_str(cx, WRITE_TYPE_NAME, &meta)?.map(|s| s.value())
+                    } else {
+                        Some("__type".to_owned())
+                    };
+                    if let Some(tag) = tag {
+                        match &item.data {
+                            syn::Data::Struct(syn::DataStruct { fields, .. }) => match fields {
+                                syn::Fields::Named(_) => {
+                                    internal_tag.set(&meta.path, tag);
+                                }
+                                syn::Fields::Unnamed(_) | syn::Fields::Unit => {
+                                    let msg = "#[serde(write_type_name)] can only be used on structs with named fields";
+                                    cx.syn_error(meta.error(msg));
+                                }
+                            },
+                            syn::Data::Enum(_) | syn::Data::Union(_) => {
+                                let msg = "#[serde(write_type_name)] can only be used on structs with named fields";
+                                cx.syn_error(meta.error(msg));
+                            }
+                        }
+                    }
                 } else if meta.path == CONTENT {
                     // #[serde(content = "c")]
                     if let Some(s) = get_lit_str(cx, CONTENT, &meta)? {
@@ -451,6 +528,16 @@ impl Container {
                             }
                         }
                     }
+                } else if meta.path == SERIALIZE_WITH {
+                    // #[serde(serialize_with = "...")]
+                    if let Some(path) = parse_lit_into_expr_path(cx, SERIALIZE_WITH, &meta)? {
+                        serialize_with.set(&meta.path, path);
+                    }
+                } else if meta.path == DESERIALIZE_WITH {
+                    // #[serde(deserialize_with = "...")]
+                    if let Some(path) = parse_lit_into_expr_path(cx, DESERIALIZE_WITH, &meta)? {
+                        deserialize_with.set(&meta.path, path);
+                    }
                 } else if meta.path == FROM {
                     // #[serde(from = "Type")]
                     if let Some(from_ty) = parse_lit_into_ty(cx, FROM, &meta)? {
@@ -466,6 +553,11 @@ impl Container {
                     if let Some(into_ty) = parse_lit_into_ty(cx, INTO, &meta)? {
                         type_into.set_opt(&meta.path, Some(into_ty));
                     }
+                } else if meta.path == BUILDER {
+                    // #[serde(builder = "Type")]
+                    if let Some(builder_ty) = parse_lit_into_ty(cx, BUILDER, &meta)? {
+                        type_builder.set_opt(&meta.path, Some(builder_ty));
+                    }
                 } else if meta.path == REMOTE {
                     // #[serde(remote = "...")]
                     if let Some(path) = parse_lit_into_path(cx, REMOTE, &meta)? {
@@ -491,6 +583,67 @@ impl Container {
                     if let Some(s) = get_lit_str(cx, EXPECTING, &meta)? {
                         expecting.set(&meta.path, s.value());
                     }
+                } else if meta.path == AS_STRING {
+                    // #[serde(as_string(format = "{major}.{minor}.{patch}"))]
+                    match &item.data {
+                        syn::Data::Struct(syn::DataStruct {
+                            fields: syn::Fields::Named(_),
+                            ..
+                        }) => {}
+                        _ => {
+                            let msg = "#[serde(as_string)] can only be used on structs with named fields";
+                            return Err(meta.error(msg));
+                        }
+                    }
+                    meta.parse_nested_meta(|meta| {
+                        if meta.path == FORMAT {
+                            if let Some(s) = get_lit_str(cx, FORMAT, &meta)? {
+                                match AsStringFormat::parse(&s.value()) {
+                                    Ok(format) => as_string_format.set(&meta.path, format),
+                                    Err(msg) => cx.error_spanned_by(s, msg),
+                                }
+                            }
+                        } else {
+                            let msg = "malformed as_string attribute, expected `as_string(format = \"...\")`";
+                            return Err(meta.error(msg));
+                        }
+                        Ok(())
+                    })?;
+                } else if meta.path == CONVENIENCE_API {
+                    // #[serde(convenience_api)]
+                    convenience_api.set_true(meta.path);
+                } else if meta.path == STRICT_FIELDS {
+                    // #[serde(strict_fields(field1, field2))]
+                    match &item.data {
+                        syn::Data::Struct(syn::DataStruct {
+                            fields: syn::Fields::Named(_),
+                            ..
+                        }) => {}
+                        _ => {
+                            let msg = "#[serde(strict_fields)] can only be used on structs with named fields";
+                            return Err(meta.error(msg));
+                        }
+                    }
+                    let mut idents = Vec::new();
+                    meta.parse_nested_meta(|meta| {
+                        match meta.path.get_ident() {
+                            Some(ident) => idents.push(ident.clone()),
+                            None => {
+                                let msg = "malformed strict_fields attribute, expected `strict_fields(field1, field2)`";
+                                return Err(meta.error(msg));
+                            }
+                        }
+                        Ok(())
+                    })?;
+                    strict_fields.set(&meta.path, idents);
+                } else if meta.path.segments.len() > 1 {
+                    // Attributes under a namespaced path, such as
+                    // `#[serde(other::thing = "...")]`, are reserved for
+                    // attribute macros from other crates which rewrite the
+                    // item before the derive runs. The derive does not
+                    // interpret them but records them for consumers of
+                    // serde_derive_internals that recognize the namespace.
+                    third_party_attrs.push(parse_third_party_attr(&meta)?);
                 } else {
                     let path = meta.path.to_token_stream().to_string().replace(' ', "");
                     return Err(
@@ -520,7 +673,10 @@ impl Container {
         Container {
             name: MultiName::from_attrs(Name::from(&unraw(&item.ident)), ser_name, de_name, None),
             transparent: transparent.get(),
+            inline_newtypes: inline_newtypes.get(),
             deny_unknown_fields: deny_unknown_fields.get(),
+            sort_keys: sort_keys.get(),
+            document_impl: document_impl.get(),
             default: default.get().unwrap_or(Default::None),
             rename_all_rules: RenameAllRules {
                 serialize: rename_all_ser_rule.get().unwrap_or(RenameRule::None),
@@ -530,18 +686,29 @@ impl Container {
                 serialize: rename_all_fields_ser_rule.get().unwrap_or(RenameRule::None),
                 deserialize: rename_all_fields_de_rule.get().unwrap_or(RenameRule::None),
             },
+            accept_original_variant_names: accept_original_variant_names.get(),
+            original_variant_names: original_variant_names.get(),
             ser_bound: ser_bound.get(),
             de_bound: de_bound.get(),
+            ser_cfg: ser_cfg.get(),
+            de_cfg: de_cfg.get(),
+            serialize_with: serialize_with.get(),
+            deserialize_with: deserialize_with.get(),
             tag: decide_tag(cx, item, untagged, internal_tag, content),
             type_from: type_from.get(),
             type_try_from: type_try_from.get(),
             type_into: type_into.get(),
+            type_builder: type_builder.get(),
             remote: remote.get(),
             identifier: decide_identifier(cx, item, field_identifier, variant_identifier),
             serde_path: serde_path.get(),
             is_packed,
             expecting: expecting.get(),
             non_exhaustive,
+            as_string_format: as_string_format.get(),
+            convenience_api: convenience_api.get(),
+            strict_fields: strict_fields.get(),
+            third_party_attrs,
         }
     }
 
@@ -561,10 +728,30 @@ impl Container {
         self.transparent
     }
 
+    pub fn inline_newtypes(&self) -> bool {
+        self.inline_newtypes
+    }
+
+    pub fn sort_keys(&self) -> bool {
+        self.sort_keys
+    }
+
+    pub fn accept_original_variant_names(&self) -> bool {
+        self.accept_original_variant_names
+    }
+
+    pub fn original_variant_names(&self) -> bool {
+        self.original_variant_names
+    }
+
     pub fn deny_unknown_fields(&self) -> bool {
         self.deny_unknown_fields
     }
 
+    pub fn document_impl(&self) -> bool {
+        self.document_impl
+    }
+
     pub fn default(&self) -> &Default {
         &self.default
     }
@@ -577,6 +764,22 @@ impl Container {
         self.de_bound.as_ref().map(|vec| &vec[..])
     }
 
+    pub fn ser_cfg(&self) -> Option<&syn::Meta> {
+        self.ser_cfg.as_ref()
+    }
+
+    pub fn de_cfg(&self) -> Option<&syn::Meta> {
+        self.de_cfg.as_ref()
+    }
+
+    pub fn serialize_with(&self) -> Option<&syn::ExprPath> {
+        self.serialize_with.as_ref()
+    }
+
+    pub fn deserialize_with(&self) -> Option<&syn::ExprPath> {
+        self.deserialize_with.as_ref()
+    }
+
     pub fn tag(&self) -> &TagType {
         &self.tag
     }
@@ -593,6 +796,10 @@ impl Container {
         self.type_into.as_ref()
     }
 
+    pub fn type_builder(&self) -> Option<&syn::Type> {
+        self.type_builder.as_ref()
+    }
+
     pub fn remote(&self) -> Option<&syn::Path> {
         self.remote.as_ref()
     }
@@ -609,7 +816,7 @@ impl Container {
         self.serde_path.as_ref()
     }
 
-    pub fn serde_path(&self) -> Cow<syn::Path> {
+    pub fn serde_path(&self) -> Cow<'_, syn::Path> {
         self.custom_serde_path()
             .map_or_else(|| Cow::Owned(parse_quote!(_serde)), Cow::Borrowed)
     }
@@ -623,6 +830,114 @@ impl Container {
     pub fn non_exhaustive(&self) -> bool {
         self.non_exhaustive
     }
+
+    pub fn as_string_format(&self) -> Option<&AsStringFormat> {
+        self.as_string_format.as_ref()
+    }
+
+    pub fn convenience_api(&self) -> bool {
+        self.convenience_api
+    }
+
+    pub fn strict_fields(&self) -> Option<&[syn::Ident]> {
+        self.strict_fields.as_deref()
+    }
+
+    /// Attributes under a namespaced path, such as
+    /// `#[serde(other::thing = "...")]`, typically injected by a third-party
+    /// attribute macro running before the derive. The derive itself ignores
+    /// them.
+    #[allow(dead_code)] // reachable through serde_derive_internals
+    pub fn third_party_attrs(&self) -> &[syn::Meta] {
+        &self.third_party_attrs
+    }
+}
+
+/// Parsed `#[serde(as_string(format = "..."))]` pattern. The pattern is a
+/// sequence of literal text and `{field}` placeholders; `{{` and `}}` escape
+/// literal braces.
+pub struct AsStringFormat {
+    pub pattern: String,
+    pub segments: Vec<AsStringSegment>,
+}
+
+pub enum AsStringSegment {
+    Literal(String),
+    Field(String),
+}
+
+impl AsStringFormat {
+    fn parse(pattern: &str) -> Result<Self, String> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = pattern.chars().peekable();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' => {
+                    if chars.peek() == Some(&'{') {
+                        chars.next();
+                        literal.push('{');
+                        continue;
+                    }
+                    if !literal.is_empty() {
+                        segments.push(AsStringSegment::Literal(mem::take(&mut literal)));
+                    }
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(ch) => name.push(ch),
+                            None => {
+                                return Err(format!(
+                                    "unclosed placeholder in as_string format {:?}",
+                                    pattern,
+                                ));
+                            }
+                        }
+                    }
+                    if name.is_empty() || !name.chars().all(|ch| ch.is_alphanumeric() || ch == '_')
+                    {
+                        return Err(format!(
+                            "invalid field placeholder `{{{}}}` in as_string format {:?}",
+                            name, pattern,
+                        ));
+                    }
+                    if let Some(AsStringSegment::Field(_)) = segments.last() {
+                        return Err(format!(
+                            "as_string format {:?} has adjacent placeholders with no separator between them",
+                            pattern,
+                        ));
+                    }
+                    segments.push(AsStringSegment::Field(name));
+                }
+                '}' => {
+                    if chars.peek() == Some(&'}') {
+                        chars.next();
+                        literal.push('}');
+                    } else {
+                        return Err(format!("unmatched `}}` in as_string format {:?}", pattern,));
+                    }
+                }
+                ch => literal.push(ch),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(AsStringSegment::Literal(literal));
+        }
+        if !segments
+            .iter()
+            .any(|segment| matches!(segment, AsStringSegment::Field(_)))
+        {
+            return Err(format!(
+                "as_string format {:?} contains no field placeholders",
+                pattern,
+            ));
+        }
+        Ok(AsStringFormat {
+            pattern: pattern.to_owned(),
+            segments,
+        })
+    }
 }
 
 fn decide_tag(
@@ -743,6 +1058,9 @@ pub struct Variant {
     deserialize_with: Option<syn::ExprPath>,
     borrow: Option<BorrowAttribute>,
     untagged: bool,
+    // Reachable through serde_derive_internals, not used by the derive.
+    #[allow(dead_code)]
+    third_party_attrs: Vec<syn::Meta>,
 }
 
 struct BorrowAttribute {
@@ -766,6 +1084,7 @@ impl Variant {
         let mut deserialize_with = Attr::none(cx, DESERIALIZE_WITH);
         let mut borrow = Attr::none(cx, BORROW);
         let mut untagged = BoolAttr::none(cx, UNTAGGED);
+        let mut third_party_attrs = Vec::new();
 
         for attr in &variant.attrs {
             if attr.path() != SERDE {
@@ -836,18 +1155,8 @@ impl Variant {
                 } else if meta.path == WITH {
                     // #[serde(with = "...")]
                     if let Some(path) = parse_lit_into_expr_path(cx, WITH, &meta)? {
-                        let mut ser_path = path.clone();
-                        ser_path
-                            .path
-                            .segments
-                            .push(Ident::new("serialize", ser_path.span()).into());
-                        serialize_with.set(&meta.path, ser_path);
-                        let mut de_path = path;
-                        de_path
-                            .path
-                            .segments
-                            .push(Ident::new("deserialize", de_path.span()).into());
-                        deserialize_with.set(&meta.path, de_path);
+                        serialize_with.set(&meta.path, with_function(path.clone(), "serialize"));
+                        deserialize_with.set(&meta.path, with_function(path, "deserialize"));
                     }
                 } else if meta.path == SERIALIZE_WITH {
                     // #[serde(serialize_with = "...")]
@@ -885,6 +1194,10 @@ impl Variant {
                     }
                 } else if meta.path == UNTAGGED {
                     untagged.set_true(&meta.path);
+                } else if meta.path.segments.len() > 1 {
+                    // Namespaced attribute injected by a third-party
+                    // attribute macro; not interpreted by the derive.
+                    third_party_attrs.push(parse_third_party_attr(&meta)?);
                 } else {
                     let path = meta.path.to_token_stream().to_string().replace(' ', "");
                     return Err(
@@ -917,6 +1230,7 @@ impl Variant {
             deserialize_with: deserialize_with.get(),
             borrow: borrow.get(),
             untagged: untagged.get(),
+            third_party_attrs,
         }
     }
 
@@ -943,6 +1257,18 @@ impl Variant {
             .insert(self.name.deserialize.clone());
     }
 
+    // Accept this variant's source spelling on input in addition to its
+    // renamed form. Must run before `rename_by_rules`, while the deserialize
+    // name still holds the source spelling. Explicitly renamed variants are
+    // left alone: their source spelling was never part of the wire format.
+    pub fn accept_source_name(&mut self) {
+        if !self.name.deserialize_renamed {
+            self.name
+                .deserialize_aliases
+                .insert(self.name.deserialize.clone());
+        }
+    }
+
     pub fn rename_all_rules(&self) -> RenameAllRules {
         self.rename_all_rules
     }
@@ -978,6 +1304,15 @@ impl Variant {
     pub fn untagged(&self) -> bool {
         self.untagged
     }
+
+    /// Attributes under a namespaced path, such as
+    /// `#[serde(other::thing = "...")]`, typically injected by a third-party
+    /// attribute macro running before the derive. The derive itself ignores
+    /// them.
+    #[allow(dead_code)] // reachable through serde_derive_internals
+    pub fn third_party_attrs(&self) -> &[syn::Meta] {
+        &self.third_party_attrs
+    }
 }
 
 /// Represents field attribute information
@@ -992,9 +1327,26 @@ pub struct Field {
     ser_bound: Option<Vec<syn::WherePredicate>>,
     de_bound: Option<Vec<syn::WherePredicate>>,
     borrowed_lifetimes: BTreeSet<syn::Lifetime>,
+    borrowed_cow_option: bool,
     getter: Option<syn::ExprPath>,
     flatten: bool,
+    group: Option<String>,
+    allow_unknown_variants_in: Option<String>,
+    exactly_one: bool,
+    also_serialize_as: Option<Name>,
+    require_only: Option<RequireOnly>,
     transparent: bool,
+    // Reachable through serde_derive_internals, not used by the derive.
+    #[allow(dead_code)]
+    third_party_attrs: Vec<syn::Meta>,
+}
+
+/// Parsed `#[serde(require_only)]` attribute. The field must be present in
+/// the input but its value is discarded and the field is filled in with
+/// `Default::default()`. With `require_only(expect = "...")` the value must
+/// additionally be a string equal to the given literal.
+pub struct RequireOnly {
+    pub expect: Option<String>,
 }
 
 /// Represents the default to use for a field when deserializing.
@@ -1039,6 +1391,12 @@ impl Field {
         let mut borrowed_lifetimes = Attr::none(cx, BORROW);
         let mut getter = Attr::none(cx, GETTER);
         let mut flatten = BoolAttr::none(cx, FLATTEN);
+        let mut group = Attr::none(cx, GROUP);
+        let mut exactly_one = BoolAttr::none(cx, EXACTLY_ONE);
+        let mut allow_unknown_variants_in = Attr::none(cx, ALLOW_UNKNOWN_VARIANTS_IN);
+        let mut also_serialize_as = Attr::none(cx, ALSO_SERIALIZE_AS);
+        let mut require_only = Attr::none(cx, REQUIRE_ONLY);
+        let mut third_party_attrs = Vec::new();
 
         let ident = match &field.ident {
             Some(ident) => Name::from(&unraw(ident)),
@@ -1091,6 +1449,11 @@ impl Field {
                     if let Some(s) = get_lit_str(cx, ALIAS, &meta)? {
                         de_aliases.insert(&meta.path, Name::from(&s));
                     }
+                } else if meta.path == ALSO_SERIALIZE_AS {
+                    // #[serde(also_serialize_as = "foo")]
+                    if let Some(s) = get_lit_str(cx, ALSO_SERIALIZE_AS, &meta)? {
+                        also_serialize_as.set(&meta.path, Name::from(&s));
+                    }
                 } else if meta.path == DEFAULT {
                     if meta.input.peek(Token![=]) {
                         // #[serde(default = "...")]
@@ -1126,21 +1489,50 @@ impl Field {
                     if let Some(path) = parse_lit_into_expr_path(cx, DESERIALIZE_WITH, &meta)? {
                         deserialize_with.set(&meta.path, path);
                     }
+                } else if meta.path == REDACT {
+                    // #[serde(redact)]
+                    serialize_with.set(&meta.path, parse_quote!(_serde::ser::middleware::redacted));
+                } else if meta.path == BYTES {
+                    // #[serde(bytes)]
+                    serialize_with.set(
+                        &meta.path,
+                        parse_quote!(_serde::__private::ser::serialize_byte_array),
+                    );
+                    deserialize_with.set(
+                        &meta.path,
+                        parse_quote!(_serde::__private::de::deserialize_byte_array),
+                    );
+                } else if meta.path == ONE_OR_MANY {
+                    // #[serde(one_or_many)]
+                    deserialize_with.set(
+                        &meta.path,
+                        parse_quote!(_serde::__private::de::one_or_many),
+                    );
+                } else if meta.path == REQUIRE_ONLY {
+                    // #[serde(require_only)]
+                    // #[serde(require_only(expect = "v1"))]
+                    if meta.input.peek(token::Paren) {
+                        let mut expect = None;
+                        meta.parse_nested_meta(|meta| {
+                            if meta.path == EXPECT {
+                                if let Some(s) = get_lit_str(cx, EXPECT, &meta)? {
+                                    expect = Some(s.value());
+                                }
+                            } else {
+                                let msg = "malformed require_only attribute, expected `require_only(expect = \"...\")`";
+                                return Err(meta.error(msg));
+                            }
+                            Ok(())
+                        })?;
+                        require_only.set(&meta.path, RequireOnly { expect });
+                    } else {
+                        require_only.set(&meta.path, RequireOnly { expect: None });
+                    }
                 } else if meta.path == WITH {
                     // #[serde(with = "...")]
                     if let Some(path) = parse_lit_into_expr_path(cx, WITH, &meta)? {
-                        let mut ser_path = path.clone();
-                        ser_path
-                            .path
-                            .segments
-                            .push(Ident::new("serialize", ser_path.span()).into());
-                        serialize_with.set(&meta.path, ser_path);
-                        let mut de_path = path;
-                        de_path
-                            .path
-                            .segments
-                            .push(Ident::new("deserialize", de_path.span()).into());
-                        deserialize_with.set(&meta.path, de_path);
+                        serialize_with.set(&meta.path, with_function(path.clone(), "serialize"));
+                        deserialize_with.set(&meta.path, with_function(path, "deserialize"));
                     }
                 } else if meta.path == BOUND {
                     // #[serde(bound = "T: SomeBound")]
@@ -1178,6 +1570,31 @@ impl Field {
                 } else if meta.path == FLATTEN {
                     // #[serde(flatten)]
                     flatten.set_true(&meta.path);
+                } else if meta.path == GROUP {
+                    // #[serde(group = "auth")]
+                    if let Some(s) = get_lit_str(cx, GROUP, &meta)? {
+                        group.set(&meta.path, s.value());
+                    }
+                } else if meta.path == EXACTLY_ONE {
+                    // #[serde(exactly_one)]
+                    exactly_one.set_true(&meta.path);
+                } else if meta.path == ALLOW_UNKNOWN_VARIANTS_IN {
+                    // #[serde(allow_unknown_variants_in = "raw")]
+                    if let Some(s) = get_lit_str(cx, ALLOW_UNKNOWN_VARIANTS_IN, &meta)? {
+                        allow_unknown_variants_in.set(&meta.path, s.value());
+                    }
+                } else if meta.path == UNKNOWN_FIELDS {
+                    // #[serde(unknown_fields)]
+                    //
+                    // Captures every unrecognized key into the field, which
+                    // is expected to be serde::UnknownFields. The capture and
+                    // re-serialization go through the same machinery as
+                    // flattened maps.
+                    flatten.set_true(&meta.path);
+                } else if meta.path.segments.len() > 1 {
+                    // Namespaced attribute injected by a third-party
+                    // attribute macro; not interpreted by the derive.
+                    third_party_attrs.push(parse_third_party_attr(&meta)?);
                 } else {
                     let path = meta.path.to_token_stream().to_string().replace(' ', "");
                     return Err(
@@ -1200,6 +1617,7 @@ impl Field {
         }
 
         let mut borrowed_lifetimes = borrowed_lifetimes.get().unwrap_or_default();
+        let mut borrowed_cow_option = false;
         if !borrowed_lifetimes.is_empty() {
             // Cow<str> and Cow<[u8]> never borrow by default:
             //
@@ -1210,40 +1628,19 @@ impl Field {
             //
             //     impl<'de: 'a, 'a> Deserialize<'de> for Cow<'a, str>
             //     impl<'de: 'a, 'a> Deserialize<'de> for Cow<'a, [u8]>
+            //
+            // The same goes for Cow wrapped in an Option, which forwards
+            // through `visit_some` so zero-copy works through the wrapper.
             if is_cow(&field.ty, is_str) {
-                let mut path = syn::Path {
-                    leading_colon: None,
-                    segments: Punctuated::new(),
-                };
-                let span = Span::call_site();
-                path.segments.push(Ident::new("_serde", span).into());
-                path.segments.push(Ident::new("__private", span).into());
-                path.segments.push(Ident::new("de", span).into());
-                path.segments
-                    .push(Ident::new("borrow_cow_str", span).into());
-                let expr = syn::ExprPath {
-                    attrs: Vec::new(),
-                    qself: None,
-                    path,
-                };
-                deserialize_with.set_if_none(expr);
+                deserialize_with.set_if_none(private_de_function("borrow_cow_str"));
             } else if is_cow(&field.ty, is_slice_u8) {
-                let mut path = syn::Path {
-                    leading_colon: None,
-                    segments: Punctuated::new(),
-                };
-                let span = Span::call_site();
-                path.segments.push(Ident::new("_serde", span).into());
-                path.segments.push(Ident::new("__private", span).into());
-                path.segments.push(Ident::new("de", span).into());
-                path.segments
-                    .push(Ident::new("borrow_cow_bytes", span).into());
-                let expr = syn::ExprPath {
-                    attrs: Vec::new(),
-                    qself: None,
-                    path,
-                };
-                deserialize_with.set_if_none(expr);
+                deserialize_with.set_if_none(private_de_function("borrow_cow_bytes"));
+            } else if is_option(&field.ty, |ty| is_cow(ty, is_str)) {
+                borrowed_cow_option = deserialize_with.value.is_none();
+                deserialize_with.set_if_none(private_de_function("borrow_cow_str_option"));
+            } else if is_option(&field.ty, |ty| is_cow(ty, is_slice_u8)) {
+                borrowed_cow_option = deserialize_with.value.is_none();
+                deserialize_with.set_if_none(private_de_function("borrow_cow_bytes_option"));
             }
         } else if is_implicitly_borrowed(&field.ty) {
             // Types &str and &[u8] are always implicitly borrowed. No need for
@@ -1262,9 +1659,16 @@ impl Field {
             ser_bound: ser_bound.get(),
             de_bound: de_bound.get(),
             borrowed_lifetimes,
+            borrowed_cow_option,
             getter: getter.get(),
             flatten: flatten.get(),
+            group: group.get(),
+            exactly_one: exactly_one.get(),
+            allow_unknown_variants_in: allow_unknown_variants_in.get(),
+            also_serialize_as: also_serialize_as.get(),
+            require_only: require_only.get(),
             transparent: false,
+            third_party_attrs,
         }
     }
 
@@ -1326,6 +1730,13 @@ impl Field {
         &self.borrowed_lifetimes
     }
 
+    // True when `deserialize_with` was synthesized for #[serde(borrow)] on an
+    // Option<Cow<str>> or Option<Cow<[u8]>> field. Such fields keep ordinary
+    // Option semantics when missing from the input.
+    pub fn borrowed_cow_option(&self) -> bool {
+        self.borrowed_cow_option
+    }
+
     pub fn getter(&self) -> Option<&syn::ExprPath> {
         self.getter.as_ref()
     }
@@ -1334,6 +1745,26 @@ impl Field {
         self.flatten
     }
 
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    pub fn allow_unknown_variants_in(&self) -> Option<&str> {
+        self.allow_unknown_variants_in.as_deref()
+    }
+
+    pub fn exactly_one(&self) -> bool {
+        self.exactly_one
+    }
+
+    pub fn also_serialize_as(&self) -> Option<&Name> {
+        self.also_serialize_as.as_ref()
+    }
+
+    pub fn require_only(&self) -> Option<&RequireOnly> {
+        self.require_only.as_ref()
+    }
+
     pub fn transparent(&self) -> bool {
         self.transparent
     }
@@ -1341,6 +1772,15 @@ impl Field {
     pub fn mark_transparent(&mut self) {
         self.transparent = true;
     }
+
+    /// Attributes under a namespaced path, such as
+    /// `#[serde(other::thing = "...")]`, typically injected by a third-party
+    /// attribute macro running before the derive. The derive itself ignores
+    /// them.
+    #[allow(dead_code)] // reachable through serde_derive_internals
+    pub fn third_party_attrs(&self) -> &[syn::Meta] {
+        &self.third_party_attrs
+    }
 }
 
 type SerAndDe<T> = (Option<T>, Option<T>);
@@ -1415,6 +1855,31 @@ fn get_where_predicates(
     Ok((ser.at_most_one(), de.at_most_one()))
 }
 
+fn get_cfg_predicates(cx: &Ctxt, meta: &ParseNestedMeta) -> syn::Result<SerAndDe<syn::Meta>> {
+    let (ser, de) = get_ser_and_de(cx, CFG, meta, parse_lit_into_cfg_predicate)?;
+    Ok((ser.at_most_one(), de.at_most_one()))
+}
+
+fn parse_lit_into_cfg_predicate(
+    cx: &Ctxt,
+    attr_name: Symbol,
+    meta_item_name: Symbol,
+    meta: &ParseNestedMeta,
+) -> syn::Result<Option<syn::Meta>> {
+    let string = match get_lit_str2(cx, attr_name, meta_item_name, meta)? {
+        Some(string) => string,
+        None => return Ok(None),
+    };
+
+    Ok(match string.parse::<syn::Meta>() {
+        Ok(predicate) => Some(predicate),
+        Err(err) => {
+            cx.error_spanned_by(string, err);
+            None
+        }
+    })
+}
+
 fn get_lit_str(
     cx: &Ctxt,
     attr_name: Symbol,
@@ -1481,6 +1946,54 @@ fn parse_lit_into_path(
     })
 }
 
+// Appends `::serialize` or `::deserialize` to a `with = "..."` path. Turbofish
+// arguments on the module, as in `with = "opt_vec::<T, _>"`, are moved onto
+// the function, producing `opt_vec::serialize::<T, _>`, because module paths
+// cannot carry type arguments. The turbofish must cover all of the functions'
+// generic parameters, with `_` standing in for the Serializer / Deserializer
+// parameter.
+fn with_function(mut path: syn::ExprPath, function: &str) -> syn::ExprPath {
+    let span = path.span();
+    let arguments = match path.path.segments.last_mut() {
+        Some(last) => mem::replace(&mut last.arguments, syn::PathArguments::None),
+        None => syn::PathArguments::None,
+    };
+    let mut segment = syn::PathSegment::from(Ident::new(function, span));
+    if let syn::PathArguments::AngleBracketed(mut bracketed) = arguments {
+        bracketed.colon2_token = Some(<Token![::]>::default());
+        segment.arguments = syn::PathArguments::AngleBracketed(bracketed);
+    }
+    path.path.segments.push(segment);
+    path
+}
+
+// Attribute macros from other crates may inject attributes under a namespaced
+// path, such as `#[serde(example::validate = "...")]`, before the derive runs.
+// The derive does not interpret these but parses them into a `syn::Meta` so
+// that consumers of serde_derive_internals can act on the namespaces they
+// recognize. Attributes whose path is a single identifier remain reserved for
+// serde itself.
+fn parse_third_party_attr(meta: &ParseNestedMeta) -> syn::Result<syn::Meta> {
+    let path = meta.path.clone();
+    if meta.input.peek(Token![=]) {
+        Ok(syn::Meta::NameValue(syn::MetaNameValue {
+            path,
+            eq_token: meta.input.parse()?,
+            value: meta.input.parse()?,
+        }))
+    } else if meta.input.peek(token::Paren) {
+        let content;
+        let delimiter = syn::MacroDelimiter::Paren(syn::parenthesized!(content in meta.input));
+        Ok(syn::Meta::List(syn::MetaList {
+            path,
+            delimiter,
+            tokens: content.parse()?,
+        }))
+    } else {
+        Ok(syn::Meta::Path(path))
+    }
+}
+
 fn parse_lit_into_expr_path(
     cx: &Ctxt,
     attr_name: Symbol,
@@ -1588,6 +2101,25 @@ fn parse_lit_into_lifetimes(
     Ok(BTreeSet::new())
 }
 
+// Expression path `_serde::__private::de::$function` used as a synthesized
+// deserialize_with.
+fn private_de_function(function: &str) -> syn::ExprPath {
+    let span = Span::call_site();
+    let mut path = syn::Path {
+        leading_colon: None,
+        segments: Punctuated::new(),
+    };
+    path.segments.push(Ident::new("_serde", span).into());
+    path.segments.push(Ident::new("__private", span).into());
+    path.segments.push(Ident::new("de", span).into());
+    path.segments.push(Ident::new(function, span).into());
+    syn::ExprPath {
+        attrs: Vec::new(),
+        qself: None,
+        path,
+    }
+}
+
 fn is_implicitly_borrowed(ty: &syn::Type) -> bool {
     is_implicitly_borrowed_reference(ty) || is_option(ty, is_implicitly_borrowed_reference)
 }
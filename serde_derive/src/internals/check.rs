@@ -1,5 +1,6 @@
 use crate::internals::ast::{Container, Data, Field, Style};
-use crate::internals::attr::{Default, Identifier, TagType};
+use crate::internals::attr::{AsStringSegment, Default, Identifier, TagType};
+use crate::internals::case::RenameRule;
 use crate::internals::{ungroup, Ctxt, Derive};
 use syn::{Member, Type};
 
@@ -16,6 +17,15 @@ pub fn check(cx: &Ctxt, cont: &mut Container, derive: Derive) {
     check_adjacent_tag_conflict(cx, cont);
     check_transparent(cx, cont, derive);
     check_from_and_try_from(cx, cont);
+    check_delegated_attrs(cx, cont);
+    check_as_string(cx, cont);
+    check_require_only(cx, cont);
+    check_strict_fields(cx, cont);
+    check_sort_keys(cx, cont);
+    check_groups(cx, cont);
+    check_allow_unknown_variants(cx, cont);
+    check_original_variant_names(cx, cont);
+    check_useless_attrs(cx, cont, derive);
 }
 
 // If some field of a tuple struct is marked #[serde(default)] then all fields
@@ -118,6 +128,12 @@ fn check_flatten_field(cx: &Ctxt, style: Style, field: &Field) {
     if !field.attrs.flatten() {
         return;
     }
+    if field.attrs.also_serialize_as().is_some() {
+        cx.error_spanned_by(
+            field.original,
+            "#[serde(also_serialize_as)] cannot be combined with flatten",
+        );
+    }
     match style {
         Style::Tuple => {
             cx.error_spanned_by(
@@ -474,4 +490,372 @@ fn check_from_and_try_from(cx: &Ctxt, cont: &mut Container) {
             "#[serde(from = \"...\")] and #[serde(try_from = \"...\")] conflict with each other",
         );
     }
+    if cont.attrs.type_builder().is_some()
+        && (cont.attrs.type_from().is_some() || cont.attrs.type_try_from().is_some())
+    {
+        cx.error_spanned_by(
+            cont.original,
+            "#[serde(builder = \"...\")] conflicts with #[serde(from = \"...\")] and #[serde(try_from = \"...\")]",
+        );
+    }
+}
+
+// Container attributes that only affect the code generated for one direction
+// are silently dead when that direction is delegated through a proxy type.
+// deny_unknown_fields is forwarded onto the proxy deserialization; the rest
+// are reported as deny-able warnings instead of letting users believe the
+// attribute is honored.
+fn check_delegated_attrs(cx: &Ctxt, cont: &Container) {
+    let de_delegated = cont.attrs.type_from().is_some() || cont.attrs.type_try_from().is_some();
+    let ser_delegated = cont.attrs.type_into().is_some();
+
+    if de_delegated && ser_delegated {
+        match cont.attrs.tag() {
+            TagType::External => {}
+            TagType::None | TagType::Internal { .. } | TagType::Adjacent { .. } => {
+                cx.warning_spanned_by(
+                    cont.original,
+                    "enum representation attribute has no effect when both directions are delegated with #[serde(from/try_from = \"...\")] and #[serde(into = \"...\")]; apply it to the proxy type instead",
+                );
+            }
+        }
+
+        let rename_all = cont.attrs.rename_all_rules();
+        if rename_all.serialize != RenameRule::None || rename_all.deserialize != RenameRule::None {
+            cx.warning_spanned_by(
+                cont.original,
+                "#[serde(rename_all = \"...\")] has no effect when both directions are delegated with #[serde(from/try_from = \"...\")] and #[serde(into = \"...\")]; apply it to the proxy type instead",
+            );
+        }
+    }
+}
+
+// Every field of an as_string struct must appear exactly once in the format
+// pattern, and every placeholder must name a field.
+fn check_as_string(cx: &Ctxt, cont: &Container) {
+    let format = match cont.attrs.as_string_format() {
+        Some(format) => format,
+        None => return,
+    };
+
+    let fields = match &cont.data {
+        Data::Struct(Style::Struct, fields) => fields,
+        // Non-struct usage is rejected while parsing the attribute.
+        _ => return,
+    };
+
+    let mut seen = Vec::new();
+    for segment in &format.segments {
+        let name = match segment {
+            AsStringSegment::Field(name) => name,
+            AsStringSegment::Literal(_) => continue,
+        };
+        if !fields.iter().any(|field| match &field.member {
+            Member::Named(ident) => ident == name,
+            Member::Unnamed(_) => false,
+        }) {
+            cx.error_spanned_by(
+                cont.original,
+                format!(
+                    "as_string placeholder `{{{}}}` does not match any field",
+                    name
+                ),
+            );
+        }
+        if seen.contains(&name) {
+            cx.error_spanned_by(
+                cont.original,
+                format!(
+                    "as_string placeholder `{{{}}}` appears more than once",
+                    name
+                ),
+            );
+        }
+        seen.push(name);
+    }
+
+    for field in fields {
+        let name = match &field.member {
+            Member::Named(ident) => ident.to_string(),
+            Member::Unnamed(_) => continue,
+        };
+        if !seen.iter().any(|seen| **seen == name) {
+            cx.error_spanned_by(
+                cont.original,
+                format!("field `{}` does not appear in the as_string format", name),
+            );
+        }
+    }
+}
+
+// The value of a require_only field is never deserialized, so attributes
+// controlling how the value is deserialized do not make sense on it.
+fn check_require_only(cx: &Ctxt, cont: &Container) {
+    let fields: Vec<&Field> = match &cont.data {
+        Data::Enum(variants) => variants
+            .iter()
+            .flat_map(|variant| &variant.fields)
+            .collect(),
+        Data::Struct(_, fields) => fields.iter().collect(),
+    };
+
+    for field in fields {
+        if field.attrs.require_only().is_none() {
+            continue;
+        }
+        if field.attrs.deserialize_with().is_some() {
+            cx.error_spanned_by(
+                field.original,
+                "#[serde(require_only)] cannot be combined with deserialize_with",
+            );
+        }
+        if field.attrs.flatten() {
+            cx.error_spanned_by(
+                field.original,
+                "#[serde(require_only)] cannot be combined with flatten",
+            );
+        }
+        if field.attrs.skip_deserializing() {
+            cx.error_spanned_by(
+                field.original,
+                "#[serde(require_only)] cannot be combined with skip_deserializing",
+            );
+        }
+    }
+}
+
+// The strict_fields list must match the struct definition exactly, so that
+// adding a field without making a serde decision fails to compile instead of
+// silently changing the serialized format.
+fn check_strict_fields(cx: &Ctxt, cont: &Container) {
+    let declared = match cont.attrs.strict_fields() {
+        Some(declared) => declared,
+        None => return,
+    };
+
+    let fields = match &cont.data {
+        Data::Struct(Style::Struct, fields) => fields,
+        // Non-struct usage is rejected while parsing the attribute.
+        _ => return,
+    };
+
+    for field in fields {
+        let ident = match &field.member {
+            Member::Named(ident) => ident,
+            Member::Unnamed(_) => continue,
+        };
+        if !declared.iter().any(|declared| declared == ident) {
+            cx.error_spanned_by(
+                field.original,
+                format!("field `{}` is missing from the strict_fields list", ident),
+            );
+        }
+    }
+
+    for declared in declared {
+        if !fields.iter().any(|field| match &field.member {
+            Member::Named(ident) => ident == declared,
+            Member::Unnamed(_) => false,
+        }) {
+            cx.error_spanned_by(
+                declared,
+                format!("strict_fields lists unknown field `{}`", declared),
+            );
+        }
+    }
+}
+
+// The field order of a #[serde(sort_keys)] container is rearranged at compile
+// time, which is only meaningful for structs with named fields. Flattened
+// fields emit their entries at runtime, so their keys cannot be interleaved
+// into the sorted order.
+fn check_sort_keys(cx: &Ctxt, cont: &Container) {
+    if !cont.attrs.sort_keys() {
+        return;
+    }
+
+    let fields = match &cont.data {
+        Data::Struct(Style::Struct, fields) => fields,
+        _ => {
+            cx.error_spanned_by(
+                cont.original,
+                "#[serde(sort_keys)] can only be used on structs with named fields",
+            );
+            return;
+        }
+    };
+
+    for field in fields {
+        if field.attrs.flatten() {
+            cx.error_spanned_by(
+                field.original,
+                "#[serde(sort_keys)] cannot be combined with flattened fields",
+            );
+        }
+    }
+}
+
+// Group presence is counted while the keys of a map are being visited, which
+// only covers fields that participate in that loop: flattened and skipped
+// fields never do, and exactly_one is meaningless without a group to count.
+fn check_groups(cx: &Ctxt, cont: &Container) {
+    for field in cont.data.all_fields() {
+        if field.attrs.group().is_some() {
+            if field.attrs.flatten() {
+                cx.error_spanned_by(
+                    field.original,
+                    "#[serde(group)] cannot be combined with flatten",
+                );
+            }
+            if field.attrs.skip_deserializing() {
+                cx.error_spanned_by(
+                    field.original,
+                    "#[serde(group)] cannot be combined with skip_deserializing",
+                );
+            }
+        } else if field.attrs.exactly_one() {
+            cx.error_spanned_by(
+                field.original,
+                "#[serde(exactly_one)] must be used together with #[serde(group)]",
+            );
+        }
+    }
+}
+
+// The unknown-variant fallback buffers the annotated field's value and, when
+// the enum rejects it, stores the raw content into a named sibling field.
+// Both ends of that arrangement are resolved by Rust field name within the
+// same set of named fields, so everything involved has to be a plain,
+// individually-keyed field.
+fn check_allow_unknown_variants(cx: &Ctxt, cont: &Container) {
+    let field_lists: Vec<&[Field]> = match &cont.data {
+        Data::Enum(variants) => variants
+            .iter()
+            .map(|variant| variant.fields.as_slice())
+            .collect(),
+        Data::Struct(_, fields) => vec![fields.as_slice()],
+    };
+
+    for fields in field_lists {
+        for field in fields {
+            let sibling = match field.attrs.allow_unknown_variants_in() {
+                Some(sibling) => sibling,
+                None => continue,
+            };
+            if let Member::Unnamed(_) = field.member {
+                cx.error_spanned_by(
+                    field.original,
+                    "#[serde(allow_unknown_variants_in)] can only be used on named fields",
+                );
+                continue;
+            }
+            if field.attrs.flatten() || field.attrs.skip_deserializing() {
+                cx.error_spanned_by(
+                    field.original,
+                    "#[serde(allow_unknown_variants_in)] cannot be combined with flatten or skip_deserializing",
+                );
+            }
+            if field.attrs.deserialize_with().is_some() {
+                cx.error_spanned_by(
+                    field.original,
+                    "#[serde(allow_unknown_variants_in)] cannot be combined with deserialize_with",
+                );
+            }
+            let sibling_field = fields.iter().find(|other| match &other.member {
+                Member::Named(ident) => ident == sibling,
+                Member::Unnamed(_) => false,
+            });
+            match sibling_field {
+                None => {
+                    cx.error_spanned_by(
+                        field.original,
+                        format!(
+                            "#[serde(allow_unknown_variants_in)] refers to nonexistent field `{}`",
+                            sibling,
+                        ),
+                    );
+                }
+                Some(sibling_field) => {
+                    if sibling_field.member == field.member {
+                        cx.error_spanned_by(
+                            field.original,
+                            "#[serde(allow_unknown_variants_in)] cannot refer to the field itself",
+                        );
+                    } else if sibling_field.attrs.flatten()
+                        || sibling_field.attrs.skip_deserializing()
+                    {
+                        cx.error_spanned_by(
+                            field.original,
+                            format!(
+                                "#[serde(allow_unknown_variants_in)] target `{}` cannot be flattened or skipped",
+                                sibling,
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Attributes that cannot take effect given the other attributes on the same
+// element are reported as warnings rather than errors. Each combination is
+// inert with respect to one side of the data model, so it is reported only
+// while expanding the derive for that side; a derive(Serialize, Deserialize)
+// pair then reports each attribute once. Attributes that are merely unused by
+// the current derive (such as `default` under a standalone derive(Serialize))
+// are not reported, because a derive macro cannot see whether the sibling
+// derive is also being expanded for the same type.
+fn check_useless_attrs(cx: &Ctxt, cont: &Container, derive: Derive) {
+    match derive {
+        Derive::Serialize => {
+            for field in cont.data.all_fields() {
+                if !field.attrs.skip_serializing() {
+                    continue;
+                }
+                if field.attrs.skip_serializing_if().is_some() {
+                    cx.warning_spanned_by(
+                        field.original,
+                        "#[serde(skip_serializing_if)] has no effect on a field that is never serialized",
+                    );
+                }
+                if field.attrs.also_serialize_as().is_some() {
+                    cx.warning_spanned_by(
+                        field.original,
+                        "#[serde(also_serialize_as)] has no effect on a field that is never serialized",
+                    );
+                }
+            }
+        }
+        Derive::Deserialize => {
+            for field in cont.data.all_fields() {
+                if field.attrs.skip_deserializing() && field.attrs.deserialize_with().is_some() {
+                    cx.warning_spanned_by(
+                        field.original,
+                        "#[serde(deserialize_with)] has no effect on a field that is never deserialized",
+                    );
+                }
+            }
+        }
+    }
+}
+
+// The variant naming attributes only make sense on enums; both concern how
+// variant keys are matched and reported during deserialization.
+fn check_original_variant_names(cx: &Ctxt, cont: &Container) {
+    if let Data::Enum(_) = cont.data {
+        return;
+    }
+    if cont.attrs.accept_original_variant_names() {
+        cx.error_spanned_by(
+            cont.original,
+            "#[serde(accept_original_variant_names)] can only be used on enums",
+        );
+    }
+    if cont.attrs.original_variant_names() {
+        cx.error_spanned_by(
+            cont.original,
+            "#[serde(original_variant_names)] can only be used on enums",
+        );
+    }
 }
@@ -1,6 +1,8 @@
+use proc_macro2::TokenStream;
 use quote::ToTokens;
 use std::cell::RefCell;
 use std::fmt::Display;
+use std::mem;
 use std::thread;
 
 /// A type to collect errors together and format them.
@@ -13,6 +15,9 @@ pub struct Ctxt {
     // The contents will be set to `None` during checking. This is so that checking can be
     // enforced.
     errors: RefCell<Option<Vec<syn::Error>>>,
+    // Unlike errors, warnings do not abort expansion. They are collected here
+    // and rendered into the generated code as deny-able diagnostics.
+    warnings: RefCell<Vec<(TokenStream, String)>>,
 }
 
 impl Ctxt {
@@ -22,6 +27,7 @@ impl Ctxt {
     pub fn new() -> Self {
         Ctxt {
             errors: RefCell::new(Some(Vec::new())),
+            warnings: RefCell::new(Vec::new()),
         }
     }
 
@@ -37,6 +43,22 @@ impl Ctxt {
             .push(syn::Error::new_spanned(obj.into_token_stream(), msg));
     }
 
+    /// Add a warning to the context object with a tokenizable object.
+    ///
+    /// The object is used for spanning in warning messages.
+    pub fn warning_spanned_by<A: ToTokens, T: Display>(&self, obj: A, msg: T) {
+        self.warnings
+            .borrow_mut()
+            .push((obj.into_token_stream(), msg.to_string()));
+    }
+
+    /// Take the accumulated warnings, leaving the context empty. Warnings do
+    /// not prevent expansion; the caller is expected to render them into the
+    /// generated code.
+    pub fn take_warnings(&self) -> Vec<(TokenStream, String)> {
+        mem::take(&mut *self.warnings.borrow_mut())
+    }
+
     /// Add one of Syn's parse errors.
     pub fn syn_error(&self, err: syn::Error) {
         self.errors.borrow_mut().as_mut().unwrap().push(err);
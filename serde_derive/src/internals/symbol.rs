@@ -4,28 +4,46 @@ use syn::{Ident, Path};
 #[derive(Copy, Clone)]
 pub struct Symbol(&'static str);
 
+pub const ACCEPT_ORIGINAL_VARIANT_NAMES: Symbol = Symbol("accept_original_variant_names");
 pub const ALIAS: Symbol = Symbol("alias");
+pub const ALLOW_UNKNOWN_VARIANTS_IN: Symbol = Symbol("allow_unknown_variants_in");
+pub const ALSO_SERIALIZE_AS: Symbol = Symbol("also_serialize_as");
+pub const AS_STRING: Symbol = Symbol("as_string");
 pub const BORROW: Symbol = Symbol("borrow");
 pub const BOUND: Symbol = Symbol("bound");
+pub const BUILDER: Symbol = Symbol("builder");
+pub const BYTES: Symbol = Symbol("bytes");
+pub const CFG: Symbol = Symbol("cfg");
 pub const CONTENT: Symbol = Symbol("content");
+pub const CONVENIENCE_API: Symbol = Symbol("convenience_api");
 pub const CRATE: Symbol = Symbol("crate");
 pub const DEFAULT: Symbol = Symbol("default");
 pub const DENY_UNKNOWN_FIELDS: Symbol = Symbol("deny_unknown_fields");
 pub const DESERIALIZE: Symbol = Symbol("deserialize");
 pub const DESERIALIZE_WITH: Symbol = Symbol("deserialize_with");
+pub const DOCUMENT_IMPL: Symbol = Symbol("document_impl");
+pub const EXACTLY_ONE: Symbol = Symbol("exactly_one");
+pub const EXPECT: Symbol = Symbol("expect");
 pub const EXPECTING: Symbol = Symbol("expecting");
 pub const FIELD_IDENTIFIER: Symbol = Symbol("field_identifier");
 pub const FLATTEN: Symbol = Symbol("flatten");
+pub const FORMAT: Symbol = Symbol("format");
 pub const FROM: Symbol = Symbol("from");
 pub const GETTER: Symbol = Symbol("getter");
+pub const GROUP: Symbol = Symbol("group");
+pub const INLINE_NEWTYPES: Symbol = Symbol("inline_newtypes");
 pub const INTO: Symbol = Symbol("into");
 pub const NON_EXHAUSTIVE: Symbol = Symbol("non_exhaustive");
+pub const ONE_OR_MANY: Symbol = Symbol("one_or_many");
+pub const ORIGINAL_VARIANT_NAMES: Symbol = Symbol("original_variant_names");
 pub const OTHER: Symbol = Symbol("other");
+pub const REDACT: Symbol = Symbol("redact");
 pub const REMOTE: Symbol = Symbol("remote");
 pub const RENAME: Symbol = Symbol("rename");
 pub const RENAME_ALL: Symbol = Symbol("rename_all");
 pub const RENAME_ALL_FIELDS: Symbol = Symbol("rename_all_fields");
 pub const REPR: Symbol = Symbol("repr");
+pub const REQUIRE_ONLY: Symbol = Symbol("require_only");
 pub const SERDE: Symbol = Symbol("serde");
 pub const SERIALIZE: Symbol = Symbol("serialize");
 pub const SERIALIZE_WITH: Symbol = Symbol("serialize_with");
@@ -33,12 +51,16 @@ pub const SKIP: Symbol = Symbol("skip");
 pub const SKIP_DESERIALIZING: Symbol = Symbol("skip_deserializing");
 pub const SKIP_SERIALIZING: Symbol = Symbol("skip_serializing");
 pub const SKIP_SERIALIZING_IF: Symbol = Symbol("skip_serializing_if");
+pub const SORT_KEYS: Symbol = Symbol("sort_keys");
+pub const STRICT_FIELDS: Symbol = Symbol("strict_fields");
 pub const TAG: Symbol = Symbol("tag");
 pub const TRANSPARENT: Symbol = Symbol("transparent");
 pub const TRY_FROM: Symbol = Symbol("try_from");
+pub const UNKNOWN_FIELDS: Symbol = Symbol("unknown_fields");
 pub const UNTAGGED: Symbol = Symbol("untagged");
 pub const VARIANT_IDENTIFIER: Symbol = Symbol("variant_identifier");
 pub const WITH: Symbol = Symbol("with");
+pub const WRITE_TYPE_NAME: Symbol = Symbol("write_type_name");
 
 impl PartialEq<Symbol> for Ident {
     fn eq(&self, word: &Symbol) -> bool {
@@ -82,10 +82,12 @@ mod bound;
 mod fragment;
 
 mod de;
+mod doc;
 mod dummy;
 mod pretend;
 mod ser;
 mod this;
+mod warnings;
 
 #[proc_macro_derive(Serialize, attributes(serde))]
 pub fn derive_serialize(input: TokenStream) -> TokenStream {
@@ -2,7 +2,7 @@ use crate::fragment::{Fragment, Match, Stmts};
 use crate::internals::ast::{Container, Data, Field, Style, Variant};
 use crate::internals::name::Name;
 use crate::internals::{attr, replace_receiver, Ctxt, Derive};
-use crate::{bound, dummy, pretend, this};
+use crate::{bound, doc, dummy, pretend, this, warnings};
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, quote_spanned};
 use syn::spanned::Spanned;
@@ -17,6 +17,7 @@ pub fn expand_derive_serialize(input: &mut syn::DeriveInput) -> syn::Result<Toke
         None => return Err(ctxt.check().unwrap_err()),
     };
     precondition(&ctxt, &cont);
+    let useless_attrs = warnings::render(&ctxt);
     ctxt.check()?;
 
     let ident = &cont.ident;
@@ -40,24 +41,124 @@ pub fn expand_derive_serialize(input: &mut syn::DeriveInput) -> syn::Result<Toke
                 }
             }
         }
+    } else if let Some(with) = cont.attrs.serialize_with() {
+        let vis = &input.vis;
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #serde::Serialize for #ident #ty_generics #where_clause {
+                fn serialize<__S>(&self, __serializer: __S) -> #serde::__private::Result<__S::Ok, __S::Error>
+                where
+                    __S: #serde::Serializer,
+                {
+                    #with(self, __serializer)
+                }
+            }
+
+            #[automatically_derived]
+            impl #impl_generics #ident #ty_generics #where_clause {
+                /// Derived serialization of this type, for the container's
+                /// `serialize_with` function to delegate to.
+                #vis fn serialize_fields<__S>(&self, __serializer: __S) -> #serde::__private::Result<__S::Ok, __S::Error>
+                where
+                    __S: #serde::Serializer,
+                {
+                    #body
+                }
+            }
+        }
     } else {
+        let convenience_api = if cont.attrs.convenience_api() {
+            Some(quote! {
+                #[automatically_derived]
+                impl #impl_generics #ident #ty_generics #where_clause {
+                    /// Serializes `self` to bytes using the given data format.
+                    pub fn to_bytes<__F>(&self) -> #serde::__private::Result<#serde::__private::Vec<u8>, __F::SerializeError>
+                    where
+                        __F: #serde::convenience::BytesFormat,
+                    {
+                        __F::serialize_to_bytes(self)
+                    }
+                }
+            })
+        } else {
+            None
+        };
+        // An unsatisfied bound on a concrete type is reported when the
+        // function is type-checked, so a field whose newtype wrapper is not
+        // transparent fails to compile rather than silently serializing with
+        // an extra layer of wrapping.
+        let inline_newtypes_check = if cont.attrs.inline_newtypes() {
+            let field_tys = cont
+                .data
+                .all_fields()
+                .filter(|field| {
+                    !field.attrs.skip_serializing() && field.attrs.serialize_with().is_none()
+                })
+                .map(|field| field.ty);
+            Some(quote! {
+                fn __assert_inline_newtype<__T: ?Sized + #serde::ser::InlineNewtype>() {}
+                #(__assert_inline_newtype::<#field_tys>();)*
+            })
+        } else {
+            None
+        };
+        // A transparent container is exactly the promise the marker trait
+        // makes: its serialized form is its single field's.
+        let inline_newtype_marker = if cont.attrs.transparent() {
+            Some(quote! {
+                #[automatically_derived]
+                impl #impl_generics #serde::ser::InlineNewtype for #ident #ty_generics #where_clause {}
+            })
+        } else {
+            None
+        };
+        // Destructuring the value with an exhaustive pattern turns a field
+        // added to the struct but absent from the strict_fields list into a
+        // compile error, instead of silently changing the serialized format.
+        let strict_fields_check = cont.attrs.strict_fields().map(|fields| {
+            quote! {
+                let Self { #(#fields: _,)* } = *self;
+            }
+        });
+        let wire_format_doc = if cont.attrs.document_impl() {
+            let doc = doc::wire_format_doc(&cont);
+            Some(quote!(#[doc = #doc]))
+        } else {
+            None
+        };
         quote! {
+            #wire_format_doc
             #[automatically_derived]
             impl #impl_generics #serde::Serialize for #ident #ty_generics #where_clause {
                 fn serialize<__S>(&self, __serializer: __S) -> #serde::__private::Result<__S::Ok, __S::Error>
                 where
                     __S: #serde::Serializer,
                 {
+                    #inline_newtypes_check
+                    #strict_fields_check
                     #body
                 }
             }
+            #inline_newtype_marker
+            #convenience_api
         }
     };
 
-    Ok(dummy::wrap_in_const(
+    let generated = dummy::wrap_in_const(
         cont.attrs.custom_serde_path(),
-        impl_block,
-    ))
+        quote! {
+            #useless_attrs
+            #impl_block
+        },
+    );
+
+    Ok(match cont.attrs.ser_cfg() {
+        Some(predicate) => quote! {
+            #[cfg(#predicate)]
+            #generated
+        },
+        None => generated,
+    })
 }
 
 fn precondition(cx: &Ctxt, cont: &Container) {
@@ -170,6 +271,8 @@ fn serialize_body(cont: &Container, params: &Parameters) -> Fragment {
         serialize_transparent(cont, params)
     } else if let Some(type_into) = cont.attrs.type_into() {
         serialize_into(params, type_into)
+    } else if let Some(format) = cont.attrs.as_string_format() {
+        serialize_as_string(cont, params, format)
     } else {
         match &cont.data {
             Data::Enum(variants) => serialize_enum(params, variants, &cont.attrs),
@@ -217,6 +320,48 @@ fn serialize_into(params: &Parameters, type_into: &syn::Type) -> Fragment {
     }
 }
 
+fn serialize_as_string(
+    cont: &Container,
+    params: &Parameters,
+    format: &attr::AsStringFormat,
+) -> Fragment {
+    let fields = match &cont.data {
+        Data::Struct(Style::Struct, fields) => fields,
+        _ => unreachable!("checked in serde_derive_internals"),
+    };
+
+    let mut fmt = String::new();
+    let mut args = Vec::new();
+    for segment in &format.segments {
+        match segment {
+            attr::AsStringSegment::Literal(literal) => {
+                for ch in literal.chars() {
+                    match ch {
+                        '{' => fmt.push_str("{{"),
+                        '}' => fmt.push_str("}}"),
+                        _ => fmt.push(ch),
+                    }
+                }
+            }
+            attr::AsStringSegment::Field(name) => {
+                fmt.push_str("{}");
+                let field = fields
+                    .iter()
+                    .find(|field| match &field.member {
+                        Member::Named(ident) => ident == name,
+                        Member::Unnamed(_) => false,
+                    })
+                    .expect("checked in serde_derive_internals");
+                args.push(get_member(params, field, &field.member));
+            }
+        }
+    }
+
+    quote_expr! {
+        _serde::Serializer::collect_str(__serializer, &format_args!(#fmt, #(#args),*))
+    }
+}
+
 fn serialize_unit_struct(cattrs: &attr::Container) -> Fragment {
     let type_name = cattrs.name().serialize_name();
 
@@ -327,8 +472,13 @@ fn serialize_struct_as_struct(
     fields: &[Field],
     cattrs: &attr::Container,
 ) -> Fragment {
-    let serialize_fields =
-        serialize_struct_visitor(fields, params, false, &StructTrait::SerializeStruct);
+    let serialize_fields = serialize_struct_visitor(
+        fields,
+        params,
+        false,
+        &StructTrait::SerializeStruct,
+        cattrs.sort_keys(),
+    );
 
     let type_name = cattrs.name().serialize_name();
 
@@ -343,11 +493,18 @@ fn serialize_struct_as_struct(
     let let_mut = mut_if(serialized_fields.peek().is_some() || tag_field_exists);
 
     let len = serialized_fields
-        .map(|field| match field.attrs.skip_serializing_if() {
-            None => quote!(1),
-            Some(path) => {
-                let field_expr = get_member(params, field, &field.member);
-                quote!(if #path(#field_expr) { 0 } else { 1 })
+        .map(|field| {
+            let count = if field.attrs.also_serialize_as().is_some() {
+                quote!(2)
+            } else {
+                quote!(1)
+            };
+            match field.attrs.skip_serializing_if() {
+                None => count,
+                Some(path) => {
+                    let field_expr = get_member(params, field, &field.member);
+                    quote!(if #path(#field_expr) { 0 } else { #count })
+                }
             }
         })
         .fold(
@@ -368,8 +525,13 @@ fn serialize_struct_as_map(
     fields: &[Field],
     cattrs: &attr::Container,
 ) -> Fragment {
-    let serialize_fields =
-        serialize_struct_visitor(fields, params, false, &StructTrait::SerializeMap);
+    let serialize_fields = serialize_struct_visitor(
+        fields,
+        params,
+        false,
+        &StructTrait::SerializeMap,
+        cattrs.sort_keys(),
+    );
 
     let tag_field = serialize_struct_tag_field(cattrs, &StructTrait::SerializeMap);
     let tag_field_exists = !tag_field.is_empty();
@@ -896,7 +1058,7 @@ fn serialize_struct_variant(
         }
     };
 
-    let serialize_fields = serialize_struct_visitor(fields, params, true, &struct_trait);
+    let serialize_fields = serialize_struct_visitor(fields, params, true, &struct_trait, false);
 
     let mut serialized_fields = fields
         .iter()
@@ -909,9 +1071,14 @@ fn serialize_struct_variant(
         .map(|field| {
             let member = &field.member;
 
+            let count = if field.attrs.also_serialize_as().is_some() {
+                quote!(2)
+            } else {
+                quote!(1)
+            };
             match field.attrs.skip_serializing_if() {
-                Some(path) => quote!(if #path(#member) { 0 } else { 1 }),
-                None => quote!(1),
+                Some(path) => quote!(if #path(#member) { 0 } else { #count }),
+                None => count,
             }
         })
         .fold(quote!(0), |sum, expr| quote!(#sum + #expr));
@@ -970,7 +1137,7 @@ fn serialize_struct_variant_with_flatten(
     name: &Name,
 ) -> Fragment {
     let struct_trait = StructTrait::SerializeMap;
-    let serialize_fields = serialize_struct_visitor(fields, params, true, &struct_trait);
+    let serialize_fields = serialize_struct_visitor(fields, params, true, &struct_trait, false);
 
     let mut serialized_fields = fields
         .iter()
@@ -1104,10 +1271,21 @@ fn serialize_struct_visitor(
     params: &Parameters,
     is_enum: bool,
     struct_trait: &StructTrait,
+    sort_keys: bool,
 ) -> Vec<TokenStream> {
-    fields
+    let mut fields: Vec<&Field> = fields
         .iter()
         .filter(|&field| !field.attrs.skip_serializing())
+        .collect();
+    if sort_keys {
+        fields.sort_by(|a, b| {
+            let a = &a.attrs.name().serialize_name().value;
+            let b = &b.attrs.name().serialize_name().value;
+            a.cmp(b)
+        });
+    }
+    fields
+        .into_iter()
         .map(|field| {
             let member = &field.member;
 
@@ -1136,9 +1314,18 @@ fn serialize_struct_visitor(
                 }
             } else {
                 let func = struct_trait.serialize_field(span);
-                quote! {
+                let mut ser = quote! {
                     #func(&mut __serde_state, #key_expr, #field_expr)?;
+                };
+                if let Some(old_key) = field.attrs.also_serialize_as() {
+                    // Write the same value a second time under the legacy
+                    // key so that readers which have not yet picked up the
+                    // rename keep working.
+                    ser.extend(quote! {
+                        #func(&mut __serde_state, #old_key, #field_expr)?;
+                    });
                 }
+                ser
             };
 
             match skip {
@@ -1293,8 +1480,11 @@ fn get_member(params: &Parameters, field: &Field, member: &Member) -> TokenStrea
             quote!(_serde::__private::ser::constrain::<#ty>(#inner))
         }
         (true, Some(getter)) => {
+            // The getter may return the field type itself, a reference to
+            // it, or a Cow of it; large fields need not be cloned just to be
+            // serialized.
             let ty = field.ty;
-            quote!(_serde::__private::ser::constrain::<#ty>(&#getter(#self_var)))
+            quote!(_serde::__private::ser::constrain_borrowed::<#ty, _>(&#getter(#self_var)))
         }
         (false, Some(_)) => {
             unreachable!("getter is only allowed for remote impls");
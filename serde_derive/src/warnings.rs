@@ -0,0 +1,27 @@
+use crate::internals::Ctxt;
+use proc_macro2::{Span, TokenStream};
+use quote::quote_spanned;
+
+// The checks in `internals` report attributes that have no effect as warnings
+// rather than errors. There is no stable API for a procedural macro to emit a
+// warning directly, so each one is rendered as a use of a deprecated constant
+// spanned to the offending attribute. The resulting diagnostic fires under the
+// `deprecated` lint, which lets downstream crates escalate it with
+// #[deny(deprecated)] or silence it with #[allow(deprecated)].
+pub fn render(cx: &Ctxt) -> TokenStream {
+    let mut tokens = TokenStream::new();
+    for (obj, message) in cx.take_warnings() {
+        let span = obj
+            .into_iter()
+            .next()
+            .map_or_else(Span::call_site, |tt| tt.span());
+        tokens.extend(quote_spanned! {span=>
+            const _: () = {
+                #[deprecated(note = #message)]
+                const SERDE_ATTRIBUTE_HAS_NO_EFFECT: () = ();
+                SERDE_ATTRIBUTE_HAS_NO_EFFECT
+            };
+        });
+    }
+    tokens
+}
@@ -75,3 +75,84 @@ macro_rules! seq {
         vec
     }};
 }
+
+// Expected token sets for std types whose encoding depends on
+// `is_human_readable`, plus the assertion that checks a value against both
+// profiles. Format crates can assert the same sets to verify how they
+// interact with the std impls in each mode.
+
+macro_rules! assert_tokens_readable_and_compact {
+    ($value:expr, $readable:expr, $compact:expr $(,)?) => {{
+        serde_test::assert_tokens(&$value.clone().readable(), &$readable[..]);
+        serde_test::assert_tokens(&$value.compact(), &$compact[..]);
+    }};
+}
+
+macro_rules! duration_tokens {
+    ($secs:expr, $nanos:expr) => {
+        [
+            Token::Struct {
+                name: "Duration",
+                len: 2,
+            },
+            Token::Str("secs"),
+            Token::U64($secs),
+            Token::Str("nanos"),
+            Token::U32($nanos),
+            Token::StructEnd,
+        ]
+    };
+}
+
+macro_rules! system_time_tokens {
+    ($secs:expr, $nanos:expr) => {
+        [
+            Token::Struct {
+                name: "SystemTime",
+                len: 2,
+            },
+            Token::Str("secs_since_epoch"),
+            Token::U64($secs),
+            Token::Str("nanos_since_epoch"),
+            Token::U32($nanos),
+            Token::StructEnd,
+        ]
+    };
+}
+
+macro_rules! octet_tuple_tokens {
+    ($octets:expr) => {
+        seq![
+            Token::Tuple { len: $octets.len() },
+            $octets.iter().copied().map(Token::U8),
+            Token::TupleEnd,
+        ]
+    };
+}
+
+macro_rules! ip_addr_compact_tokens {
+    ($variant:literal, $octets:expr) => {
+        seq![
+            Token::NewtypeVariant {
+                name: "IpAddr",
+                variant: $variant,
+            },
+            octet_tuple_tokens!($octets),
+        ]
+    };
+}
+
+macro_rules! socket_addr_compact_tokens {
+    ($variant:literal, $octets:expr, $port:expr) => {
+        seq![
+            Token::NewtypeVariant {
+                name: "SocketAddr",
+                variant: $variant,
+            },
+            Token::Tuple { len: 2 },
+            octet_tuple_tokens!($octets),
+            Token::U16($port),
+            Token::TupleEnd,
+        ]
+    };
+}
@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 mod regression {
     automod::dir!("tests/regression");
 }
@@ -782,7 +782,7 @@ fn test_unknown_field_rename_enum() {
             variant: "SailorMoon",
             len: 3,
         }],
-        "unknown variant `SailorMoon`, expected `sailor_moon` or `usagi_tsukino`",
+        "unknown variant `SailorMoon`, expected `sailor_moon` or `usagi_tsukino`, did you mean `sailor_moon`?",
     );
 
     assert_de_tokens_error::<AliasEnum>(
@@ -1076,6 +1076,48 @@ where
     b: B,
 }
 
+#[test]
+fn test_with_module_turbofish() {
+    mod as_is {
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            T: Serialize,
+            S: Serializer,
+        {
+            value.serialize(serializer)
+        }
+
+        pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+        where
+            T: Deserialize<'de>,
+            D: Deserializer<'de>,
+        {
+            T::deserialize(deserializer)
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Struct {
+        #[serde(with = "as_is::<u32, _>")]
+        x: u32,
+    }
+
+    assert_tokens(
+        &Struct { x: 1 },
+        &[
+            Token::Struct {
+                name: "Struct",
+                len: 1,
+            },
+            Token::Str("x"),
+            Token::U32(1),
+            Token::StructEnd,
+        ],
+    );
+}
+
 #[test]
 fn test_serialize_with_struct() {
     let a = 1;
@@ -1489,7 +1531,7 @@ fn test_invalid_length_enum() {
             Token::I32(1),
             Token::TupleVariantEnd,
         ],
-        "invalid length 1, expected tuple variant InvalidLengthEnum::A with 3 elements",
+        "invalid length 1, expected element 1 of tuple variant InvalidLengthEnum::A with 3 elements",
     );
     assert_de_tokens_error::<InvalidLengthEnum>(
         &[
@@ -1501,7 +1543,26 @@ fn test_invalid_length_enum() {
             Token::I32(1),
             Token::TupleVariantEnd,
         ],
-        "invalid length 1, expected tuple variant InvalidLengthEnum::B with 2 elements",
+        "invalid length 1, expected element 1 of tuple variant InvalidLengthEnum::B with 2 elements",
+    );
+}
+
+#[test]
+fn test_invalid_length_tuple_struct() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Rgb(u8, u8, u8);
+
+    assert_de_tokens_error::<Rgb>(
+        &[
+            Token::TupleStruct {
+                name: "Rgb",
+                len: 3,
+            },
+            Token::U8(0),
+            Token::U8(127),
+            Token::TupleStructEnd,
+        ],
+        "invalid length 2, expected element 2 of tuple struct Rgb with 3 elements",
     );
 }
 
@@ -1588,6 +1649,119 @@ fn test_from_into_traits() {
     assert_de_tokens_error::<TryFromU32>(&[Token::U32(5)], "out of range");
 }
 
+#[test]
+fn test_deny_unknown_fields_forwarded_to_proxy() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(from = "ProxyPoint", deny_unknown_fields)]
+    struct Point {
+        x: u32,
+        y: u32,
+    }
+
+    #[derive(Deserialize)]
+    struct ProxyPoint {
+        x: u32,
+        y: u32,
+    }
+
+    impl From<ProxyPoint> for Point {
+        fn from(proxy: ProxyPoint) -> Self {
+            Point {
+                x: proxy.x,
+                y: proxy.y,
+            }
+        }
+    }
+
+    assert_de_tokens(
+        &Point { x: 1, y: 2 },
+        &[
+            Token::Struct {
+                name: "ProxyPoint",
+                len: 2,
+            },
+            Token::Str("x"),
+            Token::U32(1),
+            Token::Str("y"),
+            Token::U32(2),
+            Token::StructEnd,
+        ],
+    );
+
+    // The proxy type alone would ignore the unknown key.
+    assert_de_tokens_error::<Point>(
+        &[
+            Token::Struct {
+                name: "ProxyPoint",
+                len: 3,
+            },
+            Token::Str("x"),
+            Token::U32(1),
+            Token::Str("z"),
+        ],
+        "unknown field `z`, expected `x` or `y`",
+    );
+}
+
+#[test]
+fn test_builder() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(builder = "RangeBuilder")]
+    struct Range {
+        lo: u32,
+        hi: u32,
+    }
+
+    #[derive(Deserialize)]
+    struct RangeBuilder {
+        lo: u32,
+        hi: u32,
+    }
+
+    impl RangeBuilder {
+        fn build(self) -> Result<Range, String> {
+            if self.lo <= self.hi {
+                Ok(Range {
+                    lo: self.lo,
+                    hi: self.hi,
+                })
+            } else {
+                Err(format!("lo {} exceeds hi {}", self.lo, self.hi))
+            }
+        }
+    }
+
+    assert_de_tokens(
+        &Range { lo: 1, hi: 5 },
+        &[
+            Token::Struct {
+                name: "RangeBuilder",
+                len: 2,
+            },
+            Token::Str("lo"),
+            Token::U32(1),
+            Token::Str("hi"),
+            Token::U32(5),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens_error::<Range>(
+        &[
+            Token::Struct {
+                name: "RangeBuilder",
+                len: 2,
+            },
+            Token::Str("lo"),
+            Token::U32(5),
+            Token::Str("hi"),
+            Token::U32(1),
+            Token::StructEnd,
+        ],
+        "lo 5 exceeds hi 1",
+    );
+}
+
 #[test]
 fn test_collect_other() {
     let mut extra = HashMap::new();
@@ -3009,4 +3183,1225 @@ mod flatten {
             }
         }
     }
+
+    #[test]
+    fn flatten_with_default() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Outer {
+            outer: i32,
+            #[serde(flatten, default = "inner_default")]
+            inner: Inner,
+        }
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Inner {
+            a: Option<i32>,
+            b: Option<i32>,
+        }
+
+        fn inner_default() -> Inner {
+            Inner {
+                a: Some(7),
+                b: None,
+            }
+        }
+
+        // No keys belonging to the flattened type: the field default is used
+        // rather than constructing Inner out of its own inner defaults.
+        assert_de_tokens(
+            &Outer {
+                outer: 1,
+                inner: inner_default(),
+            },
+            &[
+                Token::Map { len: None },
+                Token::Str("outer"),
+                Token::I32(1),
+                Token::MapEnd,
+            ],
+        );
+
+        // Any key belonging to the flattened type switches to ordinary
+        // flatten deserialization; absent inner fields get their own
+        // defaults, not pieces of the field default.
+        assert_de_tokens(
+            &Outer {
+                outer: 1,
+                inner: Inner {
+                    a: None,
+                    b: Some(2),
+                },
+            },
+            &[
+                Token::Map { len: None },
+                Token::Str("outer"),
+                Token::I32(1),
+                Token::Str("b"),
+                Token::I32(2),
+                Token::MapEnd,
+            ],
+        );
+
+        // Unknown keys that the flattened type does not consume still leave
+        // the field default in effect.
+        assert_de_tokens(
+            &Outer {
+                outer: 1,
+                inner: inner_default(),
+            },
+            &[
+                Token::Map { len: None },
+                Token::Str("outer"),
+                Token::I32(1),
+                Token::Str("unrelated"),
+                Token::I32(9),
+                Token::MapEnd,
+            ],
+        );
+
+        // A flattened type with a required field still errors when its keys
+        // are absent; the default does not mask genuine failures.
+        #[derive(Debug, PartialEq, Deserialize, Default)]
+        struct Required {
+            c: i32,
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct OuterRequired {
+            #[serde(flatten, default)]
+            inner: Required,
+        }
+
+        assert_de_tokens_error::<OuterRequired>(
+            &[Token::Map { len: None }, Token::MapEnd],
+            "missing field `c`",
+        );
+    }
+}
+
+#[test]
+fn test_redact_field() {
+    #[derive(Debug, PartialEq, Serialize)]
+    struct Credentials {
+        username: &'static str,
+        #[serde(redact)]
+        password: &'static str,
+    }
+
+    assert_ser_tokens(
+        &Credentials {
+            username: "alice",
+            password: "hunter2",
+        },
+        &[
+            Token::Struct {
+                name: "Credentials",
+                len: 2,
+            },
+            Token::Str("username"),
+            Token::Str("alice"),
+            Token::Str("password"),
+            Token::Str("<redacted>"),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_bytes_array_field() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Packet {
+        #[serde(bytes)]
+        header: [u8; 4],
+        len: u16,
+    }
+
+    assert_tokens(
+        &Packet {
+            header: [1, 2, 3, 4],
+            len: 5,
+        },
+        &[
+            Token::Struct {
+                name: "Packet",
+                len: 2,
+            },
+            Token::Str("header"),
+            Token::Bytes(b"\x01\x02\x03\x04"),
+            Token::Str("len"),
+            Token::U16(5),
+            Token::StructEnd,
+        ],
+    );
+
+    // Formats without a native bytes type serialize byte arrays as sequences.
+    assert_de_tokens(
+        &Packet {
+            header: [1, 2, 3, 4],
+            len: 5,
+        },
+        &[
+            Token::Struct {
+                name: "Packet",
+                len: 2,
+            },
+            Token::Str("header"),
+            Token::Seq { len: Some(4) },
+            Token::U8(1),
+            Token::U8(2),
+            Token::U8(3),
+            Token::U8(4),
+            Token::SeqEnd,
+            Token::Str("len"),
+            Token::U16(5),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens_error::<Packet>(
+        &[
+            Token::Struct {
+                name: "Packet",
+                len: 2,
+            },
+            Token::Str("header"),
+            Token::Bytes(b"\x01\x02\x03"),
+        ],
+        "invalid length 3, expected a byte array of length 4",
+    );
+}
+
+#[test]
+fn test_float_canonical_nan() {
+    use serde::de::value::{Error, F32Deserializer, F64Deserializer};
+    use serde::float::{canonical_nan, Float};
+
+    assert_eq!((-0.0f64).canonical().to_bits(), 0.0f64.to_bits());
+    assert_eq!((-0.0f32).canonical().to_bits(), 0.0f32.to_bits());
+    assert_eq!((-f64::NAN).canonical().to_bits(), f64::NAN.to_bits());
+    assert_eq!(1.5f64.canonical().to_bits(), 1.5f64.to_bits());
+
+    let de = F64Deserializer::<Error>::new(-f64::NAN);
+    let value: f64 = canonical_nan::deserialize(de).unwrap();
+    assert_eq!(value.to_bits(), f64::NAN.to_bits());
+
+    let de = F32Deserializer::<Error>::new(-0.0);
+    let value: f32 = canonical_nan::deserialize(de).unwrap();
+    assert_eq!(value.to_bits(), 0.0f32.to_bits());
+
+    #[derive(Serialize)]
+    struct Wrapper {
+        #[serde(with = "serde::float::canonical_nan")]
+        value: f64,
+    }
+
+    assert_ser_tokens(
+        &Wrapper { value: -0.0 },
+        &[
+            Token::Struct {
+                name: "Wrapper",
+                len: 1,
+            },
+            Token::Str("value"),
+            Token::F64(0.0),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_float_reject_nan() {
+    use serde::de::value::{Error, F64Deserializer};
+    use serde::float::reject_nan;
+
+    #[derive(Serialize)]
+    struct Wrapper {
+        #[serde(with = "serde::float::reject_nan")]
+        value: f64,
+    }
+
+    assert_ser_tokens(
+        &Wrapper { value: 1.5 },
+        &[
+            Token::Struct {
+                name: "Wrapper",
+                len: 1,
+            },
+            Token::Str("value"),
+            Token::F64(1.5),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens_error(
+        &Wrapper { value: f64::NAN },
+        &[
+            Token::Struct {
+                name: "Wrapper",
+                len: 1,
+            },
+            Token::Str("value"),
+        ],
+        "NaN is not an allowed value",
+    );
+
+    let de = F64Deserializer::<Error>::new(f64::NAN);
+    let err = reject_nan::deserialize::<f64, _>(de).unwrap_err();
+    assert_eq!(err.to_string(), "NaN is not an allowed value");
+
+    let de = F64Deserializer::<Error>::new(1.5);
+    assert_eq!(reject_nan::deserialize::<f64, _>(de).unwrap(), 1.5);
+}
+
+#[test]
+fn test_map_keys_as_strings() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Scores {
+        #[serde(with = "serde::helpers::map_keys_as_strings")]
+        by_id: BTreeMap<u64, u32>,
+    }
+
+    let mut by_id = BTreeMap::new();
+    by_id.insert(1, 10);
+    by_id.insert(42, 20);
+
+    assert_tokens(
+        &Scores { by_id },
+        &[
+            Token::Struct {
+                name: "Scores",
+                len: 1,
+            },
+            Token::Str("by_id"),
+            Token::Map { len: Some(2) },
+            Token::Str("1"),
+            Token::U32(10),
+            Token::Str("42"),
+            Token::U32(20),
+            Token::MapEnd,
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens_error::<Scores>(
+        &[
+            Token::Struct {
+                name: "Scores",
+                len: 1,
+            },
+            Token::Str("by_id"),
+            Token::Map { len: Some(1) },
+            Token::Str("not a number"),
+            Token::U32(1),
+        ],
+        "invalid digit found in string",
+    );
+}
+
+#[test]
+fn test_cow_slice() {
+    use std::borrow::Cow;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Samples<'a> {
+        #[serde(with = "serde::helpers::cow_slice", borrow)]
+        readings: Cow<'a, [u16]>,
+    }
+
+    assert_tokens(
+        &Samples {
+            readings: Cow::Borrowed(&[1, 2]),
+        },
+        &[
+            Token::Struct {
+                name: "Samples",
+                len: 1,
+            },
+            Token::Str("readings"),
+            Token::Seq { len: Some(2) },
+            Token::U16(1),
+            Token::U16(2),
+            Token::SeqEnd,
+            Token::StructEnd,
+        ],
+    );
+
+    // Deserialization always produces Cow::Owned; there is no borrowed visit
+    // for typed slices in the data model.
+    let de = serde::de::value::SeqDeserializer::<_, serde::de::value::Error>::new(
+        vec![3u16, 4].into_iter(),
+    );
+    let readings: Cow<[u16]> = serde::helpers::cow_slice::deserialize(de).unwrap();
+    assert!(matches!(readings, Cow::Owned(_)));
+    assert_eq!(*readings, [3, 4]);
+}
+
+#[test]
+fn test_lossy_utf8() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        #[serde(with = "serde::helpers::lossy_utf8")]
+        message: String,
+    }
+
+    assert_tokens(
+        &Record {
+            message: "ok".to_owned(),
+        },
+        &[
+            Token::Struct {
+                name: "Record",
+                len: 1,
+            },
+            Token::Str("message"),
+            Token::Str("ok"),
+            Token::StructEnd,
+        ],
+    );
+
+    // Invalid UTF-8 byte input is replaced instead of failing.
+    assert_de_tokens(
+        &Record {
+            message: "a\u{fffd}b".to_owned(),
+        },
+        &[
+            Token::Struct {
+                name: "Record",
+                len: 1,
+            },
+            Token::Str("message"),
+            Token::Bytes(b"a\xffb"),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_one_or_many() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Hosts {
+        #[serde(one_or_many)]
+        names: Vec<String>,
+    }
+
+    // A single value is promoted to a one-element vector.
+    assert_de_tokens(
+        &Hosts {
+            names: vec!["localhost".to_owned()],
+        },
+        &[
+            Token::Struct {
+                name: "Hosts",
+                len: 1,
+            },
+            Token::Str("names"),
+            Token::Str("localhost"),
+            Token::StructEnd,
+        ],
+    );
+
+    // A sequence deserializes element by element, as without the attribute.
+    assert_de_tokens(
+        &Hosts {
+            names: vec!["a".to_owned(), "b".to_owned()],
+        },
+        &[
+            Token::Struct {
+                name: "Hosts",
+                len: 1,
+            },
+            Token::Str("names"),
+            Token::Seq { len: Some(2) },
+            Token::Str("a"),
+            Token::Str("b"),
+            Token::SeqEnd,
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens_error::<Hosts>(
+        &[
+            Token::Struct {
+                name: "Hosts",
+                len: 1,
+            },
+            Token::Str("names"),
+            Token::U32(1),
+        ],
+        "invalid type: integer `1`, expected a string",
+    );
+}
+
+#[test]
+fn test_skip_undecodable() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "type")]
+    enum Event {
+        Created { id: u32 },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Log {
+        #[serde(with = "serde::helpers::skip_undecodable")]
+        events: Vec<Event>,
+    }
+
+    // The element with the unrecognized tag is dropped instead of failing
+    // the whole sequence.
+    assert_de_tokens(
+        &Log {
+            events: vec![Event::Created { id: 1 }, Event::Created { id: 3 }],
+        },
+        &[
+            Token::Struct {
+                name: "Log",
+                len: 1,
+            },
+            Token::Str("events"),
+            Token::Seq { len: Some(3) },
+            Token::Map { len: Some(2) },
+            Token::Str("type"),
+            Token::Str("Created"),
+            Token::Str("id"),
+            Token::U32(1),
+            Token::MapEnd,
+            Token::Map { len: Some(2) },
+            Token::Str("type"),
+            Token::Str("Deleted"),
+            Token::Str("id"),
+            Token::U32(2),
+            Token::MapEnd,
+            Token::Map { len: Some(2) },
+            Token::Str("type"),
+            Token::Str("Created"),
+            Token::Str("id"),
+            Token::U32(3),
+            Token::MapEnd,
+            Token::SeqEnd,
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_convenience_api() {
+    use serde::convenience::BytesFormat;
+    use serde::de::value::{Error as ValueError, U32Deserializer};
+    use serde::ser::Impossible;
+
+    // A minimal format that can only represent a single u32, as four
+    // little-endian bytes.
+    struct U32Le;
+
+    struct U32LeSerializer;
+
+    impl Serializer for U32LeSerializer {
+        type Ok = Vec<u8>;
+        type Error = ValueError;
+        type SerializeSeq = Impossible<Vec<u8>, ValueError>;
+        type SerializeTuple = Impossible<Vec<u8>, ValueError>;
+        type SerializeTupleStruct = Impossible<Vec<u8>, ValueError>;
+        type SerializeTupleVariant = Impossible<Vec<u8>, ValueError>;
+        type SerializeMap = Impossible<Vec<u8>, ValueError>;
+        type SerializeStruct = Impossible<Vec<u8>, ValueError>;
+        type SerializeStructVariant = Impossible<Vec<u8>, ValueError>;
+
+        fn serialize_u32(self, v: u32) -> Result<Vec<u8>, ValueError> {
+            Ok(v.to_le_bytes().to_vec())
+        }
+
+        fn serialize_newtype_struct<T>(
+            self,
+            _name: &'static str,
+            value: &T,
+        ) -> Result<Vec<u8>, ValueError>
+        where
+            T: ?Sized + Serialize,
+        {
+            value.serialize(self)
+        }
+
+        fn serialize_bool(self, _: bool) -> Result<Vec<u8>, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_i8(self, _: i8) -> Result<Vec<u8>, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_i16(self, _: i16) -> Result<Vec<u8>, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_i32(self, _: i32) -> Result<Vec<u8>, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_i64(self, _: i64) -> Result<Vec<u8>, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_u8(self, _: u8) -> Result<Vec<u8>, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_u16(self, _: u16) -> Result<Vec<u8>, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_u64(self, _: u64) -> Result<Vec<u8>, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_f32(self, _: f32) -> Result<Vec<u8>, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_f64(self, _: f64) -> Result<Vec<u8>, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_char(self, _: char) -> Result<Vec<u8>, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_str(self, _: &str) -> Result<Vec<u8>, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_bytes(self, _: &[u8]) -> Result<Vec<u8>, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_none(self) -> Result<Vec<u8>, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_some<T>(self, _: &T) -> Result<Vec<u8>, ValueError>
+        where
+            T: ?Sized + Serialize,
+        {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_unit(self) -> Result<Vec<u8>, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_unit_struct(self, _: &'static str) -> Result<Vec<u8>, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_unit_variant(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+        ) -> Result<Vec<u8>, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_newtype_variant<T>(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+            _: &T,
+        ) -> Result<Vec<u8>, ValueError>
+        where
+            T: ?Sized + Serialize,
+        {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_tuple_struct(
+            self,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Self::SerializeTupleStruct, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_tuple_variant(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Self::SerializeTupleVariant, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_struct(
+            self,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Self::SerializeStruct, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+        fn serialize_struct_variant(
+            self,
+            _: &'static str,
+            _: u32,
+            _: &'static str,
+            _: usize,
+        ) -> Result<Self::SerializeStructVariant, ValueError> {
+            Err(de::Error::custom("unsupported"))
+        }
+    }
+
+    impl BytesFormat for U32Le {
+        type SerializeError = ValueError;
+        type DeserializeError = ValueError;
+
+        fn serialize_to_bytes<T>(value: &T) -> Result<Vec<u8>, ValueError>
+        where
+            T: ?Sized + Serialize,
+        {
+            value.serialize(U32LeSerializer)
+        }
+
+        fn deserialize_from_bytes<'de, T>(bytes: &'de [u8]) -> Result<T, ValueError>
+        where
+            T: Deserialize<'de>,
+        {
+            let array = <[u8; 4]>::try_from(bytes)
+                .map_err(|_| de::Error::custom("expected exactly 4 bytes"))?;
+            T::deserialize(U32Deserializer::new(u32::from_le_bytes(array)))
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(transparent, convenience_api)]
+    struct Code(u32);
+
+    let code = Code(0x01020304);
+    let bytes = code.to_bytes::<U32Le>().unwrap();
+    assert_eq!(bytes, [0x04, 0x03, 0x02, 0x01]);
+    assert_eq!(Code::from_bytes::<U32Le>(&bytes).unwrap(), code);
+
+    let err = Code::from_bytes::<U32Le>(b"too many bytes").unwrap_err();
+    assert_eq!(err.to_string(), "expected exactly 4 bytes");
+}
+
+#[test]
+fn test_require_only() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Packet {
+        #[serde(require_only)]
+        magic: (),
+        data: u32,
+    }
+
+    // The marker field must be present but its value is ignored.
+    assert_de_tokens(
+        &Packet { magic: (), data: 1 },
+        &[
+            Token::Struct {
+                name: "Packet",
+                len: 2,
+            },
+            Token::Str("magic"),
+            Token::U64(0xDEAD),
+            Token::Str("data"),
+            Token::U32(1),
+            Token::StructEnd,
+        ],
+    );
+
+    // Struct deserialized from a sequence.
+    assert_de_tokens(
+        &Packet { magic: (), data: 1 },
+        &[
+            Token::Seq { len: Some(2) },
+            Token::Str("whatever"),
+            Token::U32(1),
+            Token::SeqEnd,
+        ],
+    );
+
+    assert_de_tokens_error::<Packet>(
+        &[
+            Token::Struct {
+                name: "Packet",
+                len: 2,
+            },
+            Token::Str("data"),
+            Token::U32(1),
+            Token::StructEnd,
+        ],
+        "missing field `magic`",
+    );
+}
+
+#[test]
+fn test_require_only_expect() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Versioned {
+        #[serde(require_only(expect = "v1"))]
+        version: (),
+        data: u32,
+    }
+
+    assert_de_tokens(
+        &Versioned {
+            version: (),
+            data: 1,
+        },
+        &[
+            Token::Struct {
+                name: "Versioned",
+                len: 2,
+            },
+            Token::Str("version"),
+            Token::Str("v1"),
+            Token::Str("data"),
+            Token::U32(1),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens_error::<Versioned>(
+        &[
+            Token::Struct {
+                name: "Versioned",
+                len: 2,
+            },
+            Token::Str("version"),
+            Token::Str("v2"),
+        ],
+        "invalid value: string \"v2\", expected the string \"v1\"",
+    );
+}
+
+#[test]
+fn test_third_party_namespaced_attrs() {
+    // Namespaced attributes like `#[serde(other::thing)]` are reserved for
+    // attribute macros from other crates. The derive ignores them rather
+    // than rejecting them as unknown.
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(example::container(a, b))]
+    enum Namespaced {
+        #[serde(example::variant = "x")]
+        Variant {
+            #[serde(example::field)]
+            f: u8,
+        },
+    }
+
+    assert_tokens(
+        &Namespaced::Variant { f: 1 },
+        &[
+            Token::StructVariant {
+                name: "Namespaced",
+                variant: "Variant",
+                len: 1,
+            },
+            Token::Str("f"),
+            Token::U8(1),
+            Token::StructVariantEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_unknown_fields_bag() {
+    use serde::de::event::{from_iter, Event};
+    use serde::UnknownFields;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Document {
+        id: u32,
+        #[serde(unknown_fields)]
+        rest: UnknownFields,
+    }
+
+    let events = vec![
+        Event::MapStart(None),
+        Event::Str("id".into()),
+        Event::U32(7),
+        Event::Str("extra".into()),
+        Event::Str("x".into()),
+        Event::Str("nested".into()),
+        Event::MapStart(Some(1)),
+        Event::Str("k".into()),
+        Event::U32(1),
+        Event::MapEnd,
+        Event::MapEnd,
+    ];
+    let document =
+        Document::deserialize(&mut from_iter::<_, serde::de::value::Error>(events)).unwrap();
+    assert_eq!(document.id, 7);
+    assert_eq!(document.rest.len(), 2);
+
+    // The captured fields are re-serialized verbatim after the known ones.
+    assert_ser_tokens(
+        &document,
+        &[
+            Token::Map { len: None },
+            Token::Str("id"),
+            Token::U32(7),
+            Token::Str("extra"),
+            Token::Str("x"),
+            Token::Str("nested"),
+            Token::Map { len: Some(1) },
+            Token::Str("k"),
+            Token::U32(1),
+            Token::MapEnd,
+            Token::MapEnd,
+        ],
+    );
+
+    let empty = Document {
+        id: 1,
+        rest: UnknownFields::new(),
+    };
+    assert!(empty.rest.is_empty());
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(serialize_with = "checked_temperature_ser")]
+#[serde(deserialize_with = "checked_temperature_de")]
+struct CheckedTemperature {
+    celsius: i32,
+}
+
+fn checked_temperature_ser<S>(value: &CheckedTemperature, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    if value.celsius < -273 {
+        return Err(serde::ser::Error::custom("below absolute zero"));
+    }
+    value.serialize_fields(serializer)
+}
+
+fn checked_temperature_de<'de, D>(deserializer: D) -> Result<CheckedTemperature, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = CheckedTemperature::deserialize_fields(deserializer)?;
+    if value.celsius < -273 {
+        return Err(de::Error::custom("below absolute zero"));
+    }
+    Ok(value)
+}
+
+#[test]
+fn test_container_serialize_with() {
+    assert_tokens(
+        &CheckedTemperature { celsius: 20 },
+        &[
+            Token::Struct {
+                name: "CheckedTemperature",
+                len: 1,
+            },
+            Token::Str("celsius"),
+            Token::I32(20),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens_error(
+        &CheckedTemperature { celsius: -300 },
+        &[],
+        "below absolute zero",
+    );
+
+    assert_de_tokens_error::<CheckedTemperature>(
+        &[
+            Token::Struct {
+                name: "CheckedTemperature",
+                len: 1,
+            },
+            Token::Str("celsius"),
+            Token::I32(-300),
+            Token::StructEnd,
+        ],
+        "below absolute zero",
+    );
+}
+
+#[test]
+fn test_field_groups() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Auth {
+        #[serde(group = "auth", exactly_one)]
+        token: Option<String>,
+        #[serde(group = "auth")]
+        password: Option<String>,
+        #[serde(group = "tls")]
+        cert: Option<String>,
+        #[serde(group = "tls")]
+        key_file: Option<String>,
+    }
+
+    assert_de_tokens(
+        &Auth {
+            token: Some("t".to_owned()),
+            password: None,
+            cert: Some("c".to_owned()),
+            key_file: None,
+        },
+        &[
+            Token::Struct {
+                name: "Auth",
+                len: 2,
+            },
+            Token::Str("token"),
+            Token::Some,
+            Token::Str("t"),
+            Token::Str("cert"),
+            Token::Some,
+            Token::Str("c"),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens_error::<Auth>(
+        &[
+            Token::Struct {
+                name: "Auth",
+                len: 2,
+            },
+            Token::Str("token"),
+            Token::Some,
+            Token::Str("t"),
+            Token::Str("password"),
+            Token::Some,
+            Token::Str("p"),
+            Token::StructEnd,
+        ],
+        "group `auth` requires exactly one of fields `token`, `password`, but 2 were present",
+    );
+
+    assert_de_tokens_error::<Auth>(
+        &[
+            Token::Struct {
+                name: "Auth",
+                len: 0,
+            },
+            Token::StructEnd,
+        ],
+        "group `auth` requires exactly one of fields `token`, `password`, but none were present",
+    );
+
+    assert_de_tokens_error::<Auth>(
+        &[
+            Token::Struct {
+                name: "Auth",
+                len: 3,
+            },
+            Token::Str("token"),
+            Token::Some,
+            Token::Str("t"),
+            Token::Str("cert"),
+            Token::Some,
+            Token::Str("c"),
+            Token::Str("key_file"),
+            Token::Some,
+            Token::Str("k"),
+            Token::StructEnd,
+        ],
+        "group `tls` allows at most one of fields `cert`, `key_file`, but 2 were present",
+    );
+}
+
+#[test]
+fn test_allow_unknown_variants_in() {
+    use serde::de::event::{from_iter, Event};
+    use serde::UnknownFields;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Message {
+        Ping { seq: u32 },
+    }
+
+    #[derive(Serialize, Deserialize, Debug)]
+    struct Envelope {
+        id: u32,
+        #[serde(default, allow_unknown_variants_in = "raw")]
+        msg: Option<Message>,
+        #[serde(default)]
+        raw: Option<UnknownFields>,
+    }
+
+    // A recognized variant deserializes normally and captures nothing.
+    let events = vec![
+        Event::MapStart(None),
+        Event::Str("id".into()),
+        Event::U32(1),
+        Event::Str("msg".into()),
+        Event::MapStart(Some(1)),
+        Event::Str("Ping".into()),
+        Event::MapStart(Some(1)),
+        Event::Str("seq".into()),
+        Event::U32(7),
+        Event::MapEnd,
+        Event::MapEnd,
+        Event::MapEnd,
+    ];
+    let envelope =
+        Envelope::deserialize(&mut from_iter::<_, serde::de::value::Error>(events)).unwrap();
+    assert_eq!(envelope.id, 1);
+    assert_eq!(envelope.msg, Some(Message::Ping { seq: 7 }));
+    assert!(envelope.raw.is_none());
+
+    // An unrecognized tag is captured raw into the sibling instead of
+    // failing the struct.
+    let events = vec![
+        Event::MapStart(None),
+        Event::Str("id".into()),
+        Event::U32(2),
+        Event::Str("msg".into()),
+        Event::MapStart(Some(1)),
+        Event::Str("Pong".into()),
+        Event::MapStart(Some(1)),
+        Event::Str("ack".into()),
+        Event::U32(9),
+        Event::MapEnd,
+        Event::MapEnd,
+        Event::MapEnd,
+    ];
+    let envelope =
+        Envelope::deserialize(&mut from_iter::<_, serde::de::value::Error>(events)).unwrap();
+    assert_eq!(envelope.id, 2);
+    assert!(envelope.msg.is_none());
+    assert_eq!(envelope.raw.as_ref().map(UnknownFields::len), Some(1));
+
+    // A recognized variant with a malformed payload is a real error, not a
+    // candidate for capture.
+    let events = vec![
+        Event::MapStart(None),
+        Event::Str("id".into()),
+        Event::U32(3),
+        Event::Str("msg".into()),
+        Event::MapStart(Some(1)),
+        Event::Str("Ping".into()),
+        Event::MapStart(Some(1)),
+        Event::Str("seq".into()),
+        Event::Str("not a number".into()),
+        Event::MapEnd,
+        Event::MapEnd,
+        Event::MapEnd,
+    ];
+    let error = Envelope::deserialize(&mut from_iter::<_, serde::de::value::Error>(events))
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("invalid type"), "{}", error);
+}
+
+#[test]
+#[allow(deprecated)] // the derive reports the inert skip_serializing_if below
+fn test_useless_attr_warnings_do_not_affect_behavior() {
+    // This attribute combination is reported as a deny-able warning by the
+    // derive, but expansion still succeeds and the inert attribute is simply
+    // ignored.
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Warned {
+        a: u8,
+        #[serde(skip_serializing, skip_serializing_if = "Option::is_none", default)]
+        b: Option<u8>,
+        c: u8,
+    }
+
+    let value = Warned {
+        a: 1,
+        b: Some(2),
+        c: 3,
+    };
+
+    assert_ser_tokens(
+        &value,
+        &[
+            Token::Struct {
+                name: "Warned",
+                len: 2,
+            },
+            Token::Str("a"),
+            Token::U8(1),
+            Token::Str("c"),
+            Token::U8(3),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens(
+        &Warned {
+            a: 1,
+            b: None,
+            c: 3,
+        },
+        &[
+            Token::Struct {
+                name: "Warned",
+                len: 2,
+            },
+            Token::Str("a"),
+            Token::U8(1),
+            Token::Str("c"),
+            Token::U8(3),
+            Token::StructEnd,
+        ],
+    );
+
+    // A representation attribute on a fully delegated container also warns
+    // rather than failing the build; the delegation still wins.
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(from = "u32", into = "u32", rename_all = "UPPERCASE")]
+    enum Delegated {
+        One,
+        Other,
+    }
+
+    impl From<u32> for Delegated {
+        fn from(v: u32) -> Self {
+            match v {
+                1 => Delegated::One,
+                _ => Delegated::Other,
+            }
+        }
+    }
+
+    impl From<Delegated> for u32 {
+        fn from(v: Delegated) -> Self {
+            match v {
+                Delegated::One => 1,
+                Delegated::Other => 0,
+            }
+        }
+    }
+
+    assert_ser_tokens(&Delegated::One, &[Token::U32(1)]);
+    assert_de_tokens(&Delegated::One, &[Token::U32(1)]);
+}
+
+#[test]
+fn test_original_variant_names() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    #[serde(
+        rename_all = "lowercase",
+        original_variant_names,
+        accept_original_variant_names
+    )]
+    enum Op {
+        Create,
+        Delete,
+    }
+
+    // Serialization uses the renamed wire spelling, and deserialization
+    // accepts it.
+    assert_tokens(
+        &Op::Create,
+        &[Token::UnitVariant {
+            name: "Op",
+            variant: "create",
+        }],
+    );
+
+    // The original spelling is also still accepted on input.
+    assert_de_tokens(
+        &Op::Delete,
+        &[Token::UnitVariant {
+            name: "Op",
+            variant: "Delete",
+        }],
+    );
+
+    // Unknown variants are reported in terms of the source spellings.
+    assert_de_tokens_error::<Op>(
+        &[Token::UnitVariant {
+            name: "Op",
+            variant: "update",
+        }],
+        "unknown variant `update`, expected `Create` or `Delete`",
+    );
 }
@@ -161,6 +161,7 @@ fn test_cow() {
 
 #[test]
 fn test_lifetimes() {
+    #[allow(dead_code)]
     #[derive(Deserialize)]
     pub struct Cows<'a, 'b> {
         _copied: Cow<'a, str>,
@@ -177,6 +178,7 @@ fn test_lifetimes() {
         Deserialize::deserialize(deserializer).unwrap()
     }
 
+    #[allow(dead_code)]
     #[derive(Deserialize)]
     pub struct Wrap<'a, 'b> {
         #[serde(borrow = "'b")]
@@ -191,3 +193,95 @@ fn test_lifetimes() {
         Deserialize::deserialize(deserializer).unwrap()
     }
 }
+
+#[test]
+fn test_option_cow() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct OptionalCows<'a, 'b> {
+        #[serde(borrow)]
+        str_cow: Option<Cow<'a, str>>,
+
+        #[serde(borrow)]
+        bytes_cow: Option<Cow<'b, [u8]>>,
+    }
+
+    assert_de_tokens(
+        &OptionalCows {
+            str_cow: Some(Cow::Borrowed("str")),
+            bytes_cow: Some(Cow::Borrowed(b"bytes")),
+        },
+        &[
+            Token::Struct {
+                name: "OptionalCows",
+                len: 2,
+            },
+            Token::BorrowedStr("str_cow"),
+            Token::Some,
+            Token::BorrowedStr("str"),
+            Token::BorrowedStr("bytes_cow"),
+            Token::Some,
+            Token::BorrowedBytes(b"bytes"),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens(
+        &OptionalCows {
+            str_cow: None,
+            bytes_cow: None,
+        },
+        &[
+            Token::Struct {
+                name: "OptionalCows",
+                len: 2,
+            },
+            Token::BorrowedStr("str_cow"),
+            Token::None,
+            Token::BorrowedStr("bytes_cow"),
+            Token::None,
+            Token::StructEnd,
+        ],
+    );
+
+    // Fields absent from the input fall back to None like any other Option,
+    // despite the synthesized deserialize_with.
+    assert_de_tokens(
+        &OptionalCows {
+            str_cow: None,
+            bytes_cow: None,
+        },
+        &[
+            Token::Struct {
+                name: "OptionalCows",
+                len: 0,
+            },
+            Token::StructEnd,
+        ],
+    );
+
+    // Equality of Cow does not distinguish Borrowed from Owned, so check the
+    // variant explicitly through a deserializer producing borrowed data.
+    use serde::de::event::{from_iter, Event};
+
+    let mut de = from_iter::<_, serde::de::value::Error>(vec![
+        Event::MapStart(Some(2)),
+        Event::Str(Cow::Borrowed("str_cow")),
+        Event::Some,
+        Event::Str(Cow::Borrowed("str")),
+        Event::Str(Cow::Borrowed("bytes_cow")),
+        Event::Some,
+        Event::Bytes(Cow::Borrowed(b"bytes")),
+        Event::MapEnd,
+    ]);
+    let cows = OptionalCows::deserialize(&mut de).unwrap();
+    de.end().unwrap();
+
+    match cows.str_cow {
+        Some(Cow::Borrowed("str")) => {}
+        _ => panic!("expected a borrowed string"),
+    }
+    match cows.bytes_cow {
+        Some(Cow::Borrowed(b"bytes")) => {}
+        _ => panic!("expected borrowed bytes"),
+    }
+}
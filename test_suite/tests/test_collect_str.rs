@@ -0,0 +1,237 @@
+//! Byte-for-byte verification of `collect_str`-based Serialize impls.
+//!
+//! serde_test's `Token::Str` cannot distinguish a value that went through
+//! `Serializer::collect_str` from one that called `serialize_str` directly.
+//! The serializer in this file accepts only `collect_str` and captures the
+//! formatted output, so Display-based serializations can be asserted exactly.
+
+use serde::ser::{Error as _, Impossible, Serialize, Serializer};
+use std::fmt::{self, Display};
+
+struct CollectStrSerializer;
+
+#[derive(Debug)]
+struct NotCollectStr(String);
+
+impl Display for NotCollectStr {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.0)
+    }
+}
+
+impl serde::ser::Error for NotCollectStr {
+    fn custom<T: Display>(msg: T) -> Self {
+        NotCollectStr(msg.to_string())
+    }
+}
+
+impl std::error::Error for NotCollectStr {}
+
+macro_rules! reject {
+    ($($func:ident($($arg:ty),*);)*) => {
+        $(
+            fn $func(self, $(_: $arg),*) -> Result<Self::Ok, Self::Error> {
+                Err(NotCollectStr::custom(concat!(
+                    "serialized through ",
+                    stringify!($func),
+                    ", not collect_str",
+                )))
+            }
+        )*
+    };
+}
+
+impl Serializer for CollectStrSerializer {
+    type Ok = String;
+    type Error = NotCollectStr;
+    type SerializeSeq = Impossible<String, NotCollectStr>;
+    type SerializeTuple = Impossible<String, NotCollectStr>;
+    type SerializeTupleStruct = Impossible<String, NotCollectStr>;
+    type SerializeTupleVariant = Impossible<String, NotCollectStr>;
+    type SerializeMap = Impossible<String, NotCollectStr>;
+    type SerializeStruct = Impossible<String, NotCollectStr>;
+    type SerializeStructVariant = Impossible<String, NotCollectStr>;
+
+    fn collect_str<T>(self, value: &T) -> Result<String, NotCollectStr>
+    where
+        T: ?Sized + Display,
+    {
+        Ok(value.to_string())
+    }
+
+    reject! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_char(char);
+        serialize_str(&str);
+        serialize_bytes(&[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(&'static str);
+    }
+
+    fn serialize_some<T>(self, _: &T) -> Result<String, NotCollectStr>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(NotCollectStr::custom(
+            "serialized through serialize_some, not collect_str",
+        ))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<String, NotCollectStr> {
+        Err(NotCollectStr::custom(
+            "serialized through serialize_unit_variant, not collect_str",
+        ))
+    }
+
+    fn serialize_newtype_struct<T>(self, _: &'static str, _: &T) -> Result<String, NotCollectStr>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(NotCollectStr::custom(
+            "serialized through serialize_newtype_struct, not collect_str",
+        ))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<String, NotCollectStr>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(NotCollectStr::custom(
+            "serialized through serialize_newtype_variant, not collect_str",
+        ))
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, NotCollectStr> {
+        Err(NotCollectStr::custom(
+            "serialized through serialize_seq, not collect_str",
+        ))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, NotCollectStr> {
+        Err(NotCollectStr::custom(
+            "serialized through serialize_tuple, not collect_str",
+        ))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, NotCollectStr> {
+        Err(NotCollectStr::custom(
+            "serialized through serialize_tuple_struct, not collect_str",
+        ))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, NotCollectStr> {
+        Err(NotCollectStr::custom(
+            "serialized through serialize_tuple_variant, not collect_str",
+        ))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, NotCollectStr> {
+        Err(NotCollectStr::custom(
+            "serialized through serialize_map, not collect_str",
+        ))
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, NotCollectStr> {
+        Err(NotCollectStr::custom(
+            "serialized through serialize_struct, not collect_str",
+        ))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, NotCollectStr> {
+        Err(NotCollectStr::custom(
+            "serialized through serialize_struct_variant, not collect_str",
+        ))
+    }
+}
+
+/// Asserts that `value` serializes itself with `collect_str` and that the
+/// formatted output is exactly `expected`.
+#[track_caller]
+fn assert_collect_str<T>(value: &T, expected: &str)
+where
+    T: ?Sized + Serialize,
+{
+    match value.serialize(CollectStrSerializer) {
+        Ok(output) => assert_eq!(output, expected),
+        Err(err) => panic!("{}", err),
+    }
+}
+
+#[test]
+fn test_display_based_impl() {
+    struct Celsius(i32);
+
+    impl Display for Celsius {
+        fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "{}\u{b0}C", self.0)
+        }
+    }
+
+    impl Serialize for Celsius {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_str(self)
+        }
+    }
+
+    assert_collect_str(&Celsius(-40), "-40\u{b0}C");
+}
+
+#[test]
+fn test_boxed_error() {
+    let error = Box::<dyn std::error::Error + Send + Sync>::from("disk on fire");
+    assert_collect_str(&error, "disk on fire");
+}
+
+#[test]
+fn test_rejects_serialize_str() {
+    let err = "plain".serialize(CollectStrSerializer).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "serialized through serialize_str, not collect_str"
+    );
+}
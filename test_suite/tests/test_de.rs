@@ -2,7 +2,7 @@
     clippy::cast_lossless,
     clippy::decimal_literal_representation,
     clippy::derive_partial_eq_without_eq,
-    clippy::empty_enum,
+    clippy::empty_enums,
     clippy::manual_assert,
     clippy::needless_pass_by_value,
     clippy::uninlined_format_args,
@@ -13,7 +13,7 @@
 use serde::de::value::{F32Deserializer, F64Deserializer};
 use serde::de::{Deserialize, DeserializeOwned, Deserializer, IntoDeserializer};
 use serde_derive::Deserialize;
-use serde_test::{assert_de_tokens, Configure, Token};
+use serde_test::{assert_de_tokens, assert_de_tokens_error, Configure, Token};
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::default::Default;
 use std::ffi::{CStr, CString, OsString};
@@ -36,6 +36,7 @@ use std::sync::atomic::{AtomicI64, AtomicU64};
 use std::sync::{Arc, Weak as ArcWeak};
 use std::time::{Duration, UNIX_EPOCH};
 
+#[allow(unused_macros)]
 #[macro_use]
 mod macros;
 
@@ -1917,6 +1918,12 @@ fn test_range_from() {
     );
 }
 
+#[test]
+fn test_range_full() {
+    test(.., &[Token::UnitStruct { name: "RangeFull" }]);
+    test(.., &[Token::Unit]);
+}
+
 #[test]
 fn test_range_to() {
     test(
@@ -2385,3 +2392,245 @@ fn test_atomics() {
         test(AtomicU64::load, 8589934592u64);
     }
 }
+
+#[test]
+fn test_boxed_error() {
+    let error: Box<dyn std::error::Error + Send + Sync> =
+        Deserialize::deserialize("oops".into_deserializer())
+            .unwrap_or_else(|e: serde::de::value::Error| panic!("{}", e));
+    assert_eq!(error.to_string(), "oops");
+    assert_eq!(format!("{:?}", error), "\"oops\"");
+}
+
+#[test]
+fn test_next_key_u64() {
+    use serde::de::value::MapDeserializer;
+    use serde::de::MapAccess;
+
+    let mut map: MapDeserializer<_, serde::de::value::Error> =
+        MapDeserializer::new(vec![(7u64, "seven"), (8u64, "eight")].into_iter());
+
+    // The default implementation routes through `next_key_seed`.
+    assert_eq!(map.next_key_u64().unwrap(), Some(7));
+    assert_eq!(map.next_value::<String>().unwrap(), "seven");
+    assert_eq!(MapAccess::next_key_u64(&mut &mut map).unwrap(), Some(8));
+    assert_eq!(map.next_value::<String>().unwrap(), "eight");
+    assert_eq!(map.next_key_u64().unwrap(), None);
+}
+
+#[test]
+fn test_boxed_bytes() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Packet {
+        #[serde(with = "serde::helpers::boxed_bytes")]
+        payload: Box<[u8]>,
+    }
+
+    let expected = Packet {
+        payload: vec![1, 2, 3].into_boxed_slice(),
+    };
+
+    // The format's owned buffer is taken over without copying.
+    assert_de_tokens(
+        &expected,
+        &[
+            Token::Struct {
+                name: "Packet",
+                len: 1,
+            },
+            Token::Str("payload"),
+            Token::ByteBuf(b"\x01\x02\x03"),
+            Token::StructEnd,
+        ],
+    );
+
+    // Sequence input written by the blanket Box<[u8]> impl still works.
+    assert_de_tokens(
+        &expected,
+        &[
+            Token::Struct {
+                name: "Packet",
+                len: 1,
+            },
+            Token::Str("payload"),
+            Token::Seq { len: Some(3) },
+            Token::U8(1),
+            Token::U8(2),
+            Token::U8(3),
+            Token::SeqEnd,
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_os_string_lossy() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Manifest {
+        #[serde(with = "serde::helpers::os_string_lossy")]
+        source: OsString,
+    }
+
+    // A plain string is accepted on every platform, unlike the default
+    // platform-tagged enum encoding.
+    assert_de_tokens(
+        &Manifest {
+            source: OsString::from("src/main.rs"),
+        },
+        &[
+            Token::Struct {
+                name: "Manifest",
+                len: 1,
+            },
+            Token::Str("source"),
+            Token::Str("src/main.rs"),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_budget() {
+    use serde::de::budget::BudgetDeserializer;
+    use serde::de::value::{Error, MapDeserializer, SeqDeserializer, StrDeserializer};
+
+    // Each of the three values and the sequence itself costs one unit.
+    let seq = SeqDeserializer::<_, Error>::new(vec![1u8, 2, 3].into_iter());
+    let ok = Vec::<u8>::deserialize(BudgetDeserializer::new(seq, 4)).unwrap();
+    assert_eq!(ok, vec![1, 2, 3]);
+
+    let seq = SeqDeserializer::<_, Error>::new(vec![1u8, 2, 3].into_iter());
+    let err = Vec::<u8>::deserialize(BudgetDeserializer::new(seq, 3)).unwrap_err();
+    assert_eq!(err.to_string(), "deserialization size budget exceeded");
+
+    // Strings cost a unit per byte on top of the unit for the value, so a
+    // short claimed length cannot smuggle in a huge payload.
+    let str_de = StrDeserializer::<Error>::new("budgeted");
+    assert!(String::deserialize(BudgetDeserializer::new(str_de, 9)).is_ok());
+    let str_de = StrDeserializer::<Error>::new("budgeted");
+    assert!(String::deserialize(BudgetDeserializer::new(str_de, 8)).is_err());
+
+    // The budget is shared across nesting: keys, values, and the containers
+    // themselves all draw from it.
+    let map = MapDeserializer::<_, Error>::new(vec![("a", 1u8), ("b", 2u8)].into_iter());
+    let ok = HashMap::<String, u8>::deserialize(BudgetDeserializer::new(map, 7)).unwrap();
+    assert_eq!(ok.len(), 2);
+    let map = MapDeserializer::<_, Error>::new(vec![("a", 1u8), ("b", 2u8)].into_iter());
+    let err = HashMap::<String, u8>::deserialize(BudgetDeserializer::new(map, 6)).unwrap_err();
+    assert_eq!(err.to_string(), "deserialization size budget exceeded");
+}
+
+#[test]
+fn test_array_drops_initialized_prefix_on_error() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Deserialize)]
+    struct Noisy;
+
+    impl Drop for Noisy {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    // The input ends after two of three elements. The two elements that were
+    // already deserialized into the array must be dropped, and the third,
+    // which never existed, must not be.
+    assert_de_tokens_error::<[Noisy; 3]>(
+        &[
+            Token::Tuple { len: 3 },
+            Token::UnitStruct { name: "Noisy" },
+            Token::UnitStruct { name: "Noisy" },
+            Token::TupleEnd,
+        ],
+        "invalid length 2, expected an array of length 3",
+    );
+    assert_eq!(DROPS.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn test_or_else() {
+    use serde::de::or_else::OrElseDeserializer;
+    use serde::de::value::{Error, MapDeserializer, StrDeserializer};
+
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Version {
+        major: u32,
+        minor: u32,
+    }
+
+    fn deserialize_version<'de, D>(deserializer: D) -> Result<Version, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let buffered = OrElseDeserializer::new(deserializer)?;
+        // Current representation: a map with named fields.
+        match buffered.try_deserialize::<Version>() {
+            Ok(version) => Ok(version),
+            // Legacy representation: a "major.minor" string.
+            Err(_) => {
+                let s = buffered.into_deserialize::<String>()?;
+                let mut parts = s.splitn(2, '.');
+                let major = parts.next().and_then(|p| p.parse().ok());
+                let minor = parts.next().and_then(|p| p.parse().ok());
+                match (major, minor) {
+                    (Some(major), Some(minor)) => Ok(Version { major, minor }),
+                    _ => Err(serde::de::Error::custom("malformed version string")),
+                }
+            }
+        }
+    }
+
+    let current = MapDeserializer::<_, Error>::new(vec![("major", 1u32), ("minor", 2)].into_iter());
+    assert_eq!(
+        deserialize_version(current).unwrap(),
+        Version { major: 1, minor: 2 }
+    );
+
+    let legacy = StrDeserializer::<Error>::new("1.2");
+    assert_eq!(
+        deserialize_version(legacy).unwrap(),
+        Version { major: 1, minor: 2 }
+    );
+
+    let bad = StrDeserializer::<Error>::new("nonsense");
+    assert_eq!(
+        deserialize_version(bad).unwrap_err().to_string(),
+        "malformed version string"
+    );
+}
+
+#[test]
+fn test_narrowing_from_128() {
+    // In-range negative input must not be rejected by the upper-bound check:
+    // casting it to u128 for that comparison would wrap to a huge value.
+    let de = <i128 as IntoDeserializer>::into_deserializer(-5);
+    assert_eq!(i8::deserialize(de).unwrap(), -5);
+    let de = <i128 as IntoDeserializer>::into_deserializer(-5);
+    assert_eq!(i64::deserialize(de).unwrap(), -5);
+    let de = <i128 as IntoDeserializer>::into_deserializer(-5);
+    assert_eq!(
+        NonZeroI8::deserialize(de).unwrap(),
+        NonZeroI8::new(-5).unwrap()
+    );
+
+    let de = <i128 as IntoDeserializer>::into_deserializer(200);
+    assert_eq!(u8::deserialize(de).unwrap(), 200);
+
+    // Saturating clamps toward the correct bound regardless of the sign of
+    // the 128-bit input.
+    let de = <u128 as IntoDeserializer>::into_deserializer(1u128 << 127);
+    assert_eq!(
+        Saturating::<i8>::deserialize(de).unwrap(),
+        Saturating(i8::MAX)
+    );
+    let de = <i128 as IntoDeserializer>::into_deserializer(-1000);
+    assert_eq!(
+        Saturating::<i8>::deserialize(de).unwrap(),
+        Saturating(i8::MIN)
+    );
+    let de = <i128 as IntoDeserializer>::into_deserializer(-5);
+    assert_eq!(Saturating::<u8>::deserialize(de).unwrap(), Saturating(0));
+    let de = <i128 as IntoDeserializer>::into_deserializer(-5);
+    assert_eq!(Saturating::<i64>::deserialize(de).unwrap(), Saturating(-5));
+}
@@ -0,0 +1,164 @@
+//! Asserts upper bounds on the number of heap allocations performed during
+//! deserialization, so that zero-copy promises do not regress silently when
+//! derive internals change.
+
+use serde::de::value::{BorrowedStrDeserializer, Error, MapDeserializer};
+use serde::de::IntoDeserializer;
+use serde::Deserialize;
+use serde_derive::Deserialize;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Runs `f` and asserts that it performed at most `bound` heap allocations.
+///
+/// A mutex serializes the measured sections so that tests running on other
+/// threads do not contribute to each other's counts.
+fn assert_allocates_at_most<T>(bound: usize, f: impl FnOnce() -> T) -> T {
+    static MEASURING: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    let _guard = MEASURING.lock().unwrap();
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let value = f();
+    let after = ALLOCATIONS.load(Ordering::Relaxed);
+    let count = after - before;
+    assert!(
+        count <= bound,
+        "expected at most {} allocations, measured {}",
+        bound,
+        count
+    );
+    value
+}
+
+#[test]
+fn test_borrowed_str_zero_alloc() {
+    let de = BorrowedStrDeserializer::<Error>::new("borrowed");
+    let value = assert_allocates_at_most(0, || <&str>::deserialize(de).unwrap());
+    assert_eq!(value, "borrowed");
+}
+
+#[test]
+fn test_derived_struct_borrowed_fields_zero_alloc() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Record<'a> {
+        #[serde(borrow)]
+        key: &'a str,
+        count: u64,
+    }
+
+    // Building the input deserializer allocates; deserializing from it into a
+    // struct of borrowed fields must not.
+    let entries = [
+        ("key", RecordField::Str("name")),
+        ("count", RecordField::U64(7)),
+    ];
+    let de = MapDeserializer::<_, Error>::new(entries.iter().cloned());
+
+    let record = assert_allocates_at_most(0, || Record::deserialize(de).unwrap());
+    assert_eq!(
+        record,
+        Record {
+            key: "name",
+            count: 7,
+        }
+    );
+}
+
+#[derive(Clone)]
+enum RecordField {
+    Str(&'static str),
+    U64(u64),
+}
+
+impl<'de> IntoDeserializer<'de, Error> for RecordField {
+    type Deserializer = RecordFieldDeserializer;
+
+    fn into_deserializer(self) -> RecordFieldDeserializer {
+        RecordFieldDeserializer { field: self }
+    }
+}
+
+struct RecordFieldDeserializer {
+    field: RecordField,
+}
+
+impl<'de> serde::Deserializer<'de> for RecordFieldDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        match self.field {
+            RecordField::Str(v) => visitor.visit_borrowed_str(v),
+            RecordField::U64(v) => visitor.visit_u64(v),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+#[test]
+fn test_string_single_alloc() {
+    let de = BorrowedStrDeserializer::<Error>::new("owned");
+    let value = assert_allocates_at_most(1, || String::deserialize(de).unwrap());
+    assert_eq!(value, "owned");
+}
+
+#[test]
+fn test_vec_bounded_alloc() {
+    use serde::de::value::SeqDeserializer;
+
+    let de = SeqDeserializer::<_, Error>::new(1u32..=16);
+    // A sequence with an exact size hint should allocate its Vec storage
+    // once, not grow repeatedly.
+    let value = assert_allocates_at_most(1, || Vec::<u32>::deserialize(de).unwrap());
+    assert_eq!(value.len(), 16);
+}
+
+#[test]
+fn test_small_string_inline_zero_alloc() {
+    use serde::small_string::SmallString;
+
+    let de = BorrowedStrDeserializer::<Error>::new("field_identifier");
+    let value = assert_allocates_at_most(0, || SmallString::deserialize(de).unwrap());
+    assert!(value.is_inline());
+    assert_eq!(value, "field_identifier");
+}
+
+#[test]
+fn test_small_string_heap_fallback() {
+    use serde::small_string::SmallString;
+
+    let long = "a string that does not fit in the inline buffer";
+    let de = BorrowedStrDeserializer::<Error>::new(long);
+    let value = SmallString::deserialize(de).unwrap();
+    assert!(!value.is_inline());
+    assert_eq!(value, long);
+}
@@ -1,6 +1,6 @@
 #![allow(
     clippy::derive_partial_eq_without_eq,
-    clippy::empty_enum,
+    clippy::empty_enums,
     clippy::unreadable_literal
 )]
 #![cfg_attr(feature = "unstable", feature(never_type))]
@@ -200,6 +200,35 @@ fn test_i128() {
     );
 }
 
+#[test]
+fn test_i128_out_of_range() {
+    let deserializer = <i128 as IntoDeserializer>::into_deserializer(256);
+    let error = u8::deserialize(deserializer).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid value: integer `256`, expected u8",
+    );
+
+    let deserializer =
+        <i128 as IntoDeserializer>::into_deserializer(-170141183460469231731687303715884105728);
+    let error = i64::deserialize(deserializer).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid value: integer `-170141183460469231731687303715884105728`, expected i64",
+    );
+}
+
+#[test]
+fn test_u128_out_of_range() {
+    let deserializer =
+        <u128 as IntoDeserializer>::into_deserializer(340282366920938463463374607431768211455);
+    let error = u64::deserialize(deserializer).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid value: integer `340282366920938463463374607431768211455`, expected u64",
+    );
+}
+
 #[test]
 fn test_u8() {
     let test = assert_de_tokens_error::<u8>;
@@ -1566,3 +1595,53 @@ fn test_never_type() {
         "cannot deserialize `!`",
     );
 }
+
+#[test]
+fn test_unknown_field_did_you_mean() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Example {
+        website: u32,
+        weight: u32,
+    }
+
+    assert_de_tokens_error::<Example>(
+        &[
+            Token::Struct {
+                name: "Example",
+                len: 2,
+            },
+            Token::Str("websote"),
+        ],
+        "unknown field `websote`, expected `website` or `weight`, did you mean `website`?",
+    );
+
+    // Nothing close enough: no suggestion.
+    assert_de_tokens_error::<Example>(
+        &[
+            Token::Struct {
+                name: "Example",
+                len: 2,
+            },
+            Token::Str("color"),
+        ],
+        "unknown field `color`, expected `website` or `weight`",
+    );
+}
+
+#[test]
+fn test_unknown_variant_did_you_mean() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Animal {
+        Dog,
+        Sheep,
+    }
+
+    assert_de_tokens_error::<Animal>(
+        &[Token::UnitVariant {
+            name: "Animal",
+            variant: "Sheeep",
+        }],
+        "unknown variant `Sheeep`, expected `Dog` or `Sheep`, did you mean `Sheep`?",
+    );
+}
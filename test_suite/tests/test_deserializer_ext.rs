@@ -0,0 +1,83 @@
+#![allow(clippy::derive_partial_eq_without_eq)]
+
+use serde::de::event::{from_iter, Event};
+use serde::de::value::{Error, MapDeserializer, StrDeserializer, U32Deserializer};
+use serde::de::{DeserializerExt, IntoDeserializer};
+use serde::Deserialize;
+use serde_derive::Deserialize;
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[test]
+fn test_with_context() {
+    let de: StrDeserializer<Error> = "one".into_deserializer();
+    let value = String::deserialize(de.with_context(|| "parsing Foo")).unwrap();
+    assert_eq!(value, "one");
+
+    let de: StrDeserializer<Error> = "one".into_deserializer();
+    let error = u32::deserialize(de.with_context(|| "parsing Foo")).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "parsing Foo: invalid type: string \"one\", expected u32",
+    );
+
+    // Errors arising deep inside the value still come back through the
+    // top-level call and pick up the context.
+    let de = MapDeserializer::<_, Error>::new(vec![("x", "one"), ("y", "two")].into_iter());
+    let error = Point::deserialize(de.with_context(|| "parsing Point")).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "parsing Point: invalid type: string \"one\", expected i32",
+    );
+}
+
+#[test]
+fn test_depth_limit() {
+    let events = [
+        Event::SeqStart(None),
+        Event::SeqStart(None),
+        Event::U32(1),
+        Event::SeqEnd,
+        Event::SeqEnd,
+    ];
+
+    let mut de = from_iter::<_, Error>(events.clone());
+    let value = Vec::<Vec<u32>>::deserialize((&mut de).depth_limit(2)).unwrap();
+    assert_eq!(value, vec![vec![1]]);
+
+    let mut de = from_iter::<_, Error>(events);
+    let error = Vec::<Vec<u32>>::deserialize((&mut de).depth_limit(1)).unwrap_err();
+    assert_eq!(error.to_string(), "exceeded recursion depth limit");
+
+    // Scalars do not count as a level of nesting.
+    let de: U32Deserializer<Error> = 1996u32.into_deserializer();
+    assert_eq!(u32::deserialize(de.depth_limit(0)), Ok(1996));
+
+    // `Some` does.
+    let events = [Event::Some, Event::U32(1)];
+    let mut de = from_iter::<_, Error>(events);
+    let error = Option::<u32>::deserialize((&mut de).depth_limit(0)).unwrap_err();
+    assert_eq!(error.to_string(), "exceeded recursion depth limit");
+}
+
+#[test]
+fn test_traced() {
+    let mut calls = Vec::new();
+    let de = MapDeserializer::<_, Error>::new(vec![("x", 1), ("y", 2)].into_iter());
+    let point = Point::deserialize(de.traced(|method| calls.push(method))).unwrap();
+    assert_eq!(point, Point { x: 1, y: 2 });
+    assert_eq!(
+        calls,
+        [
+            "deserialize_struct",
+            "deserialize_identifier",
+            "deserialize_i32",
+            "deserialize_identifier",
+            "deserialize_i32",
+        ],
+    );
+}
@@ -507,6 +507,62 @@ fn string_and_bytes() {
     );
 }
 
+#[test]
+fn borrowed_str_and_bytes() {
+    // Content buffering preserves borrowed strings and bytes, so untagged
+    // enums are able to deserialize zero-copy when the format provides
+    // borrowed data.
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(untagged)]
+    enum Untagged<'a> {
+        Str {
+            #[serde(borrow)]
+            string: &'a str,
+        },
+        Bytes {
+            #[serde(borrow)]
+            bytes: &'a [u8],
+        },
+    }
+
+    assert_de_tokens(
+        &Untagged::Str { string: "borrowed" },
+        &[
+            Token::Struct {
+                name: "Untagged",
+                len: 1,
+            },
+            Token::Str("string"),
+            Token::BorrowedStr("borrowed"),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens(
+        &Untagged::Bytes { bytes: b"borrowed" },
+        &[
+            Token::Struct {
+                name: "Untagged",
+                len: 1,
+            },
+            Token::Str("bytes"),
+            Token::BorrowedBytes(b"borrowed"),
+            Token::StructEnd,
+        ],
+    );
+
+    // Same thing through a newtype variant.
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(untagged)]
+    enum Newtype<'a> {
+        Str(#[serde(borrow)] &'a str),
+        U8(u8),
+    }
+
+    assert_de_tokens(&Newtype::Str("borrowed"), &[Token::BorrowedStr("borrowed")]);
+    assert_de_tokens(&Newtype::U8(1), &[Token::U8(1)]);
+}
+
 #[test]
 fn contains_flatten() {
     #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -0,0 +1,164 @@
+use serde::de::event::{from_iter, Event};
+use serde::de::value::Error;
+use serde::Deserialize;
+use serde_derive::Deserialize;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+fn from_events<'de, T>(events: Vec<Event<'de>>) -> Result<T, Error>
+where
+    T: Deserialize<'de>,
+{
+    let mut de = from_iter(events);
+    let value = T::deserialize(&mut de)?;
+    de.end()?;
+    Ok(value)
+}
+
+#[test]
+fn test_struct() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    let events = vec![
+        Event::MapStart(Some(2)),
+        Event::Str(Cow::Borrowed("x")),
+        Event::I32(1),
+        Event::Str(Cow::Borrowed("y")),
+        Event::I32(2),
+        Event::MapEnd,
+    ];
+
+    assert_eq!(from_events::<Point>(events).unwrap(), Point { x: 1, y: 2 });
+}
+
+#[test]
+fn test_nested_seq() {
+    let events = vec![
+        Event::SeqStart(None),
+        Event::SeqStart(Some(2)),
+        Event::U8(1),
+        Event::U8(2),
+        Event::SeqEnd,
+        Event::SeqStart(Some(0)),
+        Event::SeqEnd,
+        Event::SeqEnd,
+    ];
+
+    assert_eq!(
+        from_events::<Vec<Vec<u8>>>(events).unwrap(),
+        vec![vec![1, 2], vec![]]
+    );
+}
+
+#[test]
+fn test_map() {
+    let events = vec![
+        Event::MapStart(None),
+        Event::Str(Cow::Borrowed("a")),
+        Event::U32(1),
+        Event::Str(Cow::Owned("b".to_owned())),
+        Event::U32(2),
+        Event::MapEnd,
+    ];
+
+    let mut expected = BTreeMap::new();
+    expected.insert("a".to_owned(), 1);
+    expected.insert("b".to_owned(), 2);
+    assert_eq!(
+        from_events::<BTreeMap<String, u32>>(events).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_borrowed_str() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    struct Borrowing<'a> {
+        #[serde(borrow)]
+        name: &'a str,
+    }
+
+    let events = vec![
+        Event::MapStart(Some(1)),
+        Event::Str(Cow::Borrowed("name")),
+        Event::Str(Cow::Borrowed("borrowed")),
+        Event::MapEnd,
+    ];
+
+    assert_eq!(
+        from_events::<Borrowing>(events).unwrap(),
+        Borrowing { name: "borrowed" }
+    );
+}
+
+#[test]
+fn test_option() {
+    let events = vec![Event::None];
+    assert_eq!(from_events::<Option<u8>>(events).unwrap(), None);
+
+    let events = vec![Event::Some, Event::U8(1)];
+    assert_eq!(from_events::<Option<u8>>(events).unwrap(), Some(1));
+
+    // The Some marker may be omitted by formats that do not distinguish
+    // missing values from present ones.
+    let events = vec![Event::U8(1)];
+    assert_eq!(from_events::<Option<u8>>(events).unwrap(), Some(1));
+}
+
+#[test]
+fn test_enum() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    enum Shape {
+        Empty,
+        Circle(u32),
+        Rect { w: u32, h: u32 },
+    }
+
+    let events = vec![Event::Str(Cow::Borrowed("Empty"))];
+    assert_eq!(from_events::<Shape>(events).unwrap(), Shape::Empty);
+
+    let events = vec![
+        Event::MapStart(Some(1)),
+        Event::Str(Cow::Borrowed("Circle")),
+        Event::U32(4),
+        Event::MapEnd,
+    ];
+    assert_eq!(from_events::<Shape>(events).unwrap(), Shape::Circle(4));
+
+    let events = vec![
+        Event::MapStart(Some(1)),
+        Event::Str(Cow::Borrowed("Rect")),
+        Event::MapStart(Some(2)),
+        Event::Str(Cow::Borrowed("w")),
+        Event::U32(3),
+        Event::Str(Cow::Borrowed("h")),
+        Event::U32(4),
+        Event::MapEnd,
+        Event::MapEnd,
+    ];
+    assert_eq!(
+        from_events::<Shape>(events).unwrap(),
+        Shape::Rect { w: 3, h: 4 }
+    );
+}
+
+#[test]
+fn test_truncated_input() {
+    let events = vec![Event::SeqStart(None), Event::U8(1)];
+    let err = from_events::<Vec<u8>>(events).unwrap_err();
+    assert_eq!(err.to_string(), "unexpected end of events");
+}
+
+#[test]
+fn test_trailing_events() {
+    let events = vec![Event::U8(1), Event::U8(2)];
+    let err = from_events::<u8>(events).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid value: integer `2`, expected no remaining events"
+    );
+}
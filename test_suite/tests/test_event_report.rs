@@ -0,0 +1,547 @@
+//! Event-stream assertions with machine-readable failure reports.
+//!
+//! `assert_events` serializes a value into the event representation from
+//! `serde::de::event` and compares it against an expected stream. On a
+//! mismatch it panics with readable text like serde_test does, and when the
+//! `SERDE_TEST_JSON` environment variable is set it additionally prints a
+//! single JSON line to stderr — expected events, actual events, and the index
+//! of divergence — so CI systems and wrapper tooling can present diffs
+//! without parsing panic messages.
+
+use serde::de::event::{from_iter, Event};
+use serde::de::value::Error;
+use serde::ser::{self, Serialize};
+use serde::Deserialize;
+use serde_derive::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::env;
+use std::fmt::Write;
+
+struct EventSerializer {
+    events: Vec<Event<'static>>,
+}
+
+struct Compound<'a> {
+    ser: &'a mut EventSerializer,
+    end: Event<'static>,
+    // Variant contents are wrapped in a single-entry map keyed by the variant
+    // name, which needs its own closing event.
+    close_variant: bool,
+}
+
+impl<'a> Compound<'a> {
+    fn finish(self) -> Result<(), Error> {
+        self.ser.events.push(self.end);
+        if self.close_variant {
+            self.ser.events.push(Event::MapEnd);
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ser::Serializer for &'a mut EventSerializer {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.events.push(Event::Bool(v));
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        self.events.push(Event::I8(v));
+        Ok(())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        self.events.push(Event::I16(v));
+        Ok(())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        self.events.push(Event::I32(v));
+        Ok(())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        self.events.push(Event::I64(v));
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        self.events.push(Event::U8(v));
+        Ok(())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        self.events.push(Event::U16(v));
+        Ok(())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        self.events.push(Event::U32(v));
+        Ok(())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        self.events.push(Event::U64(v));
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Error> {
+        self.events.push(Event::F32(v));
+        Ok(())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        self.events.push(Event::F64(v));
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.events.push(Event::Char(v));
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.events.push(Event::Str(Cow::Owned(v.to_owned())));
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        self.events.push(Event::Bytes(Cow::Owned(v.to_owned())));
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.events.push(Event::None);
+        Ok(())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.events.push(Event::Some);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.events.push(Event::Unit);
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.events.push(Event::Str(Cow::Borrowed(variant)));
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.events.push(Event::MapStart(Some(1)));
+        self.events.push(Event::Str(Cow::Borrowed(variant)));
+        value.serialize(&mut *self)?;
+        self.events.push(Event::MapEnd);
+        Ok(())
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Compound<'a>, Error> {
+        self.events.push(Event::SeqStart(len));
+        Ok(Compound {
+            ser: self,
+            end: Event::SeqEnd,
+            close_variant: false,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Compound<'a>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>, Error> {
+        self.events.push(Event::MapStart(Some(1)));
+        self.events.push(Event::Str(Cow::Borrowed(variant)));
+        self.events.push(Event::SeqStart(Some(len)));
+        Ok(Compound {
+            ser: self,
+            end: Event::SeqEnd,
+            close_variant: true,
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Compound<'a>, Error> {
+        self.events.push(Event::MapStart(len));
+        Ok(Compound {
+            ser: self,
+            end: Event::MapEnd,
+            close_variant: false,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Compound<'a>, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>, Error> {
+        self.events.push(Event::MapStart(Some(1)));
+        self.events.push(Event::Str(Cow::Borrowed(variant)));
+        self.events.push(Event::MapStart(Some(len)));
+        Ok(Compound {
+            ser: self,
+            end: Event::MapEnd,
+            close_variant: true,
+        })
+    }
+}
+
+impl<'a> ser::SerializeSeq for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTuple for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTupleVariant for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeMap for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        key.serialize(&mut *self.ser)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeStruct for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.ser.events.push(Event::Str(Cow::Borrowed(key)));
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeStructVariant for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.ser.events.push(Event::Str(Cow::Borrowed(key)));
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+fn to_events<T>(value: &T) -> Vec<Event<'static>>
+where
+    T: ?Sized + Serialize,
+{
+    let mut ser = EventSerializer { events: Vec::new() };
+    value.serialize(&mut ser).unwrap();
+    ser.events
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            ch if (ch as u32) < 0x20 => {
+                write!(out, "\\u{:04x}", ch as u32).unwrap();
+            }
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_event_list(events: &[Event]) -> String {
+    let mut out = String::new();
+    out.push('[');
+    for (i, event) in events.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        out.push_str(&json_string(&format!("{:?}", event)));
+    }
+    out.push(']');
+    out
+}
+
+fn json_report(expected: &[Event], actual: &[Event]) -> String {
+    let divergence = expected
+        .iter()
+        .zip(actual)
+        .position(|(expected, actual)| expected != actual)
+        .unwrap_or_else(|| expected.len().min(actual.len()));
+    format!(
+        r#"{{"divergence": {}, "expected": {}, "actual": {}}}"#,
+        divergence,
+        json_event_list(expected),
+        json_event_list(actual),
+    )
+}
+
+#[track_caller]
+fn assert_events<T>(value: &T, expected: &[Event<'static>])
+where
+    T: ?Sized + Serialize,
+{
+    let actual = to_events(value);
+    if actual != expected {
+        if env::var_os("SERDE_TEST_JSON").is_some() {
+            eprintln!("{}", json_report(expected, &actual));
+        }
+        panic!(
+            "serialization events do not match\n expected: {:?}\n   actual: {:?}",
+            expected, actual,
+        );
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+enum Shape {
+    Dot,
+    Circle(u32),
+    Rect { w: u32, h: u32 },
+}
+
+#[test]
+fn test_assert_events() {
+    assert_events(
+        &Point { x: 1, y: 2 },
+        &[
+            Event::MapStart(Some(2)),
+            Event::Str("x".into()),
+            Event::I32(1),
+            Event::Str("y".into()),
+            Event::I32(2),
+            Event::MapEnd,
+        ],
+    );
+
+    assert_events(&Shape::Dot, &[Event::Str("Dot".into())]);
+
+    assert_events(
+        &Shape::Circle(3),
+        &[
+            Event::MapStart(Some(1)),
+            Event::Str("Circle".into()),
+            Event::U32(3),
+            Event::MapEnd,
+        ],
+    );
+
+    assert_events(
+        &Shape::Rect { w: 4, h: 5 },
+        &[
+            Event::MapStart(Some(1)),
+            Event::Str("Rect".into()),
+            Event::MapStart(Some(2)),
+            Event::Str("w".into()),
+            Event::U32(4),
+            Event::Str("h".into()),
+            Event::U32(5),
+            Event::MapEnd,
+            Event::MapEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_events_round_trip() {
+    // The recorded events use the same conventions as the event
+    // deserializer, so they feed straight back into from_iter.
+    let value = Shape::Rect { w: 4, h: 5 };
+    let events = to_events(&value);
+    let back = Shape::deserialize(&mut from_iter::<_, Error>(events)).unwrap();
+    assert_eq!(back, value);
+}
+
+#[test]
+fn test_json_report() {
+    let expected = [
+        Event::SeqStart(Some(2)),
+        Event::U8(1),
+        Event::U8(2),
+        Event::SeqEnd,
+    ];
+    let actual = [
+        Event::SeqStart(Some(2)),
+        Event::U8(1),
+        Event::U8(9),
+        Event::SeqEnd,
+    ];
+    assert_eq!(
+        json_report(&expected, &actual),
+        r#"{"divergence": 2, "expected": ["SeqStart(Some(2))", "U8(1)", "U8(2)", "SeqEnd"], "actual": ["SeqStart(Some(2))", "U8(1)", "U8(9)", "SeqEnd"]}"#,
+    );
+
+    // When one stream is a prefix of the other, the divergence is the point
+    // where the shorter one ends.
+    let truncated = [Event::SeqStart(Some(2)), Event::U8(1)];
+    assert_eq!(
+        json_report(&expected, &truncated),
+        r#"{"divergence": 2, "expected": ["SeqStart(Some(2))", "U8(1)", "U8(2)", "SeqEnd"], "actual": ["SeqStart(Some(2))", "U8(1)"]}"#,
+    );
+
+    // Strings inside events are escaped for embedding in the report.
+    let with_string = [Event::Str("a\"b".into())];
+    assert_eq!(
+        json_report(&with_string, &[]),
+        r#"{"divergence": 0, "expected": ["Str(\"a\\\"b\")"], "actual": []}"#,
+    );
+}
+
+#[test]
+#[should_panic(expected = "serialization events do not match")]
+fn test_assert_events_mismatch() {
+    assert_events(&Point { x: 1, y: 2 }, &[Event::Unit]);
+}
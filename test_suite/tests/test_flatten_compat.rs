@@ -0,0 +1,217 @@
+//! Conformance tests for the Deserializer interface that flatten and
+//! untagged enums rely on, as documented in the `serde::de` module docs.
+//! Every value here makes a round trip through the internal `Content`
+//! buffer, so these tests pin down the visit calls a format must support.
+
+#![allow(clippy::derive_partial_eq_without_eq)]
+
+use serde_derive::{Deserialize, Serialize};
+use serde_test::{assert_de_tokens, assert_tokens, Token};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Outer<T> {
+    tag: String,
+    #[serde(flatten)]
+    inner: T,
+}
+
+macro_rules! flatten_scalar_test {
+    ($($name:ident: $ty:ty = $value:expr => $token:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                #[derive(Debug, PartialEq, Serialize, Deserialize)]
+                struct Inner {
+                    value: $ty,
+                }
+
+                assert_tokens(
+                    &Outer {
+                        tag: "t".to_owned(),
+                        inner: Inner { value: $value },
+                    },
+                    &[
+                        Token::Map { len: None },
+                        Token::Str("tag"),
+                        Token::Str("t"),
+                        Token::Str("value"),
+                        $token,
+                        Token::MapEnd,
+                    ],
+                );
+            }
+        )*
+    };
+}
+
+flatten_scalar_test! {
+    flatten_bool: bool = true => Token::Bool(true),
+    flatten_i8: i8 = -1 => Token::I8(-1),
+    flatten_i16: i16 = -1 => Token::I16(-1),
+    flatten_i32: i32 = -1 => Token::I32(-1),
+    flatten_i64: i64 = -1 => Token::I64(-1),
+    flatten_u8: u8 = 1 => Token::U8(1),
+    flatten_u16: u16 = 1 => Token::U16(1),
+    flatten_u32: u32 = 1 => Token::U32(1),
+    flatten_u64: u64 = 1 => Token::U64(1),
+    flatten_f32: f32 = 1.5 => Token::F32(1.5),
+    flatten_f64: f64 = 1.5 => Token::F64(1.5),
+    flatten_char: char = 'c' => Token::Char('c'),
+    flatten_string: String = "s".to_owned() => Token::Str("s"),
+    flatten_unit: () = () => Token::Unit,
+    flatten_none: Option<u8> = None => Token::None,
+}
+
+#[test]
+fn flatten_borrowed_str() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Inner<'a> {
+        #[serde(borrow)]
+        value: &'a str,
+    }
+
+    assert_de_tokens(
+        &Outer {
+            tag: "t".to_owned(),
+            inner: Inner { value: "borrowed" },
+        },
+        &[
+            Token::Map { len: None },
+            Token::Str("tag"),
+            Token::Str("t"),
+            Token::Str("value"),
+            Token::BorrowedStr("borrowed"),
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[test]
+fn flatten_borrowed_bytes() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Inner<'a> {
+        #[serde(borrow)]
+        value: &'a [u8],
+    }
+
+    assert_de_tokens(
+        &Outer {
+            tag: "t".to_owned(),
+            inner: Inner { value: b"borrowed" },
+        },
+        &[
+            Token::Map { len: None },
+            Token::Str("tag"),
+            Token::Str("t"),
+            Token::Str("value"),
+            Token::BorrowedBytes(b"borrowed"),
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[test]
+fn flatten_nested_compound() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Inner {
+        seq: Vec<u32>,
+        map: BTreeMap<String, u32>,
+    }
+
+    let mut map = BTreeMap::new();
+    map.insert("k".to_owned(), 1);
+
+    assert_tokens(
+        &Outer {
+            tag: "t".to_owned(),
+            inner: Inner {
+                seq: vec![1, 2],
+                map,
+            },
+        },
+        &[
+            Token::Map { len: None },
+            Token::Str("tag"),
+            Token::Str("t"),
+            Token::Str("seq"),
+            Token::Seq { len: Some(2) },
+            Token::U32(1),
+            Token::U32(2),
+            Token::SeqEnd,
+            Token::Str("map"),
+            Token::Map { len: Some(1) },
+            Token::Str("k"),
+            Token::U32(1),
+            Token::MapEnd,
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[test]
+fn flatten_keys_out_of_order() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Inner {
+        value: u32,
+    }
+
+    // Fields belonging to the outer struct may appear after fields that end
+    // up buffered for the flattened one.
+    assert_de_tokens(
+        &Outer {
+            tag: "t".to_owned(),
+            inner: Inner { value: 1 },
+        },
+        &[
+            Token::Map { len: None },
+            Token::Str("value"),
+            Token::U32(1),
+            Token::Str("tag"),
+            Token::Str("t"),
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[test]
+fn flatten_untagged_enum() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(untagged)]
+    enum Inner {
+        Number { value: u32 },
+        Text { value: String },
+    }
+
+    assert_tokens(
+        &Outer {
+            tag: "t".to_owned(),
+            inner: Inner::Number { value: 1 },
+        },
+        &[
+            Token::Map { len: None },
+            Token::Str("tag"),
+            Token::Str("t"),
+            Token::Str("value"),
+            Token::U32(1),
+            Token::MapEnd,
+        ],
+    );
+
+    assert_tokens(
+        &Outer {
+            tag: "t".to_owned(),
+            inner: Inner::Text {
+                value: "s".to_owned(),
+            },
+        },
+        &[
+            Token::Map { len: None },
+            Token::Str("tag"),
+            Token::Str("t"),
+            Token::Str("value"),
+            Token::Str("s"),
+            Token::MapEnd,
+        ],
+    );
+}
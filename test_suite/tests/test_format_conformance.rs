@@ -0,0 +1,797 @@
+//! A data model conformance battery for format implementations.
+//!
+//! `run_conformance` drives a format through canonical values covering every
+//! data model kind, edge-case numbers, and deep nesting, and collects a
+//! diagnostic per failing case instead of stopping at the first. The format
+//! plugs in through the `Format` trait with its to/from functions. This file
+//! exercises the harness against a small in-memory value-tree format.
+
+#![allow(clippy::derive_partial_eq_without_eq)]
+
+use serde::de::value::{Error, MapDeserializer, SeqDeserializer, StringDeserializer};
+use serde::de::{
+    self, DeserializeOwned, Deserializer, EnumAccess, IntoDeserializer, VariantAccess, Visitor,
+};
+use serde::ser::{
+    Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant, Serializer,
+};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug, Display};
+
+/// A format under conformance test: how to turn a value into the format's
+/// representation and back.
+trait Format {
+    type Repr;
+    type Error: Display;
+
+    fn encode<T>(&mut self, value: &T) -> Result<Self::Repr, Self::Error>
+    where
+        T: ?Sized + Serialize;
+
+    fn decode<T>(&mut self, repr: Self::Repr) -> Result<T, Self::Error>
+    where
+        T: DeserializeOwned;
+}
+
+/// Runs the canonical battery against `format` and returns one diagnostic
+/// string per failing case. An empty vector means the format conforms.
+fn run_conformance<F>(format: &mut F) -> Vec<String>
+where
+    F: Format,
+{
+    let mut failures = Vec::new();
+
+    macro_rules! case {
+        ($name:expr, $value:expr) => {
+            check(format, &mut failures, $name, $value);
+        };
+    }
+
+    // Booleans and every integer width at its extremes.
+    case!("bool false", false);
+    case!("bool true", true);
+    case!("i8 min", i8::MIN);
+    case!("i8 max", i8::MAX);
+    case!("i16 min", i16::MIN);
+    case!("i16 max", i16::MAX);
+    case!("i32 min", i32::MIN);
+    case!("i32 max", i32::MAX);
+    case!("i64 min", i64::MIN);
+    case!("i64 max", i64::MAX);
+    case!("u8 max", u8::MAX);
+    case!("u16 max", u16::MAX);
+    case!("u32 max", u32::MAX);
+    case!("u64 max", u64::MAX);
+
+    // Floats, including values that expose narrowing or decimal round-trips.
+    case!("f32 min positive", f32::MIN_POSITIVE);
+    case!("f32 max", f32::MAX);
+    case!("f64 subnormal", 5e-324f64);
+    case!("f64 max", f64::MAX);
+    case!("f64 negative zero", -0.0f64);
+
+    // Chars and strings.
+    case!("char ascii", 'n');
+    case!("char astral", '\u{1F980}');
+    case!("str empty", String::new());
+    case!("str unicode", "áé\u{1F980}".to_owned());
+    case!("str embedded nul", "a\0b".to_owned());
+
+    // Bytes.
+    case!("bytes", Bytes(vec![0, 1, 254, 255]));
+    case!("bytes empty", Bytes(Vec::new()));
+
+    // Option and unit kinds.
+    case!("option none", None::<u32>);
+    case!("option some", Some(7u32));
+    case!("option nested", Some(Some(7u32)));
+    case!("unit", ());
+    case!("unit struct", UnitStruct);
+
+    // Newtype struct, and enum variants of all four styles.
+    case!("newtype struct", Newtype(77));
+    case!("unit variant", Kind::Unit);
+    case!("newtype variant", Kind::Newtype(3));
+    case!("tuple variant", Kind::Tuple(1, 2));
+    case!("struct variant", Kind::Struct { a: 9 });
+
+    // Sequences, tuples, and maps.
+    case!("seq", vec![1u32, 2, 3]);
+    case!("seq empty", Vec::<u32>::new());
+    case!("tuple", (1u8, "x".to_owned(), false));
+    case!("tuple struct", TupleStruct(1, 2));
+    case!("map", {
+        let mut map = BTreeMap::new();
+        map.insert("a".to_owned(), 1u32);
+        map.insert("b".to_owned(), 2);
+        map
+    });
+    case!("map empty", BTreeMap::<String, u32>::new());
+    case!(
+        "struct",
+        Struct {
+            a: 1,
+            b: "two".to_owned(),
+            c: None,
+        }
+    );
+
+    // Deep nesting.
+    let mut nested = Nested::Leaf(0);
+    for _ in 0..64 {
+        nested = Nested::Node(Box::new(nested));
+    }
+    case!("deep nesting", nested);
+
+    failures
+}
+
+fn check<F, T>(format: &mut F, failures: &mut Vec<String>, name: &str, value: T)
+where
+    F: Format,
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let repr = match format.encode(&value) {
+        Ok(repr) => repr,
+        Err(err) => {
+            failures.push(format!("{}: serialization failed: {}", name, err));
+            return;
+        }
+    };
+    match format.decode::<T>(repr) {
+        Ok(roundtripped) => {
+            if roundtripped != value {
+                failures.push(format!(
+                    "{}: round trip changed the value: {:?} != {:?}",
+                    name, roundtripped, value,
+                ));
+            }
+        }
+        Err(err) => {
+            failures.push(format!("{}: deserialization failed: {}", name, err));
+        }
+    }
+}
+
+// Canonical types used by the battery.
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct UnitStruct;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Newtype(u32);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct TupleStruct(u8, u8);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Kind {
+    Unit,
+    Newtype(u32),
+    Tuple(u8, u8),
+    Struct { a: u32 },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Struct {
+    a: u32,
+    b: String,
+    c: Option<bool>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Nested {
+    Leaf(u32),
+    Node(Box<Nested>),
+}
+
+/// A sequence of bytes that serializes through `serialize_bytes` rather than
+/// as a sequence of integers.
+#[derive(Debug, PartialEq)]
+struct Bytes(Vec<u8>);
+
+impl Serialize for Bytes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> de::Deserialize<'de> for Bytes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = Bytes;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("bytes")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Bytes, E> {
+                Ok(Bytes(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Bytes, E> {
+                Ok(Bytes(v))
+            }
+        }
+
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+// The in-memory value-tree format used to exercise the harness. Unit-like
+// kinds collapse into Unit, all sequence-like kinds into Seq, all map-like
+// kinds into Map, and enum variants carry their payload by name.
+
+#[derive(Debug)]
+enum Value {
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    Char(char),
+    String(String),
+    Bytes(Vec<u8>),
+    None,
+    Some(Box<Value>),
+    Unit,
+    NewtypeStruct(Box<Value>),
+    Seq(Vec<Value>),
+    Map(Vec<(Value, Value)>),
+    Variant(String, Box<Value>),
+}
+
+struct ValueSerializer;
+
+struct SeqBuilder {
+    elements: Vec<Value>,
+    variant: Option<String>,
+}
+
+struct MapBuilder {
+    entries: Vec<(Value, Value)>,
+    key: Option<Value>,
+    variant: Option<String>,
+}
+
+impl Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = SeqBuilder;
+    type SerializeTuple = SeqBuilder;
+    type SerializeTupleStruct = SeqBuilder;
+    type SerializeTupleVariant = SeqBuilder;
+    type SerializeMap = MapBuilder;
+    type SerializeStruct = MapBuilder;
+    type SerializeStructVariant = MapBuilder;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Error> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        Ok(Value::I8(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        Ok(Value::I16(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        Ok(Value::I32(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        Ok(Value::I64(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        Ok(Value::U8(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        Ok(Value::U16(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        Ok(Value::U32(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, Error> {
+        Ok(Value::U64(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, Error> {
+        Ok(Value::F32(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, Error> {
+        Ok(Value::F64(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, Error> {
+        Ok(Value::Char(v))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, Error> {
+        Ok(Value::String(v.to_owned()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, Error> {
+        Ok(Value::Bytes(v.to_vec()))
+    }
+
+    fn serialize_none(self) -> Result<Value, Error> {
+        Ok(Value::None)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Value, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value
+            .serialize(ValueSerializer)
+            .map(|v| Value::Some(Box::new(v)))
+    }
+
+    fn serialize_unit(self) -> Result<Value, Error> {
+        Ok(Value::Unit)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Error> {
+        Ok(Value::Unit)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Value, Error> {
+        Ok(Value::Variant(variant.to_owned(), Box::new(Value::Unit)))
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<Value, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value
+            .serialize(ValueSerializer)
+            .map(|v| Value::NewtypeStruct(Box::new(v)))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value
+            .serialize(ValueSerializer)
+            .map(|v| Value::Variant(variant.to_owned(), Box::new(v)))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqBuilder, Error> {
+        Ok(SeqBuilder {
+            elements: Vec::with_capacity(len.unwrap_or(0)),
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqBuilder, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SeqBuilder, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SeqBuilder, Error> {
+        Ok(SeqBuilder {
+            elements: Vec::with_capacity(len),
+            variant: Some(variant.to_owned()),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<MapBuilder, Error> {
+        Ok(MapBuilder {
+            entries: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+            variant: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<MapBuilder, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<MapBuilder, Error> {
+        Ok(MapBuilder {
+            entries: Vec::with_capacity(len),
+            key: None,
+            variant: Some(variant.to_owned()),
+        })
+    }
+}
+
+impl SeqBuilder {
+    fn finish(self) -> Value {
+        let seq = Value::Seq(self.elements);
+        match self.variant {
+            Some(variant) => Value::Variant(variant, Box::new(seq)),
+            None => seq,
+        }
+    }
+}
+
+impl SerializeSeq for SeqBuilder {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.elements.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeTuple for SeqBuilder {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeTupleStruct for SeqBuilder {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeTupleVariant for SeqBuilder {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl MapBuilder {
+    fn finish(self) -> Value {
+        let map = Value::Map(self.entries);
+        match self.variant {
+            Some(variant) => Value::Variant(variant, Box::new(map)),
+            None => map,
+        }
+    }
+}
+
+impl SerializeMap for MapBuilder {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.key = Some(key.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value before serialize_key");
+        self.entries.push((key, value.serialize(ValueSerializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeStruct for MapBuilder {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.entries.push((
+            Value::String(key.to_owned()),
+            value.serialize(ValueSerializer)?,
+        ));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl SerializeStructVariant for MapBuilder {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(self.finish())
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Value {
+    type Deserializer = Value;
+
+    fn into_deserializer(self) -> Value {
+        self
+    }
+}
+
+impl<'de> Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::I8(v) => visitor.visit_i8(v),
+            Value::I16(v) => visitor.visit_i16(v),
+            Value::I32(v) => visitor.visit_i32(v),
+            Value::I64(v) => visitor.visit_i64(v),
+            Value::U8(v) => visitor.visit_u8(v),
+            Value::U16(v) => visitor.visit_u16(v),
+            Value::U32(v) => visitor.visit_u32(v),
+            Value::U64(v) => visitor.visit_u64(v),
+            Value::F32(v) => visitor.visit_f32(v),
+            Value::F64(v) => visitor.visit_f64(v),
+            Value::Char(v) => visitor.visit_char(v),
+            Value::String(v) => visitor.visit_string(v),
+            Value::Bytes(v) => visitor.visit_byte_buf(v),
+            Value::None => visitor.visit_none(),
+            Value::Some(v) => visitor.visit_some(*v),
+            Value::Unit => visitor.visit_unit(),
+            Value::NewtypeStruct(v) => visitor.visit_newtype_struct(*v),
+            Value::Seq(v) => visitor.visit_seq(SeqDeserializer::new(v.into_iter())),
+            Value::Map(v) => visitor.visit_map(MapDeserializer::new(v.into_iter())),
+            Value::Variant(variant, payload) => visitor.visit_enum(ValueEnumAccess {
+                variant,
+                payload: *payload,
+            }),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::None => visitor.visit_none(),
+            Value::Some(v) => visitor.visit_some(*v),
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple tuple_struct
+        map struct enum identifier ignored_any
+    }
+}
+
+struct ValueEnumAccess {
+    variant: String,
+    payload: Value,
+}
+
+impl<'de> EnumAccess<'de> for ValueEnumAccess {
+    type Error = Error;
+    type Variant = Value;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Value), Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(StringDeserializer::new(self.variant))?;
+        Ok((variant, self.payload))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Value {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self {
+            Value::Unit => Ok(()),
+            _ => Err(de::Error::custom("expected unit variant payload")),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Seq(v) => visitor.visit_seq(SeqDeserializer::new(v.into_iter())),
+            _ => Err(de::Error::custom("expected tuple variant payload")),
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Map(v) => visitor.visit_map(MapDeserializer::new(v.into_iter())),
+            _ => Err(de::Error::custom("expected struct variant payload")),
+        }
+    }
+}
+
+struct ValueTreeFormat;
+
+impl Format for ValueTreeFormat {
+    type Repr = Value;
+    type Error = Error;
+
+    fn encode<T>(&mut self, value: &T) -> Result<Value, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(ValueSerializer)
+    }
+
+    fn decode<T>(&mut self, repr: Value) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        T::deserialize(repr)
+    }
+}
+
+#[test]
+fn test_value_tree_conforms() {
+    let failures = run_conformance(&mut ValueTreeFormat);
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn test_diagnostics_on_lossy_format() {
+    // A format that truncates every string demonstrates that the harness
+    // reports each failing case rather than aborting at the first.
+    struct Truncating;
+
+    impl Format for Truncating {
+        type Repr = Value;
+        type Error = Error;
+
+        fn encode<T>(&mut self, value: &T) -> Result<Value, Error>
+        where
+            T: ?Sized + Serialize,
+        {
+            fn truncate(value: &mut Value) {
+                match value {
+                    Value::String(s) => {
+                        let first_char = s.chars().next().map_or(0, char::len_utf8);
+                        s.truncate(first_char);
+                    }
+                    Value::Some(v) | Value::NewtypeStruct(v) => truncate(v),
+                    Value::Seq(v) => v.iter_mut().for_each(truncate),
+                    Value::Map(v) => v.iter_mut().for_each(|(_, v)| truncate(v)),
+                    Value::Variant(_, v) => truncate(v),
+                    _ => {}
+                }
+            }
+
+            let mut repr = value.serialize(ValueSerializer)?;
+            truncate(&mut repr);
+            Ok(repr)
+        }
+
+        fn decode<T>(&mut self, repr: Value) -> Result<T, Error>
+        where
+            T: DeserializeOwned,
+        {
+            T::deserialize(repr)
+        }
+    }
+
+    let failures = run_conformance(&mut Truncating);
+    assert!(!failures.is_empty());
+    for failure in &failures {
+        assert!(
+            failure.contains("round trip changed the value"),
+            "unexpected diagnostic: {}",
+            failure,
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! Deserializing a stream of independent top-level values from one event
+//! source, as a framed protocol would, and checking that each impl consumes
+//! exactly its own frame without reading into the next one.
+
+use serde::de::event::{from_iter, pseudo_json, Event};
+use serde::de::value::Error;
+use serde::Deserialize;
+use serde_derive::Deserialize;
+use std::fmt::Debug;
+
+#[track_caller]
+fn assert_de_frames<'de, T>(expected: &[T], events: Vec<Event<'de>>)
+where
+    T: Deserialize<'de> + PartialEq + Debug,
+{
+    let mut de = from_iter::<_, Error>(events.clone());
+    for expected_value in expected {
+        let value = T::deserialize(&mut de)
+            .unwrap_or_else(|error| panic!("{}\n input: {}", error, pseudo_json(&events)));
+        assert_eq!(&value, expected_value, "input: {}", pseudo_json(&events));
+    }
+    de.end()
+        .unwrap_or_else(|error| panic!("{}\n input: {}", error, pseudo_json(&events)));
+}
+
+#[test]
+fn test_pseudo_json() {
+    assert_eq!(
+        pseudo_json(&[
+            Event::MapStart(Some(3)),
+            Event::Str("id".into()),
+            Event::U32(1),
+            Event::Str("tags".into()),
+            Event::SeqStart(None),
+            Event::Str("a".into()),
+            Event::Str("b".into()),
+            Event::SeqEnd,
+            Event::Str("parent".into()),
+            Event::None,
+            Event::MapEnd,
+        ]),
+        r#"{"id": 1, "tags": ["a", "b"], "parent": null}"#,
+    );
+
+    // `Some` is transparent, `Unit` renders as null, bytes and chars fall
+    // back to Rust notation.
+    assert_eq!(
+        pseudo_json(&[
+            Event::SeqStart(Some(4)),
+            Event::Some,
+            Event::Bool(true),
+            Event::Unit,
+            Event::Char('x'),
+            Event::Bytes(b"hi".as_slice().into()),
+            Event::SeqEnd,
+        ]),
+        r#"[true, null, 'x', b[104, 105]]"#,
+    );
+
+    assert_eq!(pseudo_json(&[Event::F64(2.5)]), "2.5");
+    assert_eq!(pseudo_json(&[]), "");
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Frame {
+    seq: u32,
+    payload: String,
+}
+
+#[test]
+fn test_consecutive_structs() {
+    assert_de_frames(
+        &[
+            Frame {
+                seq: 1,
+                payload: "a".to_owned(),
+            },
+            Frame {
+                seq: 2,
+                payload: "b".to_owned(),
+            },
+        ],
+        vec![
+            Event::MapStart(Some(2)),
+            Event::Str("seq".into()),
+            Event::U32(1),
+            Event::Str("payload".into()),
+            Event::Str("a".into()),
+            Event::MapEnd,
+            Event::MapStart(Some(2)),
+            Event::Str("seq".into()),
+            Event::U32(2),
+            Event::Str("payload".into()),
+            Event::Str("b".into()),
+            Event::MapEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_consecutive_seqs() {
+    // Each sequence must stop at its own SeqEnd instead of running into the
+    // next frame, even without a length hint.
+    assert_de_frames(
+        &[vec![1u32, 2], vec![], vec![3]],
+        vec![
+            Event::SeqStart(None),
+            Event::U32(1),
+            Event::U32(2),
+            Event::SeqEnd,
+            Event::SeqStart(None),
+            Event::SeqEnd,
+            Event::SeqStart(None),
+            Event::U32(3),
+            Event::SeqEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_mixed_scalar_frames() {
+    assert_de_frames(
+        &[1u32, 2, 3],
+        vec![Event::U32(1), Event::U32(2), Event::U32(3)],
+    );
+}
+
+#[test]
+fn test_trailing_events_detected() {
+    let mut de = from_iter::<_, Error>(vec![Event::U32(1), Event::U32(2)]);
+    assert_eq!(u32::deserialize(&mut de), Ok(1));
+    // One frame was requested but another remains; `end` reports it.
+    assert!(de.end().is_err());
+}
+
+#[test]
+fn test_frame_underflow_detected() {
+    let mut de = from_iter::<_, Error>(vec![Event::MapStart(Some(2)), Event::Str("seq".into())]);
+    let error = Frame::deserialize(&mut de).unwrap_err();
+    assert_eq!(error.to_string(), "unexpected end of events");
+}
@@ -5,6 +5,7 @@
 #![deny(warnings)]
 #![allow(
     confusable_idents,
+    dead_code,
     unknown_lints,
     mixed_script_confusables,
     clippy::derive_partial_eq_without_eq,
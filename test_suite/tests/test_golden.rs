@@ -0,0 +1,326 @@
+//! Golden tokens for the data-model shape of the std impls.
+//!
+//! Each test in this file pins the exact sequence of data-model calls that a
+//! std type makes when serialized, grouped by category. The shapes asserted
+//! here are a stability contract: archived data written by any format against
+//! one serde version must deserialize with a later one, so changing any token
+//! sequence in this file is a semver-relevant change to serde itself, not a
+//! test to be updated in passing. Formats can pin against the same shapes by
+//! copying the token sequences asserted here.
+//!
+//! Shapes that depend on `is_human_readable` are asserted in both the
+//! readable and the compact profile.
+
+#![allow(clippy::unreadable_literal)]
+
+use serde_test::{assert_tokens, Configure, Token};
+use std::cell::{Cell, RefCell};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::ffi::CString;
+use std::marker::PhantomData;
+use std::net;
+use std::num::{NonZeroU8, Saturating, Wrapping};
+use std::ops::{Bound, Range, RangeInclusive};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+
+#[test]
+fn golden_primitives() {
+    assert_tokens(&true, &[Token::Bool(true)]);
+    assert_tokens(&1i8, &[Token::I8(1)]);
+    assert_tokens(&1i16, &[Token::I16(1)]);
+    assert_tokens(&1i32, &[Token::I32(1)]);
+    assert_tokens(&1i64, &[Token::I64(1)]);
+    assert_tokens(&1u8, &[Token::U8(1)]);
+    assert_tokens(&1u16, &[Token::U16(1)]);
+    assert_tokens(&1u32, &[Token::U32(1)]);
+    assert_tokens(&1u64, &[Token::U64(1)]);
+    assert_tokens(&1.5f32, &[Token::F32(1.5)]);
+    assert_tokens(&1.5f64, &[Token::F64(1.5)]);
+    assert_tokens(&'a', &[Token::Char('a')]);
+
+    // The pointer-sized integers serialize as their 64-bit shape on every
+    // platform.
+    assert_tokens(&1isize, &[Token::I64(1)]);
+    assert_tokens(&1usize, &[Token::U64(1)]);
+}
+
+#[test]
+fn golden_strings() {
+    assert_tokens(&String::from("golden"), &[Token::Str("golden")]);
+
+    // Paths serialize as strings in both profiles; non-UTF-8 paths fail
+    // rather than changing shape.
+    assert_tokens(
+        &PathBuf::from("/tmp/golden").readable(),
+        &[Token::Str("/tmp/golden")],
+    );
+    assert_tokens(
+        &PathBuf::from("/tmp/golden").compact(),
+        &[Token::Str("/tmp/golden")],
+    );
+
+    // CString carries the bytes without the nul terminator.
+    assert_tokens(&CString::new("golden").unwrap(), &[Token::Bytes(b"golden")]);
+}
+
+#[test]
+fn golden_option_and_unit() {
+    assert_tokens(&Option::<u8>::None, &[Token::None]);
+    assert_tokens(&Some(1u8), &[Token::Some, Token::U8(1)]);
+
+    assert_tokens(&(), &[Token::Unit]);
+    assert_tokens(
+        &PhantomData::<u8>,
+        &[Token::UnitStruct {
+            name: "PhantomData",
+        }],
+    );
+}
+
+#[test]
+fn golden_tuples_and_arrays() {
+    assert_tokens(
+        &(1u8, 2u8),
+        &[
+            Token::Tuple { len: 2 },
+            Token::U8(1),
+            Token::U8(2),
+            Token::TupleEnd,
+        ],
+    );
+
+    // Arrays share the tuple shape, not the seq shape: the length is part of
+    // the type.
+    assert_tokens(
+        &[1u8, 2, 3],
+        &[
+            Token::Tuple { len: 3 },
+            Token::U8(1),
+            Token::U8(2),
+            Token::U8(3),
+            Token::TupleEnd,
+        ],
+    );
+}
+
+#[test]
+fn golden_sequences() {
+    assert_tokens(
+        &vec![1u8, 2],
+        &[
+            Token::Seq { len: Some(2) },
+            Token::U8(1),
+            Token::U8(2),
+            Token::SeqEnd,
+        ],
+    );
+
+    let mut deque = VecDeque::new();
+    deque.push_back(1u8);
+    assert_tokens(
+        &deque,
+        &[Token::Seq { len: Some(1) }, Token::U8(1), Token::SeqEnd],
+    );
+
+    let mut set = BTreeSet::new();
+    set.insert(1u8);
+    assert_tokens(
+        &set,
+        &[Token::Seq { len: Some(1) }, Token::U8(1), Token::SeqEnd],
+    );
+}
+
+#[test]
+fn golden_maps() {
+    let mut map = BTreeMap::new();
+    map.insert(1u8, "one".to_owned());
+    map.insert(2u8, "two".to_owned());
+    assert_tokens(
+        &map,
+        &[
+            Token::Map { len: Some(2) },
+            Token::U8(1),
+            Token::Str("one"),
+            Token::U8(2),
+            Token::Str("two"),
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[test]
+fn golden_result() {
+    assert_tokens(
+        &Result::<u8, u8>::Ok(1),
+        &[
+            Token::NewtypeVariant {
+                name: "Result",
+                variant: "Ok",
+            },
+            Token::U8(1),
+        ],
+    );
+    assert_tokens(
+        &Result::<u8, u8>::Err(2),
+        &[
+            Token::NewtypeVariant {
+                name: "Result",
+                variant: "Err",
+            },
+            Token::U8(2),
+        ],
+    );
+}
+
+#[test]
+fn golden_wrappers() {
+    // The smart pointers and interior-mutability cells are invisible on the
+    // wire.
+    assert_tokens(&Box::new(1u8), &[Token::U8(1)]);
+    assert_tokens(&Rc::new(1u8), &[Token::U8(1)]);
+    assert_tokens(&Arc::new(1u8), &[Token::U8(1)]);
+    assert_tokens(&Cell::new(1u8), &[Token::U8(1)]);
+    assert_tokens(&RefCell::new(1u8), &[Token::U8(1)]);
+
+    // So are the numeric wrappers.
+    assert_tokens(&Wrapping(1u8), &[Token::U8(1)]);
+    assert_tokens(&Saturating(1u8), &[Token::U8(1)]);
+    assert_tokens(&Reverse(1u8), &[Token::U8(1)]);
+    assert_tokens(&NonZeroU8::new(1).unwrap(), &[Token::U8(1)]);
+}
+
+#[test]
+fn golden_ranges() {
+    assert_tokens(
+        &Range {
+            start: 1u8,
+            end: 2u8,
+        },
+        &[
+            Token::Struct {
+                name: "Range",
+                len: 2,
+            },
+            Token::Str("start"),
+            Token::U8(1),
+            Token::Str("end"),
+            Token::U8(2),
+            Token::StructEnd,
+        ],
+    );
+    assert_tokens(
+        &RangeInclusive::new(1u8, 2u8),
+        &[
+            Token::Struct {
+                name: "RangeInclusive",
+                len: 2,
+            },
+            Token::Str("start"),
+            Token::U8(1),
+            Token::Str("end"),
+            Token::U8(2),
+            Token::StructEnd,
+        ],
+    );
+    assert_tokens(
+        &Bound::<u8>::Unbounded,
+        &[
+            Token::Enum { name: "Bound" },
+            Token::Str("Unbounded"),
+            Token::Unit,
+        ],
+    );
+    assert_tokens(
+        &Bound::Included(1u8),
+        &[
+            Token::Enum { name: "Bound" },
+            Token::Str("Included"),
+            Token::U8(1),
+        ],
+    );
+}
+
+#[test]
+fn golden_time() {
+    assert_tokens(
+        &Duration::new(1, 2),
+        &[
+            Token::Struct {
+                name: "Duration",
+                len: 2,
+            },
+            Token::Str("secs"),
+            Token::U64(1),
+            Token::Str("nanos"),
+            Token::U32(2),
+            Token::StructEnd,
+        ],
+    );
+    assert_tokens(
+        &(UNIX_EPOCH + Duration::new(1, 2)),
+        &[
+            Token::Struct {
+                name: "SystemTime",
+                len: 2,
+            },
+            Token::Str("secs_since_epoch"),
+            Token::U64(1),
+            Token::Str("nanos_since_epoch"),
+            Token::U32(2),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn golden_net_readable() {
+    // In the human-readable profile every address type is a string.
+    assert_tokens(
+        &net::IpAddr::V4(net::Ipv4Addr::new(1, 2, 3, 4)).readable(),
+        &[Token::Str("1.2.3.4")],
+    );
+    assert_tokens(
+        &net::Ipv4Addr::new(1, 2, 3, 4).readable(),
+        &[Token::Str("1.2.3.4")],
+    );
+    assert_tokens(
+        &net::SocketAddr::from(([1, 2, 3, 4], 80)).readable(),
+        &[Token::Str("1.2.3.4:80")],
+    );
+}
+
+#[test]
+fn golden_net_compact() {
+    // In the compact profile the address types keep their structure: the
+    // version is an enum variant and the octets are a tuple.
+    assert_tokens(
+        &net::Ipv4Addr::new(1, 2, 3, 4).compact(),
+        &[
+            Token::Tuple { len: 4 },
+            Token::U8(1),
+            Token::U8(2),
+            Token::U8(3),
+            Token::U8(4),
+            Token::TupleEnd,
+        ],
+    );
+    assert_tokens(
+        &net::IpAddr::V4(net::Ipv4Addr::new(1, 2, 3, 4)).compact(),
+        &[
+            Token::NewtypeVariant {
+                name: "IpAddr",
+                variant: "V4",
+            },
+            Token::Tuple { len: 4 },
+            Token::U8(1),
+            Token::U8(2),
+            Token::U8(3),
+            Token::U8(4),
+            Token::TupleEnd,
+        ],
+    );
+}
@@ -0,0 +1,337 @@
+//! An in-memory serializer with `Error = Infallible`, exercising the
+//! `UnwrapInfallible` extension and the `ser::Error` impl for `Infallible`.
+
+use serde::ser::{
+    SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant, Serializer, UnwrapInfallible,
+};
+use serde::Serialize;
+use serde_derive::Serialize;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+
+/// Counts the scalar values in the serialized form; cannot fail.
+struct CountScalars;
+
+/// Accumulates the count of a compound value.
+struct CountCompound(usize);
+
+impl Serializer for CountScalars {
+    type Ok = usize;
+    type Error = Infallible;
+
+    type SerializeSeq = CountCompound;
+    type SerializeTuple = CountCompound;
+    type SerializeTupleStruct = CountCompound;
+    type SerializeTupleVariant = CountCompound;
+    type SerializeMap = CountCompound;
+    type SerializeStruct = CountCompound;
+    type SerializeStructVariant = CountCompound;
+
+    fn serialize_bool(self, _: bool) -> Result<usize, Infallible> {
+        Ok(1)
+    }
+
+    fn serialize_i8(self, _: i8) -> Result<usize, Infallible> {
+        Ok(1)
+    }
+
+    fn serialize_i16(self, _: i16) -> Result<usize, Infallible> {
+        Ok(1)
+    }
+
+    fn serialize_i32(self, _: i32) -> Result<usize, Infallible> {
+        Ok(1)
+    }
+
+    fn serialize_i64(self, _: i64) -> Result<usize, Infallible> {
+        Ok(1)
+    }
+
+    fn serialize_u8(self, _: u8) -> Result<usize, Infallible> {
+        Ok(1)
+    }
+
+    fn serialize_u16(self, _: u16) -> Result<usize, Infallible> {
+        Ok(1)
+    }
+
+    fn serialize_u32(self, _: u32) -> Result<usize, Infallible> {
+        Ok(1)
+    }
+
+    fn serialize_u64(self, _: u64) -> Result<usize, Infallible> {
+        Ok(1)
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<usize, Infallible> {
+        Ok(1)
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<usize, Infallible> {
+        Ok(1)
+    }
+
+    fn serialize_char(self, _: char) -> Result<usize, Infallible> {
+        Ok(1)
+    }
+
+    fn serialize_str(self, _: &str) -> Result<usize, Infallible> {
+        Ok(1)
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<usize, Infallible> {
+        Ok(1)
+    }
+
+    fn serialize_none(self) -> Result<usize, Infallible> {
+        Ok(0)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<usize, Infallible>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(CountScalars)
+    }
+
+    fn serialize_unit(self) -> Result<usize, Infallible> {
+        Ok(0)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<usize, Infallible> {
+        Ok(0)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<usize, Infallible> {
+        Ok(0)
+    }
+
+    fn serialize_newtype_struct<T>(self, _: &'static str, value: &T) -> Result<usize, Infallible>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(CountScalars)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        value: &T,
+    ) -> Result<usize, Infallible>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(CountScalars)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<CountCompound, Infallible> {
+        Ok(CountCompound(0))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<CountCompound, Infallible> {
+        Ok(CountCompound(0))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<CountCompound, Infallible> {
+        Ok(CountCompound(0))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<CountCompound, Infallible> {
+        Ok(CountCompound(0))
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<CountCompound, Infallible> {
+        Ok(CountCompound(0))
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<CountCompound, Infallible> {
+        Ok(CountCompound(0))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<CountCompound, Infallible> {
+        Ok(CountCompound(0))
+    }
+}
+
+impl SerializeSeq for CountCompound {
+    type Ok = usize;
+    type Error = Infallible;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Infallible>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.0 += value.serialize(CountScalars).unwrap_infallible();
+        Ok(())
+    }
+
+    fn end(self) -> Result<usize, Infallible> {
+        Ok(self.0)
+    }
+}
+
+impl SerializeTuple for CountCompound {
+    type Ok = usize;
+    type Error = Infallible;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Infallible>
+    where
+        T: ?Sized + Serialize,
+    {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<usize, Infallible> {
+        Ok(self.0)
+    }
+}
+
+impl SerializeTupleStruct for CountCompound {
+    type Ok = usize;
+    type Error = Infallible;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Infallible>
+    where
+        T: ?Sized + Serialize,
+    {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<usize, Infallible> {
+        Ok(self.0)
+    }
+}
+
+impl SerializeTupleVariant for CountCompound {
+    type Ok = usize;
+    type Error = Infallible;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Infallible>
+    where
+        T: ?Sized + Serialize,
+    {
+        SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<usize, Infallible> {
+        Ok(self.0)
+    }
+}
+
+impl SerializeMap for CountCompound {
+    type Ok = usize;
+    type Error = Infallible;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Infallible>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.0 += key.serialize(CountScalars).unwrap_infallible();
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Infallible>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.0 += value.serialize(CountScalars).unwrap_infallible();
+        Ok(())
+    }
+
+    fn end(self) -> Result<usize, Infallible> {
+        Ok(self.0)
+    }
+}
+
+impl SerializeStruct for CountCompound {
+    type Ok = usize;
+    type Error = Infallible;
+
+    fn serialize_field<T>(&mut self, _: &'static str, value: &T) -> Result<(), Infallible>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.0 += value.serialize(CountScalars).unwrap_infallible();
+        Ok(())
+    }
+
+    fn end(self) -> Result<usize, Infallible> {
+        Ok(self.0)
+    }
+}
+
+impl SerializeStructVariant for CountCompound {
+    type Ok = usize;
+    type Error = Infallible;
+
+    fn serialize_field<T>(&mut self, _: &'static str, value: &T) -> Result<(), Infallible>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.0 += value.serialize(CountScalars).unwrap_infallible();
+        Ok(())
+    }
+
+    fn end(self) -> Result<usize, Infallible> {
+        Ok(self.0)
+    }
+}
+
+#[test]
+fn test_unwrap_infallible() {
+    #[derive(Serialize)]
+    struct Reading {
+        timestamp: u64,
+        values: Vec<f64>,
+        tags: BTreeMap<String, String>,
+    }
+
+    let mut tags = BTreeMap::new();
+    tags.insert("site".to_owned(), "lab".to_owned());
+
+    let reading = Reading {
+        timestamp: 1,
+        values: vec![0.5, 0.25],
+        tags,
+    };
+
+    // No error handling in sight: the counting serializer cannot fail.
+    let count = reading.serialize(CountScalars).unwrap_infallible();
+    assert_eq!(count, 5);
+
+    assert_eq!(().serialize(CountScalars).unwrap_infallible(), 0);
+    assert_eq!("str".serialize(CountScalars).unwrap_infallible(), 1);
+}
+
+#[test]
+#[should_panic(expected = "error in infallible serializer: boom")]
+#[allow(unreachable_code)]
+fn test_infallible_custom_panics() {
+    use serde::ser::Error;
+
+    let _: Infallible = Error::custom("boom");
+}
@@ -7,7 +7,9 @@
 )]
 
 use serde_derive::{Deserialize, Serialize};
-use serde_test::{assert_de_tokens, assert_ser_tokens, assert_tokens, Token};
+use serde_test::{
+    assert_de_tokens, assert_de_tokens_error, assert_ser_tokens, assert_tokens, Token,
+};
 use std::marker::PhantomData;
 
 // That tests that the derived Serialize implementation doesn't trigger
@@ -589,6 +591,115 @@ fn test_internally_tagged_struct() {
     );
 }
 
+#[test]
+fn test_write_type_name_struct() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(write_type_name)]
+    pub struct Struct {
+        a: u8,
+    }
+
+    assert_tokens(
+        &Struct { a: 1 },
+        &[
+            Token::Struct {
+                name: "Struct",
+                len: 2,
+            },
+            Token::Str("__type"),
+            Token::Str("Struct"),
+            Token::Str("a"),
+            Token::U8(1),
+            Token::StructEnd,
+        ],
+    );
+
+    // The type name entry is not required on input.
+    assert_de_tokens(
+        &Struct { a: 1 },
+        &[
+            Token::Struct {
+                name: "Struct",
+                len: 1,
+            },
+            Token::Str("a"),
+            Token::U8(1),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_write_type_name_struct_custom_key() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(write_type_name = "kind", rename = "Event")]
+    pub struct Struct {
+        a: u8,
+    }
+
+    assert_tokens(
+        &Struct { a: 1 },
+        &[
+            Token::Struct {
+                name: "Event",
+                len: 2,
+            },
+            Token::Str("kind"),
+            Token::Str("Event"),
+            Token::Str("a"),
+            Token::U8(1),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_as_string_struct() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(as_string(format = "{major}.{minor}.{patch}"))]
+    struct Version {
+        major: u32,
+        minor: u32,
+        patch: u32,
+    }
+
+    assert_tokens(
+        &Version {
+            major: 1,
+            minor: 2,
+            patch: 33,
+        },
+        &[Token::Str("1.2.33")],
+    );
+
+    assert_de_tokens_error::<Version>(
+        &[Token::Str("1.2")],
+        "invalid value: string \"1.2\", expected a string in the format \"{major}.{minor}.{patch}\"",
+    );
+
+    assert_de_tokens_error::<Version>(
+        &[Token::Str("1.2.x")],
+        "invalid value: string \"1.2.x\", expected a string in the format \"{major}.{minor}.{patch}\"",
+    );
+}
+
+#[test]
+fn test_as_string_struct_with_literal_suffix() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(as_string(format = "[{x}, {y}]"))]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    assert_tokens(&Point { x: -4, y: 7 }, &[Token::Str("[-4, 7]")]);
+
+    assert_de_tokens_error::<Point>(
+        &[Token::Str("[-4, 7] trailing")],
+        "invalid value: string \"[-4, 7] trailing\", expected a string in the format \"[{x}, {y}]\"",
+    );
+}
+
 #[test]
 fn test_internally_tagged_braced_struct_with_zero_fields() {
     #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -778,6 +889,56 @@ fn test_rename_all() {
     );
 }
 
+#[test]
+fn test_rename_all_variant_directional() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum E {
+        #[serde(rename_all(serialize = "camelCase", deserialize = "SCREAMING_SNAKE_CASE"))]
+        V {
+            serialize: bool,
+            serialize_seq: bool,
+        },
+    }
+
+    assert_ser_tokens(
+        &E::V {
+            serialize: true,
+            serialize_seq: true,
+        },
+        &[
+            Token::StructVariant {
+                name: "E",
+                variant: "V",
+                len: 2,
+            },
+            Token::Str("serialize"),
+            Token::Bool(true),
+            Token::Str("serializeSeq"),
+            Token::Bool(true),
+            Token::StructVariantEnd,
+        ],
+    );
+
+    assert_de_tokens(
+        &E::V {
+            serialize: true,
+            serialize_seq: true,
+        },
+        &[
+            Token::StructVariant {
+                name: "E",
+                variant: "V",
+                len: 2,
+            },
+            Token::Str("SERIALIZE"),
+            Token::Bool(true),
+            Token::Str("SERIALIZE_SEQ"),
+            Token::Bool(true),
+            Token::StructVariantEnd,
+        ],
+    );
+}
+
 #[test]
 fn test_rename_all_fields() {
     #[derive(Serialize, Deserialize, Debug, PartialEq)]
@@ -864,3 +1025,340 @@ fn test_packed_struct_can_derive_serialize() {
         t: f32,
     }
 }
+
+#[test]
+fn test_strict_fields() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(strict_fields(id, name))]
+    struct Record {
+        id: u32,
+        #[serde(rename = "label")]
+        name: String,
+    }
+
+    // The attribute only asserts the field list at compile time; the
+    // serialized form is unchanged.
+    assert_tokens(
+        &Record {
+            id: 7,
+            name: "x".to_owned(),
+        },
+        &[
+            Token::Struct {
+                name: "Record",
+                len: 2,
+            },
+            Token::Str("id"),
+            Token::U32(7),
+            Token::Str("label"),
+            Token::Str("x"),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_wide_enum_identifiers() {
+    // More than 16 variant names, so the generated identifier visitor
+    // matches on the name's length before comparing strings.
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Wide {
+        A,
+        Bb,
+        Cc,
+        Ddd,
+        Eee,
+        Ffff,
+        Gggg,
+        Hhhhh,
+        Iiiii,
+        Jjjjjj,
+        Kkkkkk,
+        Lllllll,
+        Mmmmmmm,
+        Nnnnnnnn,
+        Oooooooo,
+        Ppppppppp,
+        #[serde(alias = "qq")]
+        Qqqqqqqqq,
+    }
+
+    assert_tokens(
+        &Wide::A,
+        &[Token::UnitVariant {
+            name: "Wide",
+            variant: "A",
+        }],
+    );
+    assert_tokens(
+        &Wide::Oooooooo,
+        &[Token::UnitVariant {
+            name: "Wide",
+            variant: "Oooooooo",
+        }],
+    );
+    assert_de_tokens(
+        &Wide::Qqqqqqqqq,
+        &[Token::UnitVariant {
+            name: "Wide",
+            variant: "qq",
+        }],
+    );
+    assert_de_tokens_error::<Wide>(
+        &[Token::UnitVariant {
+            name: "Wide",
+            variant: "Zz",
+        }],
+        "unknown variant `Zz`, expected one of `A`, `Bb`, `Cc`, `Ddd`, `Eee`, `Ffff`, `Gggg`, `Hhhhh`, `Iiiii`, `Jjjjjj`, `Kkkkkk`, `Lllllll`, `Mmmmmmm`, `Nnnnnnnn`, `Oooooooo`, `Ppppppppp`, `Qqqqqqqqq`, `qq`",
+    );
+}
+
+#[test]
+fn test_cfg_per_direction() {
+    // `all()` is always true and `any()` always false, so Request generates
+    // both impls while Response generates Serialize only.
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(cfg(serialize = "all()", deserialize = "all()"))]
+    struct Request {
+        id: u32,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(cfg(serialize = "all()", deserialize = "any()"))]
+    struct Response {
+        ok: bool,
+    }
+
+    assert_tokens(
+        &Request { id: 1 },
+        &[
+            Token::Struct {
+                name: "Request",
+                len: 1,
+            },
+            Token::Str("id"),
+            Token::U32(1),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens(
+        &Response { ok: true },
+        &[
+            Token::Struct {
+                name: "Response",
+                len: 1,
+            },
+            Token::Str("ok"),
+            Token::Bool(true),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_sort_keys() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(sort_keys)]
+    struct Canonical {
+        zeta: u32,
+        alpha: u32,
+        #[serde(rename = "beta")]
+        gamma: u32,
+    }
+
+    let value = Canonical {
+        zeta: 1,
+        alpha: 2,
+        gamma: 3,
+    };
+
+    // Fields are emitted in lexicographic order of their serialized names,
+    // regardless of declaration order.
+    assert_ser_tokens(
+        &value,
+        &[
+            Token::Struct {
+                name: "Canonical",
+                len: 3,
+            },
+            Token::Str("alpha"),
+            Token::U32(2),
+            Token::Str("beta"),
+            Token::U32(3),
+            Token::Str("zeta"),
+            Token::U32(1),
+            Token::StructEnd,
+        ],
+    );
+
+    // Deserialization accepts any order as usual.
+    assert_de_tokens(
+        &value,
+        &[
+            Token::Struct {
+                name: "Canonical",
+                len: 3,
+            },
+            Token::Str("zeta"),
+            Token::U32(1),
+            Token::Str("alpha"),
+            Token::U32(2),
+            Token::Str("beta"),
+            Token::U32(3),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_also_serialize_as() {
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Config {
+        #[serde(rename = "max_connections", alias = "max_conns")]
+        #[serde(also_serialize_as = "max_conns")]
+        max_connections: u32,
+        #[serde(also_serialize_as = "verbose", skip_serializing_if = "is_false")]
+        debug: bool,
+    }
+
+    fn is_false(b: &bool) -> bool {
+        !*b
+    }
+
+    // The value is written under both the current and the legacy key, and
+    // both keys count toward the struct length.
+    assert_ser_tokens(
+        &Config {
+            max_connections: 8,
+            debug: true,
+        },
+        &[
+            Token::Struct {
+                name: "Config",
+                len: 4,
+            },
+            Token::Str("max_connections"),
+            Token::U32(8),
+            Token::Str("max_conns"),
+            Token::U32(8),
+            Token::Str("debug"),
+            Token::Bool(true),
+            Token::Str("verbose"),
+            Token::Bool(true),
+            Token::StructEnd,
+        ],
+    );
+
+    // A skipped field contributes neither key.
+    assert_ser_tokens(
+        &Config {
+            max_connections: 8,
+            debug: false,
+        },
+        &[
+            Token::Struct {
+                name: "Config",
+                len: 2,
+            },
+            Token::Str("max_connections"),
+            Token::U32(8),
+            Token::Str("max_conns"),
+            Token::U32(8),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_document_impl() {
+    // The attribute only adds rustdoc to the generated impls; the wire
+    // format must be unaffected.
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(document_impl)]
+    struct Documented {
+        #[serde(rename = "ID")]
+        id: u32,
+        #[serde(default)]
+        note: String,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(document_impl, tag = "kind")]
+    enum DocumentedEnum {
+        #[serde(rename = "a")]
+        A { id: u32 },
+    }
+
+    assert_tokens(
+        &Documented {
+            id: 1,
+            note: String::new(),
+        },
+        &[
+            Token::Struct {
+                name: "Documented",
+                len: 2,
+            },
+            Token::Str("ID"),
+            Token::U32(1),
+            Token::Str("note"),
+            Token::Str(""),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_tokens(
+        &DocumentedEnum::A { id: 1 },
+        &[
+            Token::Struct {
+                name: "DocumentedEnum",
+                len: 2,
+            },
+            Token::Str("kind"),
+            Token::Str("a"),
+            Token::Str("id"),
+            Token::U32(1),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_inline_newtypes() {
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(transparent)]
+    struct UserId(u64);
+
+    // Every field is checked at compile time to serialize as its inner
+    // value, so a wrapper that forgot `transparent` cannot sneak an extra
+    // layer into the wire format.
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(inline_newtypes)]
+    struct Record {
+        id: UserId,
+        owner: Box<UserId>,
+        name: String,
+    }
+
+    assert_tokens(
+        &Record {
+            id: UserId(7),
+            owner: Box::new(UserId(8)),
+            name: "alice".to_owned(),
+        },
+        &[
+            Token::Struct {
+                name: "Record",
+                len: 3,
+            },
+            Token::Str("id"),
+            Token::U64(7),
+            Token::Str("owner"),
+            Token::U64(8),
+            Token::Str("name"),
+            Token::Str("alice"),
+            Token::StructEnd,
+        ],
+    );
+}
@@ -0,0 +1,187 @@
+use serde::num::Number;
+use serde_derive::{Deserialize, Serialize};
+use serde_test::{
+    assert_de_tokens, assert_de_tokens_error, assert_ser_tokens, assert_tokens, Token,
+};
+
+#[test]
+fn test_number_roundtrip() {
+    assert_tokens(&Number::from(3u8), &[Token::U64(3)]);
+    assert_tokens(&Number::from(-7i32), &[Token::I64(-7)]);
+    assert_tokens(&Number::from(2.5f64), &[Token::F64(2.5)]);
+}
+
+#[test]
+fn test_number_normalization() {
+    // Equal integers compare equal regardless of the type they came from.
+    assert_eq!(Number::from(1u8), Number::from(1i64));
+    assert_eq!(Number::from(1u128), Number::from(1u16));
+    assert_eq!(Number::from(-1i8), Number::from(-1i128));
+
+    // Integers and floats are never conflated.
+    assert_ne!(Number::from(1u8), Number::from(1.0f64));
+}
+
+#[test]
+fn test_number_accessors() {
+    let small = Number::from(200u8);
+    assert_eq!(small.as_i64(), Some(200));
+    assert_eq!(small.as_u64(), Some(200));
+    assert_eq!(small.as_f64(), Some(200.0));
+
+    let negative = Number::from(-5i64);
+    assert_eq!(negative.as_i64(), Some(-5));
+    assert_eq!(negative.as_u64(), None);
+    assert_eq!(negative.as_i128(), Some(-5));
+
+    let big = Number::from(u128::MAX);
+    assert_eq!(big.as_u64(), None);
+    assert_eq!(big.as_i128(), None);
+    assert_eq!(big.as_u128(), Some(u128::MAX));
+
+    let float = Number::from(0.5f64);
+    assert!(float.is_f64());
+    assert_eq!(float.as_i64(), None);
+    assert_eq!(float.as_f64(), Some(0.5));
+}
+
+#[test]
+fn test_number_decimal() {
+    let decimal = Number::from_decimal_str("-11.5e300").unwrap();
+    assert_eq!(decimal.as_decimal_str(), Some("-11.5e300"));
+    assert_eq!(decimal.as_f64(), None);
+    assert_eq!(decimal.to_string(), "-11.5e300");
+    assert_ser_tokens(&decimal, &[Token::Str("-11.5e300")]);
+
+    assert!(Number::from_decimal_str("0").is_some());
+    assert!(Number::from_decimal_str("+3.25").is_some());
+    assert!(Number::from_decimal_str("1E6").is_some());
+
+    assert!(Number::from_decimal_str("").is_none());
+    assert!(Number::from_decimal_str("-").is_none());
+    assert!(Number::from_decimal_str("1.").is_none());
+    assert!(Number::from_decimal_str("1e").is_none());
+    assert!(Number::from_decimal_str("0x1f").is_none());
+    assert!(Number::from_decimal_str("1 ").is_none());
+}
+
+#[test]
+fn test_number_display() {
+    assert_eq!(Number::from(17u8).to_string(), "17");
+    assert_eq!(Number::from(-4i16).to_string(), "-4");
+    assert_eq!(Number::from(2.5f64).to_string(), "2.5");
+    assert_eq!(format!("{:?}", Number::from(17u8)), "Number(17)");
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Sample {
+    #[serde(with = "serde::num::saturating")]
+    level: u8,
+    #[serde(with = "serde::num::clamp")]
+    gain: i16,
+}
+
+#[test]
+fn test_saturating() {
+    assert_tokens(
+        &Sample {
+            level: 200,
+            gain: -3,
+        },
+        &[
+            Token::Struct {
+                name: "Sample",
+                len: 2,
+            },
+            Token::Str("level"),
+            Token::U8(200),
+            Token::Str("gain"),
+            Token::I16(-3),
+            Token::StructEnd,
+        ],
+    );
+
+    // Out-of-range integers saturate at the bounds of the target type.
+    assert_de_tokens(
+        &Sample {
+            level: 255,
+            gain: i16::MIN,
+        },
+        &[
+            Token::Struct {
+                name: "Sample",
+                len: 2,
+            },
+            Token::Str("level"),
+            Token::U32(300),
+            Token::Str("gain"),
+            Token::I64(-1_000_000),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens(
+        &Sample { level: 0, gain: 5 },
+        &[
+            Token::Struct {
+                name: "Sample",
+                len: 2,
+            },
+            Token::Str("level"),
+            Token::I8(-1),
+            Token::Str("gain"),
+            Token::U64(5),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_clamp_floats() {
+    // `clamp` additionally truncates floats toward zero and clamps them.
+    assert_de_tokens(
+        &Sample {
+            level: 2,
+            gain: i16::MAX,
+        },
+        &[
+            Token::Struct {
+                name: "Sample",
+                len: 2,
+            },
+            Token::Str("level"),
+            Token::U8(2),
+            Token::Str("gain"),
+            Token::F64(1e99),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens_error::<Sample>(
+        &[
+            Token::Struct {
+                name: "Sample",
+                len: 2,
+            },
+            Token::Str("level"),
+            Token::U8(2),
+            Token::Str("gain"),
+            Token::F64(f64::NAN),
+            Token::StructEnd,
+        ],
+        "invalid value: floating point `NaN`, expected a number that is not NaN",
+    );
+
+    // `saturating` does not accept floats.
+    assert_de_tokens_error::<Sample>(
+        &[
+            Token::Struct {
+                name: "Sample",
+                len: 2,
+            },
+            Token::Str("level"),
+            Token::F64(2.5),
+        ],
+        "invalid type: floating point `2.5`, expected an integer",
+    );
+}
@@ -0,0 +1,67 @@
+//! The std impls whose encoding depends on `is_human_readable`, asserted in
+//! both profiles using the prebuilt token sets from the macros module.
+
+use serde_test::{Configure, Token};
+use std::net;
+use std::time::{Duration, UNIX_EPOCH};
+
+#[allow(unused_macros)]
+#[macro_use]
+mod macros;
+
+#[test]
+fn test_duration_profiles() {
+    // Duration does not consult is_human_readable; the struct shape is the
+    // same in both profiles.
+    assert_tokens_readable_and_compact!(
+        Duration::new(4, 9),
+        duration_tokens!(4, 9),
+        duration_tokens!(4, 9),
+    );
+}
+
+#[test]
+fn test_system_time_profiles() {
+    assert_tokens_readable_and_compact!(
+        UNIX_EPOCH + Duration::new(4, 9),
+        system_time_tokens!(4, 9),
+        system_time_tokens!(4, 9),
+    );
+}
+
+#[test]
+fn test_ip_addr_profiles() {
+    assert_tokens_readable_and_compact!(
+        "1.2.3.4".parse::<net::Ipv4Addr>().unwrap(),
+        [Token::Str("1.2.3.4")],
+        octet_tuple_tokens!([1u8, 2, 3, 4]),
+    );
+    assert_tokens_readable_and_compact!(
+        "::1".parse::<net::Ipv6Addr>().unwrap(),
+        [Token::Str("::1")],
+        octet_tuple_tokens!([0u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]),
+    );
+    assert_tokens_readable_and_compact!(
+        "1.2.3.4".parse::<net::IpAddr>().unwrap(),
+        [Token::Str("1.2.3.4")],
+        ip_addr_compact_tokens!("V4", [1u8, 2, 3, 4]),
+    );
+}
+
+#[test]
+fn test_socket_addr_profiles() {
+    assert_tokens_readable_and_compact!(
+        "1.2.3.4:1234".parse::<net::SocketAddr>().unwrap(),
+        [Token::Str("1.2.3.4:1234")],
+        socket_addr_compact_tokens!("V4", [1u8, 2, 3, 4], 1234),
+    );
+    assert_tokens_readable_and_compact!(
+        "[::1]:1234".parse::<net::SocketAddr>().unwrap(),
+        [Token::Str("[::1]:1234")],
+        socket_addr_compact_tokens!(
+            "V6",
+            [0u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+            1234
+        ),
+    );
+}
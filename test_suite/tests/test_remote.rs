@@ -1,6 +1,7 @@
-#![allow(clippy::redundant_field_names)]
+#![allow(dead_code, clippy::owned_cow, clippy::redundant_field_names)]
 
 use serde_derive::{Deserialize, Serialize};
+use std::borrow::Cow;
 
 mod remote {
     pub struct Unit;
@@ -94,6 +95,25 @@ mod remote {
     pub enum EnumGeneric<T> {
         Variant(T),
     }
+
+    pub struct StringPriv {
+        a: String,
+        b: String,
+    }
+
+    impl StringPriv {
+        pub fn new(a: String, b: String) -> Self {
+            StringPriv { a, b }
+        }
+
+        pub fn borrowed(&self) -> &String {
+            &self.a
+        }
+
+        pub fn cowed(&self) -> super::Cow<'_, String> {
+            super::Cow::Borrowed(&self.b)
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -134,6 +154,9 @@ struct Test {
 
     #[serde(with = "ErrorKindDef")]
     io_error_kind: ErrorKind,
+
+    #[serde(with = "StringPrivDef")]
+    string_priv: remote::StringPriv,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -192,6 +215,18 @@ struct StructPubDef {
     b: remote::Unit,
 }
 
+// Getters may avoid cloning by returning a reference to the field or a Cow
+// of it instead of an owned value.
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "remote::StringPriv")]
+struct StringPrivDef {
+    #[serde(getter = "remote::StringPriv::borrowed")]
+    a: String,
+
+    #[serde(getter = "remote::StringPriv::cowed")]
+    b: String,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(remote = "remote::StructGeneric")]
 struct StructGenericWithGetterDef<T> {
@@ -256,6 +291,12 @@ impl From<StructPrivDef> for remote::StructPriv {
     }
 }
 
+impl From<StringPrivDef> for remote::StringPriv {
+    fn from(def: StringPrivDef) -> Self {
+        remote::StringPriv::new(def.a, def.b)
+    }
+}
+
 impl<T> From<StructGenericWithGetterDef<T>> for remote::StructGeneric<T> {
     fn from(def: StructGenericWithGetterDef<T>) -> Self {
         remote::StructGeneric { value: def.value }
@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 #![allow(clippy::used_underscore_binding)]
 
 use serde_derive::{Deserialize, Serialize};
@@ -22,6 +22,7 @@ use std::sync::atomic::{AtomicI64, AtomicU64};
 use std::sync::{Arc, Mutex, RwLock, Weak as ArcWeak};
 use std::time::{Duration, UNIX_EPOCH};
 
+#[allow(unused_macros)]
 #[macro_use]
 mod macros;
 
@@ -515,6 +516,11 @@ fn test_range_from() {
     );
 }
 
+#[test]
+fn test_range_full() {
+    assert_ser_tokens(&(..), &[Token::UnitStruct { name: "RangeFull" }]);
+}
+
 #[test]
 fn test_range_to() {
     assert_ser_tokens(
@@ -917,3 +923,255 @@ fn test_rwlock_dst() {
         ],
     );
 }
+
+#[test]
+fn test_collect_seq_exact() {
+    struct FilteredLen {
+        data: Vec<i32>,
+    }
+
+    impl serde::Serialize for FilteredLen {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            // `filter` loses the exact size_hint, but the adapter's len() is
+            // still exact so the sequence length must come through as Some.
+            let items: Vec<_> = self.data.iter().filter(|x| **x != 2).collect();
+            serializer.collect_seq_exact(items)
+        }
+    }
+
+    assert_ser_tokens(
+        &FilteredLen {
+            data: vec![1, 2, 3],
+        },
+        &[
+            Token::Seq { len: Some(2) },
+            Token::I32(1),
+            Token::I32(3),
+            Token::SeqEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_collect_map_exact() {
+    struct MapToUnit {
+        keys: BTreeSet<i32>,
+    }
+
+    impl serde::Serialize for MapToUnit {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            serializer.collect_map_exact(self.keys.iter().map(|k| (k, ())))
+        }
+    }
+
+    let mut keys = BTreeSet::new();
+    keys.insert(10);
+    assert_ser_tokens(
+        &MapToUnit { keys },
+        &[
+            Token::Map { len: Some(1) },
+            Token::I32(10),
+            Token::Unit,
+            Token::MapEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_middleware_redact_fields() {
+    use serde::ser::middleware::{RedactFields, Wrap};
+
+    #[derive(Serialize)]
+    struct User {
+        name: &'static str,
+        password: &'static str,
+    }
+
+    struct Audited(User);
+
+    impl serde::Serialize for Audited {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            self.0
+                .serialize(Wrap::new(serializer, RedactFields::new(&["password"])))
+        }
+    }
+
+    assert_ser_tokens(
+        &Audited(User {
+            name: "alice",
+            password: "hunter2",
+        }),
+        &[
+            Token::Struct {
+                name: "User",
+                len: 2,
+            },
+            Token::Str("name"),
+            Token::Str("alice"),
+            Token::Str("password"),
+            Token::Str("<redacted>"),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_boxed_error() {
+    let error = Box::<dyn std::error::Error + Send + Sync>::from("oops");
+    assert_ser_tokens(&error, &[Token::Str("oops")]);
+}
+
+#[test]
+fn test_display_wrapper() {
+    use serde::ser::DisplayWrapper;
+
+    let error = std::io::Error::other("oops");
+    assert_ser_tokens(&DisplayWrapper(&error), &[Token::Str("oops")]);
+
+    #[derive(Serialize)]
+    struct Record<'a> {
+        message: DisplayWrapper<'a>,
+    }
+
+    assert_ser_tokens(
+        &Record {
+            message: DisplayWrapper(&"hello"),
+        },
+        &[
+            Token::Struct {
+                name: "Record",
+                len: 1,
+            },
+            Token::Str("message"),
+            Token::Str("hello"),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_boxed_bytes() {
+    #[derive(Serialize, Debug, PartialEq)]
+    struct Packet {
+        #[serde(with = "serde::helpers::boxed_bytes")]
+        payload: Box<[u8]>,
+    }
+
+    assert_ser_tokens(
+        &Packet {
+            payload: vec![1, 2, 3].into_boxed_slice(),
+        },
+        &[
+            Token::Struct {
+                name: "Packet",
+                len: 1,
+            },
+            Token::Str("payload"),
+            Token::Bytes(b"\x01\x02\x03"),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_os_string_lossy() {
+    use std::ffi::OsString;
+
+    #[derive(Serialize)]
+    struct Manifest {
+        #[serde(with = "serde::helpers::os_string_lossy")]
+        source: OsString,
+    }
+
+    // Valid Unicode serializes as a plain, platform-independent string.
+    assert_ser_tokens(
+        &Manifest {
+            source: OsString::from("src/main.rs"),
+        },
+        &[
+            Token::Struct {
+                name: "Manifest",
+                len: 1,
+            },
+            Token::Str("source"),
+            Token::Str("src/main.rs"),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_serialize_key_str() {
+    use serde::de::value::Error;
+    use serde::ser::SerializeMap;
+
+    struct KeyRecorder {
+        generic_keys: usize,
+        static_keys: usize,
+        intern: bool,
+    }
+
+    impl SerializeMap for KeyRecorder {
+        type Ok = ();
+        type Error = Error;
+
+        fn serialize_key<T>(&mut self, _key: &T) -> Result<(), Error>
+        where
+            T: ?Sized + serde::Serialize,
+        {
+            self.generic_keys += 1;
+            Ok(())
+        }
+
+        fn serialize_key_str(&mut self, key: &'static str) -> Result<(), Error> {
+            if self.intern {
+                self.static_keys += 1;
+                let _ = key;
+                Ok(())
+            } else {
+                self.serialize_key(key)
+            }
+        }
+
+        fn serialize_value<T>(&mut self, _value: &T) -> Result<(), Error>
+        where
+            T: ?Sized + serde::Serialize,
+        {
+            Ok(())
+        }
+
+        fn end(self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    // A format that does not override the method sees static keys through
+    // the ordinary serialize_key path.
+    let mut map = KeyRecorder {
+        generic_keys: 0,
+        static_keys: 0,
+        intern: false,
+    };
+    map.serialize_key_str("a").unwrap();
+    assert_eq!((map.generic_keys, map.static_keys), (1, 0));
+
+    // A format that overrides it receives the key without going through the
+    // generic Serialize machinery.
+    let mut map = KeyRecorder {
+        generic_keys: 0,
+        static_keys: 0,
+        intern: true,
+    };
+    map.serialize_key_str("a").unwrap();
+    assert_eq!((map.generic_keys, map.static_keys), (0, 1));
+    map.end().unwrap();
+}
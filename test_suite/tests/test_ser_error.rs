@@ -0,0 +1,417 @@
+// Tests that hand-written and derived Serialize impls propagate serializer
+// errors unchanged, no matter which call into the serializer fails. The
+// serializer here succeeds for a configurable number of calls and then fails
+// every call after that, so driving it with increasing budgets exercises every
+// error path of an impl one at a time.
+
+use serde::ser::{
+    self, Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
+    SerializeTuple, SerializeTupleStruct, SerializeTupleVariant, Serializer,
+};
+use serde_derive::Serialize;
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+
+#[derive(Debug, PartialEq)]
+struct Error(String);
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+// Fails every call once `remaining` hits zero.
+#[derive(Copy, Clone)]
+struct FailingSerializer<'a> {
+    remaining: &'a Cell<usize>,
+}
+
+impl<'a> FailingSerializer<'a> {
+    fn step(self) -> Result<(), Error> {
+        let remaining = self.remaining.get();
+        if remaining == 0 {
+            Err(Error("injected serializer failure".to_owned()))
+        } else {
+            self.remaining.set(remaining - 1);
+            Ok(())
+        }
+    }
+}
+
+macro_rules! step_only {
+    ($($method:ident: $ty:ty,)*) => {
+        $(
+            fn $method(self, _v: $ty) -> Result<(), Error> {
+                self.step()
+            }
+        )*
+    };
+}
+
+impl<'a> Serializer for FailingSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    step_only! {
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char,
+        serialize_str: &str,
+        serialize_bytes: &[u8],
+        serialize_unit_struct: &'static str,
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.step()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.step()?;
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.step()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<(), Error> {
+        self.step()
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.step()?;
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.step()?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self, Error> {
+        self.step()?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self, Error> {
+        self.step()?;
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self, Error> {
+        self.step()?;
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, Error> {
+        self.step()?;
+        Ok(self)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self, Error> {
+        self.step()?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, Error> {
+        self.step()?;
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, Error> {
+        self.step()?;
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        true
+    }
+}
+
+impl<'a> SerializeSeq for FailingSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.step()?;
+        value.serialize(*self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.step()
+    }
+}
+
+impl<'a> SerializeTuple for FailingSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.step()?;
+        value.serialize(*self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.step()
+    }
+}
+
+impl<'a> SerializeTupleStruct for FailingSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.step()?;
+        value.serialize(*self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.step()
+    }
+}
+
+impl<'a> SerializeTupleVariant for FailingSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.step()?;
+        value.serialize(*self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.step()
+    }
+}
+
+impl<'a> SerializeMap for FailingSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.step()?;
+        key.serialize(*self)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.step()?;
+        value.serialize(*self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.step()
+    }
+}
+
+impl<'a> SerializeStruct for FailingSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.step()?;
+        value.serialize(*self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.step()
+    }
+}
+
+impl<'a> SerializeStructVariant for FailingSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.step()?;
+        value.serialize(*self)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.step()
+    }
+}
+
+// Asserts that serialization succeeds when the serializer allows `calls`
+// successful calls, and that every smaller budget surfaces the injected error
+// unchanged.
+fn assert_fails_before<T>(value: &T, calls: usize)
+where
+    T: ?Sized + Serialize,
+{
+    for budget in 0..calls {
+        let remaining = Cell::new(budget);
+        let serializer = FailingSerializer {
+            remaining: &remaining,
+        };
+        match value.serialize(serializer) {
+            Ok(()) => panic!("expected failure with budget {}", budget),
+            Err(error) => assert_eq!(error, Error("injected serializer failure".to_owned())),
+        }
+    }
+
+    let remaining = Cell::new(calls);
+    let serializer = FailingSerializer {
+        remaining: &remaining,
+    };
+    value.serialize(serializer).unwrap();
+    assert_eq!(remaining.get(), 0);
+}
+
+#[test]
+fn test_primitives() {
+    assert_fails_before(&true, 1);
+    assert_fails_before(&'a', 1);
+    assert_fails_before("str", 1);
+    assert_fails_before(&0u64, 1);
+}
+
+#[test]
+fn test_option() {
+    assert_fails_before(&None::<u8>, 1);
+    // serialize_some + serialize_u8
+    assert_fails_before(&Some(1u8), 2);
+}
+
+#[test]
+fn test_seq() {
+    // serialize_seq + 2 * (serialize_element + serialize_u32) + end
+    assert_fails_before(&vec![1u32, 2u32], 6);
+}
+
+#[test]
+fn test_tuple() {
+    // serialize_tuple + 2 * (serialize_element + value) + end
+    assert_fails_before(&(1u8, "two"), 6);
+}
+
+#[test]
+fn test_map() {
+    let mut map = BTreeMap::new();
+    map.insert("key", 10u32);
+    // serialize_map + serialize_key + str + serialize_value + u32 + end
+    assert_fails_before(&map, 6);
+}
+
+#[test]
+fn test_range() {
+    // serialize_struct + 2 * (serialize_field + value) + end
+    assert_fails_before(&(1u32..2u32), 6);
+}
+
+#[test]
+fn test_derived_struct() {
+    #[derive(Serialize)]
+    struct Struct {
+        a: u8,
+        b: String,
+    }
+
+    let value = Struct {
+        a: 1,
+        b: "b".to_owned(),
+    };
+    // serialize_struct + 2 * (serialize_field + value) + end
+    assert_fails_before(&value, 6);
+}
+
+#[test]
+fn test_derived_enum() {
+    #[derive(Serialize)]
+    enum Enum {
+        Unit,
+        Newtype(u8),
+        Tuple(u8, u8),
+        Struct { a: u8 },
+    }
+
+    assert_fails_before(&Enum::Unit, 1);
+    // serialize_newtype_variant + serialize_u8
+    assert_fails_before(&Enum::Newtype(1), 2);
+    // serialize_tuple_variant + 2 * (serialize_field + u8) + end
+    assert_fails_before(&Enum::Tuple(1, 2), 6);
+    // serialize_struct_variant + serialize_field + u8 + end
+    assert_fails_before(&Enum::Struct { a: 1 }, 4);
+}
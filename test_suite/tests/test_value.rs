@@ -93,3 +93,31 @@ fn test_map_access_to_enum() {
         ],
     );
 }
+
+#[test]
+fn test_unit_only_enum_access() {
+    use serde::de::value::{
+        BorrowedStrDeserializer, EnumAccessDeserializer, U32Deserializer, UnitOnlyEnumAccess,
+    };
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    enum E {
+        A,
+        B(u32),
+    }
+
+    let access = UnitOnlyEnumAccess::new(BorrowedStrDeserializer::<value::Error>::new("A"));
+    let e = E::deserialize(EnumAccessDeserializer::new(access)).unwrap();
+    assert_eq!(E::A, e);
+
+    let access = UnitOnlyEnumAccess::new(U32Deserializer::<value::Error>::new(0));
+    let e = E::deserialize(EnumAccessDeserializer::new(access)).unwrap();
+    assert_eq!(E::A, e);
+
+    let access = UnitOnlyEnumAccess::new(BorrowedStrDeserializer::<value::Error>::new("B"));
+    let err = E::deserialize(EnumAccessDeserializer::new(access)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid type: unit variant, expected newtype variant",
+    );
+}
@@ -0,0 +1,193 @@
+use serde::de::event::{from_iter, Event};
+use serde::de::value::Error;
+use serde::Deserialize;
+use serde_derive::{Deserialize, Serialize};
+use serde_test::{assert_de_tokens, assert_ser_tokens, assert_ser_tokens_error, Token};
+use std::rc::{Rc, Weak};
+
+#[derive(Serialize, Deserialize)]
+struct Node {
+    value: u32,
+    #[serde(with = "serde::rc::weak_none")]
+    parent: Weak<Node>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Upgraded {
+    #[serde(with = "serde::rc::weak_upgrade")]
+    target: Weak<u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Strict {
+    #[serde(with = "serde::rc::weak_reject_dangling")]
+    target: Weak<u32>,
+}
+
+#[test]
+fn test_weak_none() {
+    let root = Rc::new(Node {
+        value: 0,
+        parent: Weak::new(),
+    });
+    let child = Node {
+        value: 1,
+        parent: Rc::downgrade(&root),
+    };
+
+    // The parent is never traversed, alive or not.
+    assert_ser_tokens(
+        &child,
+        &[
+            Token::Struct {
+                name: "Node",
+                len: 2,
+            },
+            Token::Str("value"),
+            Token::U32(1),
+            Token::Str("parent"),
+            Token::None,
+            Token::StructEnd,
+        ],
+    );
+
+    let events = vec![
+        Event::MapStart(Some(2)),
+        Event::Str("value".into()),
+        Event::U32(1),
+        Event::Str("parent".into()),
+        Event::None,
+        Event::MapEnd,
+    ];
+    let restored = Node::deserialize(&mut from_iter::<_, Error>(events)).unwrap();
+    assert_eq!(restored.value, 1);
+    assert!(restored.parent.upgrade().is_none());
+}
+
+#[test]
+fn test_weak_upgrade() {
+    let strong = Rc::new(7u32);
+    let alive = Upgraded {
+        target: Rc::downgrade(&strong),
+    };
+    assert_ser_tokens(
+        &alive,
+        &[
+            Token::Struct {
+                name: "Upgraded",
+                len: 1,
+            },
+            Token::Str("target"),
+            Token::Some,
+            Token::U32(7),
+            Token::StructEnd,
+        ],
+    );
+
+    let dangling = Upgraded {
+        target: Weak::new(),
+    };
+    assert_ser_tokens(
+        &dangling,
+        &[
+            Token::Struct {
+                name: "Upgraded",
+                len: 1,
+            },
+            Token::Str("target"),
+            Token::None,
+            Token::StructEnd,
+        ],
+    );
+
+    assert_de_tokens(
+        &dangling,
+        &[
+            Token::Struct {
+                name: "Upgraded",
+                len: 1,
+            },
+            Token::Str("target"),
+            Token::Some,
+            Token::U32(7),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn test_weak_reject_dangling() {
+    let strong = Rc::new(7u32);
+    let alive = Strict {
+        target: Rc::downgrade(&strong),
+    };
+
+    // The value is written directly, not as an Option.
+    assert_ser_tokens(
+        &alive,
+        &[
+            Token::Struct {
+                name: "Strict",
+                len: 1,
+            },
+            Token::Str("target"),
+            Token::U32(7),
+            Token::StructEnd,
+        ],
+    );
+
+    assert_ser_tokens_error(
+        &Strict {
+            target: Weak::new(),
+        },
+        &[
+            Token::Struct {
+                name: "Strict",
+                len: 1,
+            },
+            Token::Str("target"),
+        ],
+        "dangling weak pointer cannot be serialized",
+    );
+
+    assert_de_tokens(
+        &Strict {
+            target: Weak::new(),
+        },
+        &[
+            Token::Struct {
+                name: "Strict",
+                len: 1,
+            },
+            Token::Str("target"),
+            Token::U32(7),
+            Token::StructEnd,
+        ],
+    );
+}
+
+impl PartialEq for Upgraded {
+    fn eq(&self, _other: &Upgraded) -> bool {
+        // Deserialized pointers are always dangling; equality for test
+        // purposes only checks the shape.
+        true
+    }
+}
+
+impl std::fmt::Debug for Upgraded {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Upgraded").finish()
+    }
+}
+
+impl PartialEq for Strict {
+    fn eq(&self, _other: &Strict) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for Strict {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Strict").finish()
+    }
+}